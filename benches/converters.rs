@@ -0,0 +1,125 @@
+// Converter throughput on procedurally generated images, so the numbers do not depend on
+// any file on disk. Run with `cargo bench`; pass a filter such as `cargo bench dither`
+// to run one group.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use pixforge::asciiconverter::{convert_image_to_ascii, AsciiSettings, DetailLevel};
+use pixforge::crtconverter::{apply_crt, CrtSettings};
+use pixforge::ditherconverter::{apply_dither, DitherAlgorithm, DitherSettings};
+use pixforge::fisheyeconverter::{apply_fisheye, FisheyeSettings};
+
+const DITHER_ALGORITHMS: [DitherAlgorithm; 17] = [
+    DitherAlgorithm::FloydSteinberg,
+    DitherAlgorithm::Atkinson,
+    DitherAlgorithm::Ordered,
+    DitherAlgorithm::Threshold,
+    DitherAlgorithm::Scanline,
+    DitherAlgorithm::Pattern,
+    DitherAlgorithm::Random,
+    DitherAlgorithm::Halftone,
+    DitherAlgorithm::HalftoneCmyk,
+    DitherAlgorithm::Jarvis,
+    DitherAlgorithm::Stucki,
+    DitherAlgorithm::Burkes,
+    DitherAlgorithm::Sierra,
+    DitherAlgorithm::SierraTwoRow,
+    DitherAlgorithm::SierraLite,
+    DitherAlgorithm::BlueNoise,
+    DitherAlgorithm::YliluomaOrdered,
+];
+
+// Diagonal colour gradients with a little hashed noise on top, so error diffusion and
+// thresholding see both smooth ramps and fine detail
+fn fixture(width: u32, height: u32) -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+        let mut h = x.wrapping_mul(0x9e37_79b9) ^ y.wrapping_mul(0x85eb_ca6b);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x2c1b_3c6d);
+        let noise = (h >> 24) as i32 / 8 - 16;
+        let channel = |v: u32, max: u32| (v * 255 / max.max(1)) as i32 + noise;
+        Rgba([
+            channel(x, width - 1).clamp(0, 255) as u8,
+            channel(y, height - 1).clamp(0, 255) as u8,
+            channel(x + y, width + height - 2).clamp(0, 255) as u8,
+            255,
+        ])
+    }))
+}
+
+fn ascii(c: &mut Criterion) {
+    let image = fixture(1920, 1080);
+    let dimensions = (image.width(), image.height());
+    let mut group = c.benchmark_group("ascii");
+    for level in [DetailLevel::Low, DetailLevel::Medium, DetailLevel::High, DetailLevel::VeryHigh] {
+        let settings = AsciiSettings { detail_level: level.clone(), ..Default::default() };
+        group.bench_function(BenchmarkId::from_parameter(level.get_width()), |b| {
+            b.iter(|| convert_image_to_ascii(image.clone(), &settings, dimensions, None))
+        });
+    }
+    group.finish();
+}
+
+fn dither(c: &mut Criterion) {
+    let image = fixture(2048, 2048);
+    let mut group = c.benchmark_group("dither");
+    group.sample_size(10);
+    for algorithm in DITHER_ALGORITHMS {
+        let name = algorithm.name().to_string();
+        let settings = DitherSettings { algorithm, ..Default::default() };
+        group.bench_function(name, |b| b.iter(|| apply_dither(image.clone(), &settings, None)));
+    }
+    group.finish();
+}
+
+fn fisheye(c: &mut Criterion) {
+    let settings = FisheyeSettings::default();
+    let mut group = c.benchmark_group("fisheye");
+    group.sample_size(10);
+    for size in [512, 2048] {
+        let image = fixture(size, size);
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| apply_fisheye(image.clone(), &settings, None))
+        });
+    }
+    group.finish();
+}
+
+fn crt(c: &mut Criterion) {
+    let settings = CrtSettings::default();
+    let mut group = c.benchmark_group("crt");
+    group.sample_size(10);
+    for size in [512, 2048] {
+        let image = fixture(size, size);
+        group.bench_function(BenchmarkId::from_parameter(size), |b| b.iter(|| apply_crt(image.clone(), &settings, None)));
+    }
+    group.finish();
+}
+
+#[cfg(feature = "render")]
+fn render(c: &mut Criterion) {
+    use pixforge::asciiconverter::{render_ascii_to_image, AsciiCell, AsciiSettings};
+
+    let grid: Vec<Vec<AsciiCell>> = (0..120u32)
+        .map(|y| {
+            (0..250u32)
+                .map(|x| {
+                    let ch = b"@%#*+=-:. "[((x + y) % 10) as usize] as char;
+                    AsciiCell { ch, fg: [(x * 255 / 249) as u8, (y * 255 / 119) as u8, 128], bg: None, transparent: false }
+                })
+                .collect()
+        })
+        .collect();
+    let settings = AsciiSettings::default();
+    let mut group = c.benchmark_group("render_ascii");
+    group.sample_size(10);
+    group.bench_function("250x120", |b| b.iter(|| render_ascii_to_image(&grid, &settings, None, None).unwrap()));
+    group.finish();
+}
+
+#[cfg(not(feature = "render"))]
+fn render(_: &mut Criterion) {}
+
+criterion_group!(benches, ascii, dither, fisheye, crt, render);
+criterion_main!(benches);
@@ -1,15 +1,270 @@
-use image::DynamicImage;
-use eframe::egui;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use image::{DynamicImage, RgbImage};
+#[cfg(feature = "render")]
+use image::RgbaImage;
+
+use crate::imageutils::{box_average, sharpen_rgb, sobel_gradients};
+use crate::luminance::LuminanceModel;
+use crate::progress::{self, Progress};
+use crate::ArtsifyError;
+
+#[cfg(feature = "render")]
+const FONT_DATA: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+
+/// The character [`AsciiMode::HalfBlock`] fills every cell with.
+pub const HALF_BLOCK: char = '▀';
+
+/// The characters of [`AsciiMode::ShadeBlocks`], from the full block to a space.
+pub const SHADE_BLOCKS: &str = "█▓▒░ ";
+
+/// The standard 70-level ramp, from the densest character to a space.
+pub const DEFAULT_CHARSET: &str = "$@B%8&WM#*oahkbdpqwmZO0QLCJUYXzcvunxrjft/\\|()1{}[]?-_+~<>i!lI;:,\"^`'. ";
+
+/// Settings for [`convert_image_to_ascii`].
 #[derive(Clone, PartialEq)]
 pub struct AsciiSettings {
+    /// Keep per-character colours; when false every cell is a shade of gray.
     pub use_colors: bool,
+    /// Colour that grays are multiplied by when `use_colors` is off, for green or amber
+    /// phosphor looks. `None` keeps them neutral.
+    pub tint: Option<[u8; 3]>,
+    /// Map dark pixels to dense characters, for dark text on a light background.
+    pub invert: bool,
+    /// Floyd–Steinberg dither brightness across the grid before picking characters, so
+    /// smooth gradients mix neighbouring characters instead of banding.
+    pub dither: bool,
+    /// How pixels are turned into characters.
+    pub mode: AsciiMode,
+    /// Palette every cell colour is snapped to.
+    pub color_mode: ColorMode,
+    /// 0..100; above 0, strong edges are drawn with `/ \ | - _` along their direction
+    /// instead of a ramp character. Higher values pick up weaker edges.
+    pub edge_strength: f32,
+    /// 0..1; how much a cell's texture sways its character in [`AsciiMode::Ramp`]. Busy
+    /// cells move toward the dense end of the ramp and flat ones toward the sparse
+    /// end, so areas of the same brightness can still look different. At 1.0 texture
+    /// counts as much as brightness; 0.0 picks by brightness alone.
+    pub texture_weight: f32,
+    /// How a sample's colour is reduced to the brightness its character is picked by.
+    pub luminance: LuminanceModel,
+    /// Multiplier applied to each sample before picking a character.
     pub brightness: f32,
+    /// Contrast around mid-gray; 1.0 leaves the image unchanged.
     pub contrast: f32,
+    /// Power applied to each sample after brightness and contrast when picking a
+    /// character. 1.0 is linear; higher values darken the midtones.
+    pub gamma: f32,
+    /// Chroma multiplier for coloured output; 0.0 gives the same grays as `use_colors`
+    /// off.
+    pub saturation: f32,
+    /// Extra chroma for dull colours, scaled down as a colour nears full saturation.
+    pub vibrance: f32,
+    /// Levels per channel that coloured output is snapped to after saturation and
+    /// vibrance, 2 to 32, for a flat screen-print look. `None` leaves colours as they are.
+    pub posterize_levels: Option<u8>,
+    /// Filter used to shrink the image to one sample per character.
+    pub sampling: SamplingQuality,
+    /// 0..2; unsharp masking of the shrunken image, to bring back detail the
+    /// downscale softened. 0.0 leaves the samples untouched.
+    pub sharpen: f32,
+    /// Number of character columns in the output, unless `fit_height` is set.
     pub detail_level: DetailLevel,
+    /// Size the grid by `max_rows` instead of `detail_level`, for screens with a fixed
+    /// number of lines.
+    pub fit_height: bool,
+    /// Number of character rows when `fit_height` is set; the width follows from the
+    /// image and `char_aspect`.
+    pub max_rows: u32,
+    /// Point size used when the result is rendered back to an image.
     pub font_size: f32,
+    /// Point size for saved images, kept apart from `font_size` so an app can preview
+    /// small and export large. Applied by [`AsciiSettings::export_settings`].
+    pub export_font_size: f32,
+    /// Exact width and height in pixels for saved images. When set, exports pick the
+    /// largest font size that fits the grid instead of `export_font_size` and pad the
+    /// rest with the background, see [`render_ascii_to_size`].
+    pub export_size: Option<(u32, u32)>,
+    /// Width over height of one character cell. The grid gets this many rows per column
+    /// of the image's aspect, and renders and exports use cells of this shape, so match
+    /// it to the font the text will be shown in. DejaVu Sans Mono at the usual line
+    /// spacing is 0.5.
+    pub char_aspect: f32,
+    /// Characters from dense to sparse that brightness is mapped onto. Falls back to
+    /// [`DEFAULT_CHARSET`] when [`check_charset`] rejects it.
+    pub charset: String,
+    /// Reorder `charset` by the ink each character actually covers in the bundled font,
+    /// so brightness steps evenly along it (see [`calibrate_charset`]). Ignored without
+    /// the `render` feature.
+    pub calibrated_ramp: bool,
+    /// Canvas colour behind the characters in rendered images.
+    pub background_color: [u8; 3],
+    /// Pixels with less alpha than this become empty, transparent cells rather than
+    /// characters for whatever colour hides behind them. 0 ignores transparency.
+    pub alpha_threshold: u8,
+    /// Fill each cell of a rendered image with a dim shade of its colour, so sparse
+    /// characters still show their area.
+    pub fill_cells: bool,
+    /// Line of text set under the art, e.g. a credit or the source's file name. Rendered
+    /// images get one extra row for it and text exports append it after a blank line;
+    /// empty adds nothing.
+    pub caption: String,
+    /// Draw rendered characters over a darkened copy of the source image instead of a
+    /// plain background.
+    pub overlay_source: bool,
+    /// Brightness of the source image under the characters, 0.0 to 1.0.
+    pub overlay_brightness: f32,
+    /// Multiplier on the height of rendered rows; raise it if descenders are clipped.
+    pub line_spacing: f32,
+    /// Multiplier on the width of rendered cells.
+    pub char_spacing: f32,
+}
+
+/// How [`convert_image_to_ascii`] turns pixels into characters.
+#[derive(Clone, Copy, PartialEq)]
+pub enum AsciiMode {
+    /// One pixel per cell, drawn with a character from [`AsciiSettings::charset`]
+    /// picked by brightness.
+    Ramp,
+    /// Two pixels per cell, drawn as [`HALF_BLOCK`] in the upper pixel's colour over a
+    /// background of the lower one. Doubles the vertical resolution; `charset`,
+    /// `invert` and `edge_strength` are ignored.
+    HalfBlock,
+    /// One pixel per cell, drawn with one of the five [`SHADE_BLOCKS`] picked by
+    /// brightness, for a chunky mosaic. `charset` and `edge_strength` are ignored.
+    ShadeBlocks,
+}
+
+impl AsciiMode {
+    /// Human-readable label.
+    pub fn name(&self) -> &str {
+        match self {
+            AsciiMode::Ramp => "Characters",
+            AsciiMode::HalfBlock => "Half Blocks",
+            AsciiMode::ShadeBlocks => "Shade Blocks",
+        }
+    }
+}
+
+/// Which colours cells may take: those a terminal can show, for output that is pasted
+/// into scripts, or a two-colour gradient.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    /// Any 24-bit colour.
+    TrueColor,
+    /// The 6×6×6 cube and gray ramp of the xterm 256-colour palette, indices 16 to 255.
+    /// The first 16 are left out because terminal themes redefine them.
+    Xterm256,
+    /// The 16 standard ANSI colours, with xterm's default values.
+    Ansi16,
+    /// Every cell blends from `dark` to `light` by its adjusted brightness, whatever
+    /// `use_colors` and `tint` say. Renders use `dark` as the canvas.
+    Duotone { dark: [u8; 3], light: [u8; 3] },
 }
 
+impl ColorMode {
+    /// Human-readable label.
+    pub fn name(&self) -> &str {
+        match self {
+            ColorMode::TrueColor => "True Color",
+            ColorMode::Xterm256 => "256 Colors",
+            ColorMode::Ansi16 => "16 Colors",
+            ColorMode::Duotone { .. } => "Duotone",
+        }
+    }
+
+    /// Index of the palette entry nearest to `color`, or `None` for
+    /// [`ColorMode::TrueColor`] and [`ColorMode::Duotone`]. Distance is weighted for how the eye sees each channel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pixforge::asciiconverter::ColorMode;
+    ///
+    /// assert_eq!(ColorMode::Xterm256.palette_index([250, 5, 0]), Some(196));
+    /// assert_eq!(ColorMode::TrueColor.palette_index([250, 5, 0]), None);
+    /// ```
+    pub fn palette_index(&self, color: [u8; 3]) -> Option<u8> {
+        let indices = match self {
+            ColorMode::TrueColor | ColorMode::Duotone { .. } => return None,
+            ColorMode::Xterm256 => 16..=255,
+            ColorMode::Ansi16 => 0..=15,
+        };
+        indices.min_by_key(|&index| perceptual_distance(xterm_color(index), color))
+    }
+
+    /// `color` snapped to the nearest palette entry.
+    pub fn quantize(&self, color: [u8; 3]) -> [u8; 3] {
+        self.palette_index(color).map_or(color, xterm_color)
+    }
+}
+
+// xterm's defaults for the 16 ANSI colours
+const ANSI_COLORS: [[u8; 3]; 16] = [
+    [0, 0, 0], [205, 0, 0], [0, 205, 0], [205, 205, 0],
+    [0, 0, 238], [205, 0, 205], [0, 205, 205], [229, 229, 229],
+    [127, 127, 127], [255, 0, 0], [0, 255, 0], [255, 255, 0],
+    [92, 92, 255], [255, 0, 255], [0, 255, 255], [255, 255, 255],
+];
+
+/// The colour of entry `index` in the xterm 256-colour palette.
+pub fn xterm_color(index: u8) -> [u8; 3] {
+    match index {
+        0..=15 => ANSI_COLORS[index as usize],
+        16..=231 => {
+            let cube = index - 16;
+            let level = |step: u8| if step == 0 { 0 } else { 55 + 40 * step };
+            [level(cube / 36), level(cube / 6 % 6), level(cube % 6)]
+        }
+        _ => [8 + 10 * (index - 232); 3],
+    }
+}
+
+// Squared RGB distance weighted by the mean red level ("redmean"), a cheap stand-in for
+// a perceptual colour difference
+fn perceptual_distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    let red_mean = (a[0] as u32 + b[0] as u32) / 2;
+    let dr = a[0] as i32 - b[0] as i32;
+    let dg = a[1] as i32 - b[1] as i32;
+    let db = a[2] as i32 - b[2] as i32;
+    (((512 + red_mean) * (dr * dr) as u32) >> 8) + 4 * (dg * dg) as u32 + (((767 - red_mean) * (db * db) as u32) >> 8)
+}
+
+/// How [`convert_image_to_ascii`] samples the image down to the character grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SamplingQuality {
+    /// The average of the pixels under each cell, or linear filtering for images with
+    /// fewer than two pixels per cell; soft but quick and steady as the grid changes.
+    Fast,
+    /// Catmull-Rom, which keeps lines and text crisper.
+    Sharp,
+    /// Lanczos with three lobes; the sharpest and the slowest.
+    Best,
+}
+
+impl SamplingQuality {
+    /// Human-readable label.
+    pub fn name(&self) -> &str {
+        match self {
+            SamplingQuality::Fast => "Fast",
+            SamplingQuality::Sharp => "Sharp",
+            SamplingQuality::Best => "Best",
+        }
+    }
+
+    /// The resize filter this quality uses.
+    pub fn filter_type(&self) -> image::imageops::FilterType {
+        match self {
+            SamplingQuality::Fast => image::imageops::FilterType::Triangle,
+            SamplingQuality::Sharp => image::imageops::FilterType::CatmullRom,
+            SamplingQuality::Best => image::imageops::FilterType::Lanczos3,
+        }
+    }
+}
+
+/// Output width in characters.
 #[derive(Clone, PartialEq)]
 pub enum DetailLevel {
     Low,
@@ -20,6 +275,7 @@ pub enum DetailLevel {
 }
 
 impl DetailLevel {
+    /// Number of character columns for this level.
     pub fn get_width(&self) -> u32 {
         match self {
             DetailLevel::Low => 80,
@@ -30,6 +286,7 @@ impl DetailLevel {
         }
     }
     
+    /// Human-readable label including the column count.
     pub fn name(&self) -> &str {
         match self {
             DetailLevel::Low => "Low (80)",
@@ -45,122 +302,1275 @@ impl Default for AsciiSettings {
     fn default() -> Self {
         Self {
             use_colors: true,
+            tint: None,
+            invert: false,
+            dither: false,
+            mode: AsciiMode::Ramp,
+            color_mode: ColorMode::TrueColor,
+            edge_strength: 0.0,
+            texture_weight: 0.0,
+            luminance: LuminanceModel::Rec709,
             brightness: 1.2,
             contrast: 1.3,
+            gamma: 1.5,
+            saturation: 2.0,
+            vibrance: 0.2,
+            posterize_levels: None,
+            sampling: SamplingQuality::Fast,
+            sharpen: 0.0,
             detail_level: DetailLevel::Medium,
+            fit_height: false,
+            max_rows: 50,
             font_size: 12.0,
+            export_font_size: 12.0,
+            export_size: None,
+            char_aspect: 0.5,
+            charset: DEFAULT_CHARSET.to_string(),
+            calibrated_ramp: false,
+            background_color: [0, 0, 0],
+            alpha_threshold: 16,
+            fill_cells: false,
+            caption: String::new(),
+            overlay_source: false,
+            overlay_brightness: 0.3,
+            line_spacing: 1.0,
+            char_spacing: 1.0,
+        }
+    }
+}
+
+impl AsciiSettings {
+    /// Sets ramp inversion and the render background for `theme`. The gray of rendered
+    /// glyphs follows from the two, see [`render_ascii_to_image`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pixforge::asciiconverter::{AsciiSettings, AsciiTheme};
+    ///
+    /// let mut settings = AsciiSettings::default();
+    /// settings.apply_theme(AsciiTheme::LightPage);
+    /// assert_eq!(settings.theme(), Some(AsciiTheme::LightPage));
+    /// ```
+    pub fn apply_theme(&mut self, theme: AsciiTheme) {
+        let (invert, background_color) = theme.look();
+        self.invert = invert;
+        self.background_color = background_color;
+    }
+
+    /// The theme these settings match, if any.
+    pub fn theme(&self) -> Option<AsciiTheme> {
+        [AsciiTheme::DarkTerminal, AsciiTheme::LightPage]
+            .into_iter()
+            .find(|theme| theme.look() == (self.invert, self.background_color))
+    }
+
+    /// Whether renders and exports should keep the colours of the grid, which holds
+    /// colours or tinted grays, rather than graying them.
+    pub fn renders_in_color(&self) -> bool {
+        self.use_colors || self.tint.is_some() || matches!(self.color_mode, ColorMode::Duotone { .. })
+    }
+
+    /// Whether `other` differs from these settings only in how cells are coloured, so a
+    /// grid made with one can be brought up to date with [`recolor_ascii`].
+    pub fn differs_only_in_color(&self, other: &AsciiSettings) -> bool {
+        let uncolored = |settings: &AsciiSettings| AsciiSettings {
+            use_colors: false,
+            tint: None,
+            color_mode: ColorMode::TrueColor,
+            saturation: 0.0,
+            vibrance: 0.0,
+            posterize_levels: None,
+            ..settings.clone()
+        };
+        uncolored(self) == uncolored(other)
+    }
+
+    /// A copy of these settings without the fields that only change how a grid is
+    /// drawn, so that two grids made with equal copies hold the same cells.
+    pub fn grid_settings(&self) -> AsciiSettings {
+        let defaults = AsciiSettings::default();
+        AsciiSettings {
+            font_size: defaults.font_size,
+            export_font_size: defaults.export_font_size,
+            export_size: defaults.export_size,
+            background_color: defaults.background_color,
+            fill_cells: defaults.fill_cells,
+            caption: defaults.caption,
+            overlay_source: defaults.overlay_source,
+            overlay_brightness: defaults.overlay_brightness,
+            line_spacing: defaults.line_spacing,
+            char_spacing: defaults.char_spacing,
+            ..self.clone()
+        }
+    }
+
+    /// A copy of these settings that renders at `export_font_size`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pixforge::asciiconverter::AsciiSettings;
+    ///
+    /// let settings = AsciiSettings { font_size: 8.0, export_font_size: 32.0, ..Default::default() };
+    /// assert_eq!(settings.export_settings().font_size, 32.0);
+    /// ```
+    pub fn export_settings(&self) -> AsciiSettings {
+        AsciiSettings { font_size: self.export_font_size, ..self.clone() }
+    }
+
+    /// Canvas colour for renders: the dark colour in [`ColorMode::Duotone`], otherwise
+    /// `background_color`.
+    pub fn render_background(&self) -> [u8; 3] {
+        match self.color_mode {
+            ColorMode::Duotone { dark, .. } => dark,
+            _ => self.background_color,
+        }
+    }
+
+    /// Sets `brightness` and `contrast` so the 1st to 99th percentile of `image`'s
+    /// luminance, measured with `luminance`, spans the full range of characters. Both stay
+    /// within 0.1 to 2.0; when that is not enough the bright end is matched first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use image::{DynamicImage, GrayImage, Luma};
+    /// use pixforge::asciiconverter::AsciiSettings;
+    ///
+    /// // A dull image between 25% and 75% gray
+    /// let image = DynamicImage::ImageLuma8(GrayImage::from_fn(128, 1, |x, _| Luma([64 + x as u8])));
+    /// let mut settings = AsciiSettings::default();
+    /// settings.auto_brightness_contrast(&image);
+    /// assert!((settings.contrast - 2.0).abs() < 0.1 && (settings.brightness - 1.0).abs() < 0.1);
+    /// ```
+    pub fn auto_brightness_contrast(&mut self, image: &DynamicImage) {
+        // A few thousand samples pin down the percentiles as well as the full image
+        let luma = self.luminance.to_luma8(&image.thumbnail(AUTO_LEVELS_SIZE, AUTO_LEVELS_SIZE));
+        let mut histogram = [0usize; 256];
+        for pixel in luma.pixels() {
+            histogram[pixel[0] as usize] += 1;
+        }
+        let total = luma.len();
+        if total == 0 {
+            return;
+        }
+        let percentile = |fraction: f32| {
+            let target = (total as f32 * fraction) as usize;
+            let mut seen = 0;
+            let level = histogram.iter().position(|&count| {
+                seen += count;
+                seen > target
+            });
+            level.unwrap_or(255) as f32 / 255.0
+        };
+        let (low, high) = (percentile(0.01), percentile(0.99));
+        // ((v - 0.5) * contrast + 0.5) * brightness maps `low` to 0 and `high` to 1
+        let contrast = if low < 0.5 { 0.5 / (0.5 - low) } else { f32::MAX };
+        self.contrast = contrast.clamp(0.1, 2.0);
+        self.brightness = (1.0 / ((high - 0.5) * self.contrast + 0.5).max(f32::EPSILON)).clamp(0.1, 2.0);
+    }
+}
+
+/// Ready-made looks for [`AsciiSettings::apply_theme`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AsciiTheme {
+    /// Light characters on black, as in a terminal.
+    DarkTerminal,
+    /// Dark characters on white, for web pages and forums.
+    LightPage,
+}
+
+impl AsciiTheme {
+    /// Human-readable label.
+    pub fn name(&self) -> &str {
+        match self {
+            AsciiTheme::DarkTerminal => "Dark terminal",
+            AsciiTheme::LightPage => "Light page",
+        }
+    }
+
+    // Ramp inversion and background colour
+    fn look(&self) -> (bool, [u8; 3]) {
+        match self {
+            AsciiTheme::DarkTerminal => (false, [0, 0, 0]),
+            AsciiTheme::LightPage => (true, [255, 255, 255]),
         }
     }
 }
 
+/// Checks that `charset` can be used as [`AsciiSettings::charset`].
+///
+/// It must not be empty, and the characters it shares with [`DEFAULT_CHARSET`] must
+/// appear in the same dense-to-sparse order. Any other characters, including non-ASCII
+/// ones, may go anywhere.
+///
+/// # Example
+///
+/// ```
+/// use pixforge::asciiconverter::check_charset;
+///
+/// assert!(check_charset("@#*. ").is_ok());
+/// assert!(check_charset("█▓▒░ ").is_ok());
+/// assert!(check_charset(" .*#@").is_err());
+/// assert!(check_charset("").is_err());
+/// ```
+pub fn check_charset(charset: &str) -> Result<(), ArtsifyError> {
+    if charset.is_empty() {
+        return Err(ArtsifyError::InvalidSettings("the character ramp is empty".to_string()));
+    }
+    let mut previous: Option<(char, usize)> = None;
+    for ch in charset.chars() {
+        let Some(rank) = DEFAULT_CHARSET.chars().position(|c| c == ch) else {
+            continue;
+        };
+        if let Some((denser, denser_rank)) = previous {
+            if rank < denser_rank {
+                return Err(ArtsifyError::InvalidSettings(format!(
+                    "'{}' is denser than '{}' and must come before it",
+                    ch, denser
+                )));
+            }
+        }
+        previous = Some((ch, rank));
+    }
+    Ok(())
+}
+
+/// Output of [`convert_image_to_ascii`].
 pub struct ConversionResult {
+    /// Plain text, one line per row.
     pub ascii_art: String,
-    pub colored_ascii: Vec<Vec<(egui::Color32, char)>>,
+    /// The same characters row by row, with their colours.
+    pub colored_ascii: Vec<Vec<AsciiCell>>,
+    /// Number of columns in the grid.
+    pub char_width: u32,
+    /// Number of rows in the grid.
+    pub char_height: u32,
+    /// Time the conversion took.
+    pub elapsed: Duration,
+    /// The settings the grid was made with.
+    pub settings: AsciiSettings,
+    /// The downscaled image the cells were sampled from, one pixel per cell, or two
+    /// stacked per cell in [`AsciiMode::HalfBlock`]. Kept for [`recolor_ascii`].
+    pub samples: RgbImage,
+}
+
+impl ConversionResult {
+    /// True when there are no characters, as for an image with no pixels.
+    pub fn is_empty(&self) -> bool {
+        self.char_width == 0 || self.char_height == 0
+    }
+}
+
+/// One character of [`ConversionResult::colored_ascii`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AsciiCell {
+    /// The character drawn in the cell.
+    pub ch: char,
+    /// sRGB colour of the glyph.
+    pub fg: [u8; 3],
+    /// sRGB colour filled behind the glyph; `None` keeps the black background.
+    pub bg: Option<[u8; 3]>,
+    /// The source was see-through here (see [`AsciiSettings::alpha_threshold`]); the
+    /// cell holds a space and renders leave it clear.
+    pub transparent: bool,
 }
 
-// Optimized HSV conversion with lookup table approach
+// Saturation of an RGB colour as in HSV
 #[inline]
-fn rgb_to_hsv_fast(r: f32, g: f32, b: f32) -> (f32, f32) {
+fn hsv_saturation(r: f32, g: f32, b: f32) -> f32 {
     let max = r.max(g.max(b));
     let min = r.min(g.min(b));
-    let delta = max - min;
-    
-    let v = max;
-    let s = if max == 0.0 { 0.0 } else { delta / max };
-    
-    (s, v) // Only return what we need
+    if max == 0.0 { 0.0 } else { (max - min) / max }
 }
 
+// The pixel's colour around `gray`, the adjusted brightness its character is picked by,
+// with its chroma scaled by `saturation` and by up to `1 + vibrance` more where it is dull
 #[inline]
-fn enhance_color(r: f32, g: f32, b: f32) -> (u8, u8, u8) {
-    let (s, v) = rgb_to_hsv_fast(r, g, b);
-    
-    // Simplified saturation boost
-    let boost = if v > 0.2 && v < 0.9 { 1.4 } else if v >= 0.9 { 1.1 } else { 1.6 };
-    let enhanced_s = (s * boost).min(1.0);
-    let enhanced_v = if v < 0.3 { (v * 1.15).min(1.0) } else { v };
-    
-    // Fast color enhancement without full HSV conversion
-    let scale = enhanced_v / v.max(0.001);
-    let sat_scale = 1.0 + (enhanced_s - s);
-    
-    let avg = (r + g + b) / 3.0;
-    let r_final = (avg + (r - avg) * (1.0 + sat_scale)) * scale;
-    let g_final = (avg + (g - avg) * (1.0 + sat_scale)) * scale;
-    let b_final = (avg + (b - avg) * (1.0 + sat_scale)) * scale;
-    
-    (
-        (r_final * 255.0).clamp(0.0, 255.0) as u8,
-        (g_final * 255.0).clamp(0.0, 255.0) as u8,
-        (b_final * 255.0).clamp(0.0, 255.0) as u8,
-    )
+fn enhance_color(r: f32, g: f32, b: f32, luma: f32, gray: f32, saturation: f32, vibrance: f32) -> [u8; 3] {
+    let chroma_scale = saturation * (1.0 + vibrance * (1.0 - hsv_saturation(r, g, b)));
+    [r, g, b].map(|c| ((gray + (c - luma) * chroma_scale) * 255.0).clamp(0.0, 255.0) as u8)
 }
 
+/// Converts `image` to ASCII art.
+///
+/// `original_dimensions` is the size of the full-resolution source and sets the aspect
+/// ratio of the character grid, so a downscaled preview gives the same layout.
+/// Each finished row of characters is reported to `progress`. An empty image or
+/// empty `original_dimensions` gives an empty result.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::asciiconverter::{convert_image_to_ascii, AsciiSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(64, 32));
+/// let result = convert_image_to_ascii(image, &AsciiSettings::default(), (64, 32), None);
+/// assert_eq!(result.ascii_art.lines().count(), result.colored_ascii.len());
+/// ```
 pub fn convert_image_to_ascii(
     image: DynamicImage,
     settings: &AsciiSettings,
     original_dimensions: (u32, u32),
+    progress: Option<&Progress>,
 ) -> ConversionResult {
+    let start = Instant::now();
+    let progress = progress::or_none(progress);
     let (orig_width, orig_height) = original_dimensions;
-    
-    let char_width = settings.detail_level.get_width();
-    let char_height = ((char_width as f32 * orig_height as f32 / orig_width as f32) * 0.5) as u32;
-    let char_width = char_width.max(10);
-    let char_height = char_height.max(5);
+    if image.width() == 0 || image.height() == 0 || orig_width == 0 || orig_height == 0 {
+        return ConversionResult {
+            ascii_art: String::new(),
+            colored_ascii: Vec::new(),
+            char_width: 0,
+            char_height: 0,
+            elapsed: start.elapsed(),
+            settings: settings.clone(),
+            samples: RgbImage::new(0, 0),
+        };
+    }
 
-    // Use faster triangle filter for preview
-    let resized = image.resize_exact(char_width, char_height, image::imageops::FilterType::Triangle);
+    // Rows per column that keep the image's proportions on screen
+    let rows_per_column = orig_height as f32 / orig_width as f32 * settings.char_aspect.max(MIN_CHAR_ASPECT);
+    let (char_width, char_height) = if settings.fit_height {
+        let char_height = settings.max_rows.max(1);
+        (((char_height as f32 / rows_per_column).round() as u32).max(1), char_height)
+    } else {
+        let char_width = settings.detail_level.get_width();
+        (char_width.max(10), ((char_width as f32 * rows_per_column) as u32).max(5))
+    };
+    let pixels_per_cell = match settings.mode {
+        AsciiMode::Ramp | AsciiMode::ShadeBlocks => 1,
+        AsciiMode::HalfBlock => 2,
+    };
+
+    let resized = sample_cells(&image, char_width, char_height * pixels_per_cell, settings.sampling);
     let rgb_img = resized.to_rgb8();
+    let rgb_img = if settings.sharpen > 0.0 { sharpen_rgb(&rgb_img, settings.sharpen.min(2.0)) } else { rgb_img };
+    let alpha = resized.color().has_alpha().then(|| resized.to_luma_alpha8());
+    let is_clear = |x: u32, y: u32| alpha.as_ref().is_some_and(|alpha| alpha.get_pixel(x, y)[1] < settings.alpha_threshold);
+
+    let charset = match check_charset(&settings.charset) {
+        Ok(()) => settings.charset.as_str(),
+        Err(_) => DEFAULT_CHARSET,
+    };
+    #[cfg(feature = "render")]
+    let calibrated = if settings.calibrated_ramp { calibrate_charset(charset, None).ok() } else { None };
+    #[cfg(not(feature = "render"))]
+    let calibrated: Option<String> = None;
+    let ramp = match settings.mode {
+        AsciiMode::ShadeBlocks => SHADE_BLOCKS,
+        _ => calibrated.as_deref().unwrap_or(charset),
+    };
+    let chars: Vec<char> = ramp.chars().collect();
+    let chars_len = chars.len();
 
-    // Static character lookup
-    const CHARS: &[u8] = b"$@B%8&WM#*oahkbdpqwmZO0QLCJUYXzcvunxrjft/\\|()1{}[]?-_+~<>i!lI;:,\"^`'. ";
-    let chars_len = CHARS.len();
+    let curve_power = settings.gamma.max(0.01);
 
-    // Pre-calculate contrast and brightness adjustments
-    let brightness_mult = settings.brightness;
-    let contrast_mult = settings.contrast;
-    let curve_power = 1.5f32;
+    let edges = if settings.mode == AsciiMode::Ramp && settings.edge_strength > 0.0 {
+        Some(sobel_gradients(&image::imageops::grayscale(&rgb_img)))
+    } else {
+        None
+    };
+    let roughness = if settings.mode == AsciiMode::Ramp && settings.texture_weight > 0.0 {
+        Some(cell_roughness(&image, char_width, char_height, settings))
+    } else {
+        None
+    };
+    let texture_blend = settings.texture_weight.min(1.0) / 2.0;
+    // Sobel magnitudes are normalized to 0..255
+    let edge_threshold = 255.0 * (1.0 - settings.edge_strength.min(100.0) / 100.0);
+
+    let shade_at = |x: u32, y: u32| shade(rgb_img.get_pixel(x, y).0, settings);
     
     let mut ascii_result = String::with_capacity((char_width as usize + 1) * char_height as usize);
     let mut colored_result = Vec::with_capacity(char_height as usize);
+    // Dither error for this row and the next, with a padding slot at either end
+    let mut row_error = vec![0.0f32; char_width as usize + 2];
+    let mut next_error = vec![0.0f32; char_width as usize + 2];
 
-    for y in 0..char_height {
+    for y in progress.rows(char_height) {
         let mut row = Vec::with_capacity(char_width as usize);
         for x in 0..char_width {
-            let pixel = rgb_img.get_pixel(x, y);
-            let r = pixel[0] as f32 * (1.0 / 255.0);
-            let g = pixel[1] as f32 * (1.0 / 255.0);
-            let b = pixel[2] as f32 * (1.0 / 255.0);
-
-            // Fast luminance calculation
-            let brightness = 0.2126 * r + 0.7152 * g + 0.0722 * b;
-            let adjusted = ((brightness - 0.5) * contrast_mult + 0.5) * brightness_mult;
-            let clamped = adjusted.clamp(0.0, 1.0);
-            let curved = clamped.powf(curve_power);
-            
-            // Fast character lookup
-            let inverted = 1.0 - curved;
-            let char_index = (inverted * (chars_len - 1) as f32) as usize;
-            let ascii_char = CHARS[char_index.min(chars_len - 1)] as char;
-
-            ascii_result.push(ascii_char);
-            
-            if settings.use_colors {
-                let (final_r, final_g, final_b) = enhance_color(r, g, b);
-                let color = egui::Color32::from_rgb(final_r, final_g, final_b);
-                row.push((color, ascii_char));
-            } else {
-                let gray = (clamped * 255.0) as u8;
-                let color = egui::Color32::from_gray(gray);
-                row.push((color, ascii_char));
-            }
+            let clear = match settings.mode {
+                AsciiMode::Ramp | AsciiMode::ShadeBlocks => is_clear(x, y),
+                AsciiMode::HalfBlock => is_clear(x, y * 2) && is_clear(x, y * 2 + 1),
+            };
+            let cell = match settings.mode {
+                _ if clear => AsciiCell { ch: ' ', fg: [0, 0, 0], bg: None, transparent: true },
+                AsciiMode::Ramp | AsciiMode::ShadeBlocks => {
+                    let (clamped, color) = shade_at(x, y);
+                    let curved = clamped.powf(curve_power);
+
+                    // Fast character lookup
+                    let density = if settings.invert { curved } else { 1.0 - curved };
+                    // Positions run from the densest character at 0 to the sparsest at 1
+                    let density = match &roughness {
+                        Some(roughness) => density + (1.0 - roughness[(y * char_width + x) as usize] - density) * texture_blend,
+                        None => density,
+                    };
+                    let levels = (chars_len - 1) as f32;
+                    let char_index = if settings.dither && chars_len > 1 {
+                        let i = x as usize + 1;
+                        let wanted = density + row_error[i];
+                        let level = (wanted * levels).round().clamp(0.0, levels);
+                        let error = wanted - level / levels;
+                        row_error[i + 1] += error * 7.0 / 16.0;
+                        next_error[i - 1] += error * 3.0 / 16.0;
+                        next_error[i] += error * 5.0 / 16.0;
+                        next_error[i + 1] += error * 1.0 / 16.0;
+                        level as usize
+                    } else if settings.mode == AsciiMode::ShadeBlocks {
+                        // Five equal bands; the long ramp's truncation would leave the
+                        // last level to pure black alone
+                        (density * chars_len as f32) as usize
+                    } else {
+                        (density * levels) as usize
+                    };
+                    let edge = edges.as_ref().and_then(|gradients| {
+                        let (gx, gy) = gradients[(y * char_width + x) as usize];
+                        edge_char(gx, gy, edge_threshold)
+                    });
+                    let ch = edge.unwrap_or(chars[char_index.min(chars_len - 1)]);
+                    AsciiCell { ch, fg: color, bg: None, transparent: false }
+                }
+                AsciiMode::HalfBlock => {
+                    let (_, top) = shade_at(x, y * 2);
+                    let (_, bottom) = shade_at(x, y * 2 + 1);
+                    AsciiCell { ch: HALF_BLOCK, fg: top, bg: Some(bottom), transparent: false }
+                }
+            };
+            ascii_result.push(cell.ch);
+            row.push(cell);
         }
         ascii_result.push('\n');
         colored_result.push(row);
+        std::mem::swap(&mut row_error, &mut next_error);
+        next_error.fill(0.0);
     }
 
     ConversionResult {
         ascii_art: ascii_result,
+        // A cancelled conversion stops short of `char_height` rows
+        char_height: colored_result.len() as u32,
         colored_ascii: colored_result,
+        char_width,
+        elapsed: start.elapsed(),
+        settings: settings.clone(),
+        samples: rgb_img,
+    }
+}
+
+// One pixel per cell. Fast sampling averages everything each cell covers, so thin lines
+// count towards their cell however the grid falls; sources too small for that to help
+// are filtered like the other qualities.
+fn sample_cells(image: &DynamicImage, width: u32, height: u32, sampling: SamplingQuality) -> DynamicImage {
+    if sampling != SamplingQuality::Fast || image.width() < width * 2 || image.height() < height * 2 {
+        return image.resize_exact(width, height, sampling.filter_type());
+    }
+    let averaged = box_average(&image.to_rgba8(), width, height);
+    if image.color().has_alpha() {
+        DynamicImage::ImageRgba8(averaged)
+    } else {
+        DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(averaged).to_rgb8())
+    }
+}
+
+// Samples per cell side when measuring texture
+const TEXTURE_SAMPLES: u32 = 4;
+
+// Standard deviation of brightness within each cell, measured on a copy of `image`
+// with several pixels per cell and scaled so 0.25 and above counts as fully busy
+fn cell_roughness(image: &DynamicImage, char_width: u32, char_height: u32, settings: &AsciiSettings) -> Vec<f32> {
+    let n = TEXTURE_SAMPLES;
+    let detail = settings.luminance.to_luma8(&image.resize_exact(char_width * n, char_height * n, settings.sampling.filter_type()));
+    let mut roughness = Vec::with_capacity((char_width * char_height) as usize);
+    for cell_y in 0..char_height {
+        for cell_x in 0..char_width {
+            let values: Vec<f32> = (0..n * n)
+                .map(|i| detail.get_pixel(cell_x * n + i % n, cell_y * n + i / n)[0] as f32 / 255.0)
+                .collect();
+            let mean = values.iter().sum::<f32>() / values.len() as f32;
+            let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f32>() / values.len() as f32;
+            roughness.push((variance.sqrt() * 4.0).min(1.0));
+        }
+    }
+    roughness
+}
+
+/// Recomputes the colours of `colored_ascii` for `settings` from the `samples` of the
+/// [`ConversionResult`] it came from, leaving the characters as they are. Much cheaper
+/// than converting again, and gives the same grid as long as the settings it was made
+/// with [differ only in colour](AsciiSettings::differs_only_in_color).
+pub fn recolor_ascii(colored_ascii: &mut [Vec<AsciiCell>], samples: &RgbImage, settings: &AsciiSettings) {
+    let rows_per_cell = match settings.mode {
+        AsciiMode::Ramp | AsciiMode::ShadeBlocks => 1,
+        AsciiMode::HalfBlock => 2,
+    };
+    for (y, row) in colored_ascii.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            let (x, y) = (x as u32, y as u32 * rows_per_cell);
+            if cell.transparent || x >= samples.width() || y + rows_per_cell > samples.height() {
+                continue;
+            }
+            cell.fg = shade(samples.get_pixel(x, y).0, settings).1;
+            if settings.mode == AsciiMode::HalfBlock {
+                cell.bg = Some(shade(samples.get_pixel(x, y + 1).0, settings).1);
+            }
+        }
     }
-}
\ No newline at end of file
+}
+
+/// Brings `previous` up to date with `settings`: the same grid when only drawing
+/// settings changed, a [recoloured](recolor_ascii) copy when only colours changed, and
+/// otherwise the result of `convert`, which is the only step that resamples the image.
+pub fn update_conversion(
+    previous: Option<Arc<ConversionResult>>,
+    settings: &AsciiSettings,
+    convert: impl FnOnce() -> ConversionResult,
+) -> Arc<ConversionResult> {
+    let wanted = settings.grid_settings();
+    match previous {
+        Some(previous) if previous.settings.grid_settings() == wanted => previous,
+        Some(previous) if previous.settings.grid_settings().differs_only_in_color(&wanted) => {
+            let mut colored_ascii = previous.colored_ascii.clone();
+            recolor_ascii(&mut colored_ascii, &previous.samples, settings);
+            Arc::new(ConversionResult {
+                ascii_art: previous.ascii_art.clone(),
+                colored_ascii,
+                char_width: previous.char_width,
+                char_height: previous.char_height,
+                elapsed: previous.elapsed,
+                settings: settings.clone(),
+                samples: previous.samples.clone(),
+            })
+        }
+        _ => Arc::new(convert()),
+    }
+}
+
+// Adjusted brightness of a pixel and the colour its cell shows for it
+fn shade([r, g, b]: [u8; 3], settings: &AsciiSettings) -> (f32, [u8; 3]) {
+    let r = r as f32 * (1.0 / 255.0);
+    let g = g as f32 * (1.0 / 255.0);
+    let b = b as f32 * (1.0 / 255.0);
+
+    let brightness = settings.luminance.luminance(r, g, b);
+    let adjusted = ((brightness - 0.5) * settings.contrast + 0.5) * settings.brightness;
+    let clamped = adjusted.clamp(0.0, 1.0);
+
+    let color = if let ColorMode::Duotone { dark, light } = settings.color_mode {
+        [0, 1, 2].map(|i| (dark[i] as f32 + (light[i] as f32 - dark[i] as f32) * clamped).round() as u8)
+    } else if settings.use_colors {
+        let color = enhance_color(r, g, b, brightness, clamped, settings.saturation, settings.vibrance);
+        settings.posterize_levels.map_or(color, |levels| posterize(color, levels))
+    } else {
+        let gray = (clamped * 255.0) as u8;
+        settings.tint.map_or([gray; 3], |tint| tint.map(|c| (gray as u32 * c as u32 / 255) as u8))
+    };
+    (clamped, settings.color_mode.quantize(color))
+}
+
+// Snaps each channel to the nearest of `levels` evenly spaced values from 0 to 255
+fn posterize(color: [u8; 3], levels: u8) -> [u8; 3] {
+    let steps = levels.clamp(2, 32) as f32 - 1.0;
+    color.map(|c| ((c as f32 / 255.0 * steps).round() / steps * 255.0).round() as u8)
+}
+
+// Longest side of the thumbnail that auto brightness and contrast measure
+const AUTO_LEVELS_SIZE: u32 = 128;
+
+// Keeps the grid and the cells finite for zero or negative aspects
+const MIN_CHAR_ASPECT: f32 = 0.05;
+
+// Keeps rendered cells from collapsing at zero or negative spacing
+#[cfg(feature = "render")]
+const MIN_SPACING: f32 = 0.1;
+
+// Below this a fitted font no longer draws legible glyphs
+#[cfg(feature = "render")]
+const MIN_FIT_FONT_SIZE: f32 = 1.0;
+
+// Rows of cells each rayon task renders; fewer means more rows drawn twice at the seams
+#[cfg(feature = "render")]
+const RENDER_BAND_ROWS: usize = 16;
+
+/// Writes a character grid as an SVG document laid out like [`render_ascii_to_image`]
+/// with the same `settings` and `font_data`, so it can be printed at any size: cells are
+/// [`render_cell_size`] and each glyph sits where the raster draws it, at
+/// `settings.font_size`. Text is set in DejaVu Sans Mono, or the viewer's monospace font
+/// if that is not installed, with every character placed on its cell, on
+/// [`AsciiSettings::render_background`]. Colours follow the raster too: unless
+/// [`AsciiSettings::renders_in_color`] they are grays, which glyphs invert on a light
+/// background.
+///
+/// Needs the `render` feature.
+///
+/// # Example
+///
+/// ```
+/// use pixforge::asciiconverter::{to_svg, AsciiCell, AsciiSettings};
+///
+/// let cell = AsciiCell { ch: '#', fg: [255, 0, 0], bg: None, transparent: false };
+/// let settings = AsciiSettings { font_size: 10.0, ..Default::default() };
+/// let svg = to_svg(&[vec![cell; 4]], &settings, None).unwrap();
+/// assert!(svg.starts_with("<svg") && svg.contains("fill=\"#ff0000\""));
+/// ```
+#[cfg(feature = "render")]
+pub fn to_svg(colored_ascii: &[Vec<AsciiCell>], settings: &AsciiSettings, font_data: Option<&[u8]>) -> Result<String, ArtsifyError> {
+    use ab_glyph::{Font, FontRef, ScaleFont};
+
+    let font = FontRef::try_from_slice(font_data.unwrap_or(FONT_DATA)).map_err(|e| ArtsifyError::Font(e.to_string()))?;
+    let font_size = settings.font_size;
+    let use_colors = settings.renders_in_color();
+    let (cell_width, cell_height) = spaced_cell_size(&font, settings);
+    // Placed as `render_ascii_to_image` places them: the line is centred in the cell and
+    // the glyph's top sits an ascent above its baseline
+    let baseline = (cell_height - font_size) / 2.0 + font.as_scaled(font_size).ascent();
+    let glyph_x_offset = (cell_width - font_cell_size(&font, font_size, settings.char_aspect).0) / 2.0;
+    // SVG sizes text by the em, the raster by the height from descender to ascender
+    let em_size = font.units_per_em().map_or(font_size, |units| font_size * units / font.height_unscaled());
+    let columns = colored_ascii.iter().map(|row| row.len()).max().unwrap_or(0);
+    let width = (columns as f32 * cell_width).ceil();
+    let height = (colored_ascii.len() as f32 * cell_height).ceil();
+    let hex = |[r, g, b]: [u8; 3]| format!("#{:02x}{:02x}{:02x}", r, g, b);
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = width,
+        h = height
+    );
+    let _ = writeln!(out, "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>", hex(settings.render_background()));
+    let _ = writeln!(
+        out,
+        "<g font-family=\"'DejaVu Sans Mono', monospace\" font-size=\"{}\" xml:space=\"preserve\">",
+        round(em_size)
+    );
+    for (row_idx, row) in colored_ascii.iter().enumerate() {
+        let top = row_idx as f32 * cell_height;
+        for run in color_runs(row, |cell| cell.bg) {
+            if let Some(bg) = row[run.start].bg {
+                let _ = writeln!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    round(run.start as f32 * cell_width), round(top), round(run.len() as f32 * cell_width), round(cell_height), hex(shown_color(use_colors, bg))
+                );
+            }
+        }
+        let block = |cell: &AsciiCell| (cell.ch == HALF_BLOCK || shade_coverage(cell.ch).is_some()).then_some(cell.ch);
+        for run in color_runs(row, |cell| (cell.fg, block(cell))) {
+            let cells = &row[run.clone()];
+            // Half blocks are drawn like cell backgrounds, everything else like a glyph
+            let fill = hex(if cells[0].ch == HALF_BLOCK { shown_color(use_colors, cells[0].fg) } else { glyph_color(settings, cells[0].fg) });
+            if block(&cells[0]).is_some() {
+                // Drawn exactly, as in the PNG, rather than trusting the font's blocks
+                let (height, opacity) = match shade_coverage(cells[0].ch) {
+                    Some(coverage) if coverage < 1.0 => (cell_height, format!(" fill-opacity=\"{}\"", coverage)),
+                    Some(_) => (cell_height, String::new()),
+                    None => (cell_height / 2.0, String::new()),
+                };
+                let _ = writeln!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"{}/>",
+                    round(run.start as f32 * cell_width), round(top), round(cells.len() as f32 * cell_width), round(height), fill, opacity
+                );
+                continue;
+            }
+            if cells.iter().all(|cell| cell.ch == ' ') {
+                continue;
+            }
+            // One x per character keeps every glyph on its cell whatever the font's advance
+            let xs: Vec<String> = run.clone().map(|col| round(col as f32 * cell_width + glyph_x_offset).to_string()).collect();
+            let text: String = cells.iter().map(|cell| cell.ch).collect();
+            let _ = writeln!(
+                out,
+                "<text x=\"{}\" y=\"{}\" fill=\"{}\">{}</text>",
+                xs.join(" "), round(top + baseline), fill, escape_xml(&text)
+            );
+        }
+    }
+    out.push_str("</g>\n</svg>\n");
+    Ok(out)
+}
+
+// `color` as rendered: itself when `use_colors`, otherwise its gray
+#[cfg(feature = "render")]
+fn shown_color(use_colors: bool, [r, g, b]: [u8; 3]) -> [u8; 3] {
+    if use_colors {
+        [r, g, b]
+    } else {
+        let gray = ((r as u32 + g as u32 + b as u32) / 3) as u8;
+        [gray, gray, gray]
+    }
+}
+
+// Whether dark glyphs are needed for contrast on `background`
+#[cfg(feature = "render")]
+fn is_light([r, g, b]: [u8; 3]) -> bool {
+    r as u32 * 299 + g as u32 * 587 + b as u32 * 114 > 128 * 1000
+}
+
+// Colour a glyph of `color` is drawn in. Gray text is only legible with contrast to the
+// canvas, so on a light one dense glyphs go dark unless the grid is already inverted.
+#[cfg(feature = "render")]
+fn glyph_color(settings: &AsciiSettings, color: [u8; 3]) -> [u8; 3] {
+    let use_colors = settings.renders_in_color();
+    let shown = shown_color(use_colors, color);
+    if !use_colors && is_light(settings.render_background()) && !settings.invert {
+        shown.map(|c| 255 - c)
+    } else {
+        shown
+    }
+}
+
+// Share of its cell a shade block covers, or `None` for other characters
+#[cfg(feature = "render")]
+fn shade_coverage(ch: char) -> Option<f32> {
+    match ch {
+        '█' => Some(1.0),
+        '▓' => Some(0.75),
+        '▒' => Some(0.5),
+        '░' => Some(0.25),
+        _ => None,
+    }
+}
+
+// Two decimals are plenty for SVG coordinates and keep f32 noise out of the file
+#[cfg(feature = "render")]
+fn round(value: f32) -> f32 {
+    (value * 100.0).round() / 100.0
+}
+
+// Index ranges of consecutive cells with the same `key`
+#[cfg(feature = "render")]
+fn color_runs<K: PartialEq>(row: &[AsciiCell], key: impl Fn(&AsciiCell) -> K) -> Vec<std::ops::Range<usize>> {
+    let mut runs: Vec<std::ops::Range<usize>> = Vec::new();
+    for (i, cell) in row.iter().enumerate() {
+        match runs.last_mut() {
+            Some(run) if key(&row[run.start]) == key(cell) => run.end = i + 1,
+            _ => runs.push(i..i + 1),
+        }
+    }
+    runs
+}
+
+#[cfg(feature = "render")]
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Writes a character grid as text with ANSI colour escapes, for `cat` in a terminal.
+/// Colours are written as 24-bit values or as indices into the palette of
+/// `color_mode`, snapped to its nearest entry. A colour is only emitted where it
+/// changes, and every line ends with a reset.
+///
+/// # Example
+///
+/// ```
+/// use pixforge::asciiconverter::{to_ansi, AsciiCell, ColorMode};
+///
+/// let red = AsciiCell { ch: '#', fg: [255, 0, 0], bg: None, transparent: false };
+/// let blue = AsciiCell { ch: '.', fg: [0, 0, 255], bg: None, transparent: false };
+/// assert_eq!(to_ansi(&[vec![red, red, blue]], ColorMode::TrueColor), "\x1b[38;2;255;0;0m##\x1b[38;2;0;0;255m.\x1b[0m\n");
+/// assert_eq!(to_ansi(&[vec![red, blue]], ColorMode::Xterm256), "\x1b[38;5;196m#\x1b[38;5;21m.\x1b[0m\n");
+/// ```
+pub fn to_ansi(colored_ascii: &[Vec<AsciiCell>], color_mode: ColorMode) -> String {
+    let mut out = String::new();
+    for row in colored_ascii {
+        let mut current: Option<([u8; 3], Option<[u8; 3]>)> = None;
+        for cell in row {
+            if current != Some((cell.fg, cell.bg)) {
+                out.push_str("\x1b[");
+                write_ansi_color(&mut out, cell.fg, color_mode, false);
+                match cell.bg {
+                    Some(bg) => {
+                        out.push(';');
+                        write_ansi_color(&mut out, bg, color_mode, true);
+                    }
+                    // Back to the terminal's own background
+                    None if current.is_some_and(|(_, bg)| bg.is_some()) => out.push_str(";49"),
+                    None => {}
+                }
+                out.push('m');
+                current = Some((cell.fg, cell.bg));
+            }
+            out.push(cell.ch);
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+// SGR parameters selecting `color` as the foreground, or the background
+fn write_ansi_color(out: &mut String, color: [u8; 3], color_mode: ColorMode, background: bool) {
+    let _ = match (color_mode.palette_index(color), color_mode) {
+        (Some(index), ColorMode::Ansi16) => {
+            let base = match (background, index < 8) {
+                (false, true) => 30,
+                (false, false) => 90 - 8,
+                (true, true) => 40,
+                (true, false) => 100 - 8,
+            };
+            write!(out, "{}", base + index as u32)
+        }
+        (Some(index), _) => write!(out, "{};5;{}", if background { 48 } else { 38 }, index),
+        (None, _) => {
+            let [r, g, b] = color;
+            write!(out, "{};2;{};{};{}", if background { 48 } else { 38 }, r, g, b)
+        }
+    };
+}
+
+// The line character following an edge with Sobel gradient (gx, gy), if the edge is
+// stronger than `threshold`. The gradient points across the edge, so the line runs at a
+// right angle to it; y grows downwards.
+fn edge_char(gx: f32, gy: f32, threshold: f32) -> Option<char> {
+    if (gx * gx + gy * gy).sqrt() / 4.0 <= threshold {
+        return None;
+    }
+    let angle = gy.atan2(gx).to_degrees().rem_euclid(180.0);
+    Some(match angle {
+        a if !(22.5..157.5).contains(&a) => '|',
+        a if a < 67.5 => '/',
+        // Brighter below puts the line at the bottom of the cell, against the lit area
+        a if a < 112.5 => if gy > 0.0 { '_' } else { '-' },
+        _ => '\\',
+    })
+}
+
+/// Checks that `font_data` is a font [`render_ascii_to_image`] can draw with.
+///
+/// Needs the `render` feature.
+#[cfg(feature = "render")]
+pub fn check_font(font_data: &[u8]) -> Result<(), ArtsifyError> {
+    ab_glyph::FontRef::try_from_slice(font_data).map(|_| ()).map_err(|e| ArtsifyError::Font(e.to_string()))
+}
+
+/// Width and height in pixels of one character cell when [`render_ascii_to_image`]
+/// draws with `font_data` and `settings`. The width is the widest advance among the
+/// printable ASCII characters, so proportional fonts do not overlap, and the height
+/// follows from `char_aspect`; `char_spacing` and `line_spacing` then scale each.
+///
+/// Needs the `render` feature.
+///
+/// # Example
+///
+/// ```
+/// use pixforge::asciiconverter::{render_cell_size, AsciiSettings};
+///
+/// let settings = AsciiSettings { font_size: 10.0, char_aspect: 0.5, ..Default::default() };
+/// let (width, height) = render_cell_size(None, &settings).unwrap();
+/// assert_eq!(height, width * 2.0);
+/// let spaced = AsciiSettings { line_spacing: 1.5, ..settings };
+/// assert_eq!(render_cell_size(None, &spaced).unwrap(), (width, height * 1.5));
+/// ```
+#[cfg(feature = "render")]
+pub fn render_cell_size(font_data: Option<&[u8]>, settings: &AsciiSettings) -> Result<(f32, f32), ArtsifyError> {
+    let font = ab_glyph::FontRef::try_from_slice(font_data.unwrap_or(FONT_DATA)).map_err(|e| ArtsifyError::Font(e.to_string()))?;
+    Ok(spaced_cell_size(&font, settings))
+}
+
+#[cfg(feature = "render")]
+fn spaced_cell_size(font: &ab_glyph::FontRef, settings: &AsciiSettings) -> (f32, f32) {
+    let (width, height) = font_cell_size(font, settings.font_size, settings.char_aspect);
+    (width * settings.char_spacing.max(MIN_SPACING), height * settings.line_spacing.max(MIN_SPACING))
+}
+
+#[cfg(feature = "render")]
+fn font_cell_size(font: &ab_glyph::FontRef, font_size: f32, char_aspect: f32) -> (f32, f32) {
+    use ab_glyph::{Font, ScaleFont};
+
+    let scaled = font.as_scaled(font_size);
+    let width = (' '..='~').map(|c| scaled.h_advance(scaled.glyph_id(c))).fold(0.0, f32::max);
+    // Fonts without printable ASCII still get cells of a usable size
+    let width = if width > 0.0 { width } else { font_size * 0.6 };
+    (width, width / char_aspect.max(MIN_CHAR_ASPECT))
+}
+
+// Pixel scale glyphs are rasterized at to measure their ink
+#[cfg(feature = "render")]
+const CALIBRATION_SCALE: f32 = 48.0;
+
+/// Fraction of its cell each character of `charset` covers with ink when drawn in
+/// `font_data`, or the bundled DejaVu Sans Mono when it is `None`.
+///
+/// Needs the `render` feature.
+///
+/// # Example
+///
+/// ```
+/// use pixforge::asciiconverter::glyph_densities;
+///
+/// let densities = glyph_densities("@. ", None).unwrap();
+/// assert!(densities[0] > densities[1] && densities[1] > densities[2]);
+/// assert_eq!(densities[2], 0.0);
+/// ```
+#[cfg(feature = "render")]
+pub fn glyph_densities(charset: &str, font_data: Option<&[u8]>) -> Result<Vec<f32>, ArtsifyError> {
+    use ab_glyph::{Font, FontRef, ScaleFont};
+
+    let font = FontRef::try_from_slice(font_data.unwrap_or(FONT_DATA)).map_err(|e| ArtsifyError::Font(e.to_string()))?;
+    let scaled = font.as_scaled(CALIBRATION_SCALE);
+    let (cell_width, _) = font_cell_size(&font, CALIBRATION_SCALE, 1.0);
+    let cell_area = cell_width * CALIBRATION_SCALE;
+    Ok(charset
+        .chars()
+        .map(|ch| {
+            font.outline_glyph(scaled.scaled_glyph(ch)).map_or(0.0, |outline| {
+                let mut ink = 0.0;
+                outline.draw(|_, _, coverage| ink += coverage);
+                ink / cell_area
+            })
+        })
+        .collect())
+}
+
+/// Rebuilds `charset` so measured ink falls evenly from the first slot to the last:
+/// each slot gets the character whose [`glyph_densities`] value is nearest its share
+/// of the range, so characters may repeat or drop out. The result has the same length
+/// and is cached per font and charset.
+///
+/// Needs the `render` feature.
+#[cfg(feature = "render")]
+pub fn calibrate_charset(charset: &str, font_data: Option<&[u8]>) -> Result<String, ArtsifyError> {
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashMap;
+    use std::hash::{Hash, Hasher};
+    use std::sync::{LazyLock, Mutex};
+
+    static CACHE: LazyLock<Mutex<HashMap<(u64, String), String>>> = LazyLock::new(Default::default);
+
+    let mut hasher = DefaultHasher::new();
+    font_data.unwrap_or(FONT_DATA).hash(&mut hasher);
+    let key = (hasher.finish(), charset.to_string());
+    if let Some(calibrated) = CACHE.lock().unwrap_or_else(|e| e.into_inner()).get(&key) {
+        return Ok(calibrated.clone());
+    }
+
+    let chars: Vec<char> = charset.chars().collect();
+    let densities = glyph_densities(charset, font_data)?;
+    let min = densities.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = densities.iter().copied().fold(0.0, f32::max);
+    let calibrated: String = if chars.len() < 2 || max <= min {
+        charset.to_string()
+    } else {
+        let last = (chars.len() - 1) as f32;
+        (0..chars.len())
+            .map(|slot| {
+                let target = max - (max - min) * slot as f32 / last;
+                let distance = |&i: &usize| (densities[i] - target).abs();
+                let nearest = (0..chars.len()).min_by(|a, b| distance(a).total_cmp(&distance(b))).unwrap_or(slot);
+                chars[nearest]
+            })
+            .collect()
+    };
+    CACHE.lock().unwrap_or_else(|e| e.into_inner()).insert(key, calibrated.clone());
+    Ok(calibrated)
+}
+
+/// Draws a character grid from [`ConversionResult::colored_ascii`] at the font size of
+/// `settings`, in the TrueType or OpenType font in `font_data` or the bundled DejaVu
+/// Sans Mono when it is `None`. Cells are [`render_cell_size`] pixels on
+/// [`AsciiSettings::render_background`].
+/// Cell backgrounds are filled before the glyphs are drawn; with `fill_cells`, cells
+/// without one get their own colour at a quarter of its brightness. Unless
+/// [`AsciiSettings::renders_in_color`], every colour is replaced by its gray; on a light
+/// background the glyph grays are then inverted unless `invert` says the grid already
+/// puts dense characters on dark areas, so the densest characters are always the darkest.
+/// With `overlay_source`, `source` is stretched over the grid at `overlay_brightness`
+/// and the characters are drawn on top of it.
+/// A `caption` gets a row of its own under the grid, centred, in the duotone highlight
+/// or the tint when the art has one and otherwise in white or, on a light background,
+/// black.
+///
+/// Needs the `render` feature (on by default through `gui`).
+///
+/// # Example
+///
+/// ```
+/// use pixforge::asciiconverter::{render_ascii_to_image, AsciiCell, AsciiSettings};
+///
+/// let cell = AsciiCell { ch: '#', fg: [255, 0, 0], bg: None, transparent: false };
+/// let settings = AsciiSettings { font_size: 10.0, ..Default::default() };
+/// let image = render_ascii_to_image(&vec![vec![cell; 4]; 2], &settings, None, None).unwrap();
+/// assert_eq!(image.dimensions(), (21, 21));
+/// ```
+#[cfg(feature = "render")]
+pub fn render_ascii_to_image(colored_ascii: &[Vec<AsciiCell>], settings: &AsciiSettings, font_data: Option<&[u8]>, source: Option<&DynamicImage>) -> Result<RgbaImage, ArtsifyError> {
+    use ab_glyph::{FontRef, PxScale};
+    use image::GenericImageView;
+    use imageproc::drawing::draw_text_mut;
+    use rayon::prelude::*;
+
+    if colored_ascii.is_empty() {
+        return Err(ArtsifyError::InvalidSettings("no ASCII art to render".to_string()));
+    }
+    let char_height = colored_ascii.len();
+    let char_width = colored_ascii[0].len();
+    if char_width == 0 {
+        return Err(ArtsifyError::InvalidSettings("invalid ASCII art dimensions".to_string()));
+    }
+    let font_size = settings.font_size;
+    let use_colors = settings.renders_in_color();
+    let font = FontRef::try_from_slice(font_data.unwrap_or(FONT_DATA)).map_err(|e| ArtsifyError::Font(e.to_string()))?;
+    let scale = PxScale::from(font_size);
+    let (char_pixel_width, char_pixel_height) = spaced_cell_size(&font, settings);
+    // A pixel scale is the height from descender to ascender, so the line is `font_size` tall
+    let glyph_offset = (char_pixel_height - font_size) / 2.0;
+    // Extra character spacing is shared out on both sides of each glyph
+    let glyph_x_offset = (char_pixel_width - font_cell_size(&font, font_size, settings.char_aspect).0) / 2.0;
+    let img_width = (char_width as f32 * char_pixel_width).ceil() as u32;
+    let grid_height = (char_height as f32 * char_pixel_height).ceil() as u32;
+    let caption_height = if settings.caption.is_empty() { 0 } else { char_pixel_height.ceil() as u32 };
+    let img_height = grid_height + caption_height;
+    if img_width == 0 || grid_height == 0 {
+        return Err(ArtsifyError::InvalidSettings(format!("font size {} and spacing give an empty image", font_size)));
+    }
+    let [bg_r, bg_g, bg_b] = settings.render_background();
+    let mut img = RgbaImage::from_pixel(img_width, img_height, image::Rgba([bg_r, bg_g, bg_b, 255]));
+    if let Some(source) = source.filter(|_| settings.overlay_source) {
+        let factor = settings.overlay_brightness.clamp(0.0, 1.0);
+        let dim = |c: u8| (c as f32 * factor) as u8;
+        let mut dimmed = source.resize_exact(img_width, grid_height, image::imageops::FilterType::Triangle).to_rgba8();
+        for pixel in dimmed.pixels_mut() {
+            let [r, g, b, _] = pixel.0;
+            pixel.0 = [dim(r), dim(g), dim(b), 255];
+        }
+        image::imageops::replace(&mut img, &dimmed, 0, 0);
+    }
+    let light_background = is_light([bg_r, bg_g, bg_b]);
+    let to_rgba = |color: [u8; 3]| {
+        let [r, g, b] = shown_color(use_colors, color);
+        image::Rgba([r, g, b, 255])
+    };
+    let glyph_rgba = |color: [u8; 3]| {
+        let [r, g, b] = glyph_color(settings, color);
+        image::Rgba([r, g, b, 255])
+    };
+    // Draws one row of cells into `img`, whose top edge is at `top` in the full image
+    let draw_row = |img: &mut RgbaImage, row_idx: usize, top: u32| {
+        let y = (row_idx as f32 * char_pixel_height) as u32;
+        // Cell edges rounded the same way for neighbours, so filled cells tile without
+        // gaps; the last row and column run to the rounded-up edge of the grid
+        let y_end = if row_idx + 1 == char_height { grid_height } else { ((row_idx + 1) as f32 * char_pixel_height) as u32 };
+        let y_mid = ((row_idx as f32 + 0.5) * char_pixel_height) as u32;
+        let (y, y_end, y_mid) = (y.saturating_sub(top), y_end.saturating_sub(top), y_mid.saturating_sub(top));
+        for (col_idx, cell) in colored_ascii[row_idx].iter().enumerate() {
+            let x = (col_idx as f32 * char_pixel_width) as u32;
+            let x_end = if col_idx + 1 == char_width { img_width } else { ((col_idx + 1) as f32 * char_pixel_width) as u32 };
+            if cell.transparent {
+                fill_rect(img, (x, y), (x_end, y_end), image::Rgba([0, 0, 0, 0]));
+                continue;
+            }
+            if let Some(bg) = cell.bg {
+                fill_rect(img, (x, y), (x_end, y_end), to_rgba(bg));
+            } else if settings.fill_cells {
+                let [r, g, b, _] = glyph_rgba(cell.fg).0;
+                fill_rect(img, (x, y), (x_end, y_end), image::Rgba([r / 4, g / 4, b / 4, 255]));
+            }
+            if cell.ch == HALF_BLOCK {
+                // The font's block sits on its own em box, not our cell, so draw it exactly
+                fill_rect(img, (x, y), (x_end, y_mid), to_rgba(cell.fg));
+            } else if let Some(coverage) = shade_coverage(cell.ch) {
+                // Likewise the shade blocks, as their share of the glyph colour over the cell
+                blend_rect(img, (x, y), (x_end, y_end), glyph_rgba(cell.fg), coverage);
+            } else {
+                let glyph_y = (row_idx as f32 * char_pixel_height + glyph_offset) as i32 - top as i32;
+                let glyph_x = (col_idx as f32 * char_pixel_width + glyph_x_offset) as i32;
+                draw_text_mut(img, glyph_rgba(cell.fg), glyph_x, glyph_y, scale, &font, &cell.ch.to_string());
+            }
+        }
+    };
+    // Bands of rows are drawn in parallel, each on a copy of its part of the canvas. Glyphs
+    // overhang their cells, so a band also draws the rows either side of it that can reach
+    // into it, in the same order as a single pass would, and keeps only its own pixels.
+    // Rows past the grid run to the bottom of the image, where glyphs may spill too.
+    let row_top = |row: usize| if row >= char_height { img_height } else { (row as f32 * char_pixel_height) as u32 };
+    let reach = (2.0 * font_size / char_pixel_height).ceil() as usize + 1;
+    let band_rows = RENDER_BAND_ROWS.max(2 * reach);
+    let bands: Vec<(u32, RgbaImage)> = (0..char_height.div_ceil(band_rows))
+        .into_par_iter()
+        .map(|band| {
+            let (first, last) = (band * band_rows, ((band + 1) * band_rows).min(char_height));
+            let (from, to) = (first.saturating_sub(reach), (last + reach).min(char_height));
+            let top = row_top(from);
+            let mut canvas = img.view(0, top, img_width, row_top(to) - top).to_image();
+            for row_idx in from..to {
+                draw_row(&mut canvas, row_idx, top);
+            }
+            let own_top = row_top(first);
+            (own_top, canvas.view(0, own_top - top, img_width, row_top(last) - own_top).to_image())
+        })
+        .collect();
+    for (top, band) in bands {
+        image::imageops::replace(&mut img, &band, 0, top as i64);
+    }
+    if !settings.caption.is_empty() {
+        use ab_glyph::{Font, ScaleFont};
+
+        let caption_color = if let ColorMode::Duotone { light, .. } = settings.color_mode {
+            light
+        } else if let Some(tint) = settings.tint.filter(|_| !settings.use_colors) {
+            tint
+        } else if light_background {
+            [0, 0, 0]
+        } else {
+            [255, 255, 255]
+        };
+        let scaled = font.as_scaled(scale);
+        let text_width: f32 = settings.caption.chars().map(|c| scaled.h_advance(scaled.glyph_id(c))).sum();
+        // Captions wider than the art start at the left edge and are cut off on the right
+        let x = ((img_width as f32 - text_width) / 2.0).max(0.0) as i32;
+        let y = (grid_height as f32 + glyph_offset) as i32;
+        let [r, g, b] = caption_color;
+        draw_text_mut(&mut img, image::Rgba([r, g, b, 255]), x, y, scale, &font, &settings.caption);
+    }
+    Ok(img)
+}
+
+/// The largest font size at which [`render_ascii_to_image`] draws a `char_width` by
+/// `char_height` grid, with its caption if `settings` has one, within `size` pixels.
+/// Everything in `settings` but `font_size` is kept.
+///
+/// Needs the `render` feature.
+///
+/// # Example
+///
+/// ```
+/// use pixforge::asciiconverter::{fit_font_size, render_cell_size, AsciiSettings};
+///
+/// let settings = AsciiSettings::default();
+/// let font_size = fit_font_size(80, 24, (1920, 1080), None, &settings).unwrap();
+/// let (width, height) = render_cell_size(None, &AsciiSettings { font_size, ..settings }).unwrap();
+/// assert!(80.0 * width <= 1920.0 && 24.0 * height <= 1080.0);
+/// ```
+#[cfg(feature = "render")]
+pub fn fit_font_size(char_width: usize, char_height: usize, (width, height): (u32, u32), font_data: Option<&[u8]>, settings: &AsciiSettings) -> Result<f32, ArtsifyError> {
+    let font = ab_glyph::FontRef::try_from_slice(font_data.unwrap_or(FONT_DATA)).map_err(|e| ArtsifyError::Font(e.to_string()))?;
+    let rows = char_height + usize::from(!settings.caption.is_empty());
+    let rendered_size = |font_size: f32| {
+        let (cell_width, cell_height) = spaced_cell_size(&font, &AsciiSettings { font_size, ..settings.clone() });
+        let caption_height = if settings.caption.is_empty() { 0 } else { cell_height.ceil() as u32 };
+        ((char_width as f32 * cell_width).ceil() as u32, (char_height as f32 * cell_height).ceil() as u32 + caption_height)
+    };
+    // Cells grow in proportion to the font, so one measurement gives the size to within
+    // rounding, which the loop then steps off
+    let (unit_width, unit_height) = spaced_cell_size(&font, &AsciiSettings { font_size: 1.0, ..settings.clone() });
+    let mut font_size = (width as f32 / (char_width as f32 * unit_width)).min(height as f32 / (rows as f32 * unit_height));
+    while font_size >= MIN_FIT_FONT_SIZE {
+        let (fit_width, fit_height) = rendered_size(font_size);
+        if fit_width <= width && fit_height <= height {
+            return Ok(font_size);
+        }
+        font_size *= 0.999;
+    }
+    Err(ArtsifyError::InvalidSettings(format!("{}×{} characters do not fit in {}×{} pixels", char_width, char_height, width, height)))
+}
+
+/// Renders as [`render_ascii_to_image`] does, at the [`fit_font_size`] for `size`, and
+/// centres the result on a canvas of exactly `size` in the background colour. Art of
+/// another shape than `size` is letterboxed, never stretched.
+///
+/// Needs the `render` feature.
+///
+/// # Example
+///
+/// ```
+/// use pixforge::asciiconverter::{render_ascii_to_size, AsciiCell, AsciiSettings};
+///
+/// let cell = AsciiCell { ch: '#', fg: [255, 0, 0], bg: None, transparent: false };
+/// let image = render_ascii_to_size(&vec![vec![cell; 8]; 2], &AsciiSettings::default(), None, None, (640, 480)).unwrap();
+/// assert_eq!(image.dimensions(), (640, 480));
+/// ```
+#[cfg(feature = "render")]
+pub fn render_ascii_to_size(colored_ascii: &[Vec<AsciiCell>], settings: &AsciiSettings, font_data: Option<&[u8]>, source: Option<&DynamicImage>, size: (u32, u32)) -> Result<RgbaImage, ArtsifyError> {
+    let char_width = colored_ascii.first().map_or(0, |row| row.len());
+    if char_width == 0 {
+        return Err(ArtsifyError::InvalidSettings("no ASCII art to render".to_string()));
+    }
+    let font_size = fit_font_size(char_width, colored_ascii.len(), size, font_data, settings)?;
+    let art = render_ascii_to_image(colored_ascii, &AsciiSettings { font_size, ..settings.clone() }, font_data, source)?;
+    let [r, g, b] = settings.render_background();
+    let mut canvas = RgbaImage::from_pixel(size.0, size.1, image::Rgba([r, g, b, 255]));
+    let x = (size.0 - art.width()) / 2;
+    let y = (size.1 - art.height()) / 2;
+    image::imageops::replace(&mut canvas, &art, x as i64, y as i64);
+    Ok(canvas)
+}
+
+/// Converts every frame of the animated GIF in `gif` to ASCII art with `settings`,
+/// renders each as [`render_ascii_to_image`] does, and writes them to `output` as a
+/// looping GIF with the original frame delays. Frames that cover only part of the
+/// animation are drawn over the frames before them first. Each finished frame is
+/// reported to `progress`; a cancelled export returns [`ArtsifyError::Cancelled`].
+///
+/// Needs the `render` feature.
+#[cfg(feature = "render")]
+pub fn render_ascii_gif(
+    gif: impl std::io::BufRead + std::io::Seek,
+    output: impl std::io::Write,
+    settings: &AsciiSettings,
+    font_data: Option<&[u8]>,
+    progress: Option<&Progress>,
+) -> Result<(), ArtsifyError> {
+    use image::codecs::gif::{GifDecoder, GifEncoder, Repeat};
+    use image::{AnimationDecoder, Frame, ImageDecoder};
+
+    let progress = progress::or_none(progress);
+    let decoder = GifDecoder::new(gif)?;
+    let (width, height) = decoder.dimensions();
+    let frames = decoder.into_frames().collect_frames()?;
+    // Quantizing each frame to 256 colours dominates; speed 10 is fast and still smooth
+    let mut encoder = GifEncoder::new_with_speed(output, 10);
+    encoder.set_repeat(Repeat::Infinite)?;
+    let mut canvas = RgbaImage::new(width, height);
+    for (index, frame) in frames.iter().enumerate() {
+        if progress.is_cancelled() {
+            return Err(ArtsifyError::Cancelled);
+        }
+        progress.report(index, frames.len());
+        image::imageops::replace(&mut canvas, frame.buffer(), frame.left() as i64, frame.top() as i64);
+        let frame_image = DynamicImage::ImageRgba8(canvas.clone());
+        let result = convert_image_to_ascii(frame_image.clone(), settings, (width, height), None);
+        let rendered = render_ascii_to_image(&result.colored_ascii, settings, font_data, Some(&frame_image))?;
+        encoder.encode_frame(Frame::from_parts(rendered, 0, 0, frame.delay()))?;
+    }
+    progress.report(frames.len(), frames.len());
+    Ok(())
+}
+
+// Fills pixels from `start` up to, but not including, `end`
+#[cfg(feature = "render")]
+fn fill_rect(img: &mut RgbaImage, start: (u32, u32), end: (u32, u32), color: image::Rgba<u8>) {
+    for y in start.1..end.1.min(img.height()) {
+        for x in start.0..end.0.min(img.width()) {
+            img.put_pixel(x, y, color);
+        }
+    }
+}
+
+// Mixes `color` into the same pixels as `fill_rect`, by `coverage`
+#[cfg(feature = "render")]
+fn blend_rect(img: &mut RgbaImage, start: (u32, u32), end: (u32, u32), color: image::Rgba<u8>, coverage: f32) {
+    for y in start.1..end.1.min(img.height()) {
+        for x in start.0..end.0.min(img.width()) {
+            let pixel = img.get_pixel_mut(x, y);
+            for c in 0..3 {
+                pixel[c] = (pixel[c] as f32 + (color[c] as f32 - pixel[c] as f32) * coverage).round() as u8;
+            }
+        }
+    }
+}
@@ -0,0 +1,722 @@
+use std::path::Path;
+use std::sync::mpsc::{self, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+use image::{DynamicImage, RgbaImage};
+
+use pixforge::asciiconverter::{
+    AsciiCell, AsciiMode, AsciiSettings, AsciiTheme, ColorMode, ConversionResult, DetailLevel, SamplingQuality, DEFAULT_CHARSET, check_charset,
+    check_font, convert_image_to_ascii, fit_font_size, render_ascii_gif, render_ascii_to_image, render_ascii_to_size,
+    render_cell_size, to_ansi, to_svg, update_conversion,
+};
+use pixforge::luminance::LuminanceModel;
+use pixforge::progress::Progress;
+use pixforge::ArtsifyError;
+
+use crate::filters::{ExportJob, Filter, FilterJob, SaveJob};
+
+// File name of a picked font and its bytes, or why they can't be used
+type FontPick = (String, Result<Vec<u8>, ArtsifyError>);
+
+const PREVIEW_FONT_SIZE: f32 = 8.0;
+
+// How long sliders have to rest before a change that needs a full conversion runs
+const UPDATE_DELAY: Duration = Duration::from_millis(300);
+
+// The last conversion, shared with the jobs that make and reuse it
+#[derive(Default)]
+struct AsciiGrid {
+    result: Option<Arc<ConversionResult>>,
+    // Bumped with every new result, so views built from it know to rebuild
+    revision: u64,
+    error: Option<String>,
+}
+
+pub struct AsciiFilter {
+    pub settings: AsciiSettings,
+    // File name and bytes of the font picked for rendering; None is the bundled one
+    font: Option<(String, Arc<[u8]>)>,
+    font_dialog: Option<mpsc::Receiver<Option<FontPick>>>,
+    font_error: Option<String>,
+    // Detail follows the preview width while set; `detail_level` holds the fitted width
+    auto_detail: bool,
+    preview_width: f32,
+    show_text: bool,
+    grid: Arc<Mutex<AsciiGrid>>,
+    // Laid out once per grid revision; keyed on whether it was coloured
+    text_galley: Option<(u64, bool, Arc<egui::Galley>)>,
+    // When a change that needs a full conversion was made, until it runs
+    pending_since: Option<Instant>,
+}
+
+impl Default for AsciiFilter {
+    fn default() -> Self {
+        Self {
+            settings: AsciiSettings::default(),
+            font: None,
+            font_dialog: None,
+            font_error: None,
+            auto_detail: false,
+            preview_width: 0.0,
+            show_text: false,
+            grid: Arc::default(),
+            text_galley: None,
+            pending_since: None,
+        }
+    }
+}
+
+// Renders `image` as ASCII art for the preview. The grid in `grid` is redrawn as it is,
+// or recoloured when only colours changed; anything else converts again.
+fn convert_and_render(
+    grid: &Mutex<AsciiGrid>,
+    image: &DynamicImage,
+    settings: &AsciiSettings,
+    font: Option<&[u8]>,
+    progress: Option<&Progress>,
+) -> RgbaImage {
+    let previous = grid.lock().ok().and_then(|grid| grid.result.clone());
+    let result = update_conversion(previous, settings, || {
+        let _span = tracing::info_span!("convert_ascii").entered();
+        convert_image_to_ascii(image.clone(), settings, (image.width(), image.height()), progress)
+    });
+    // A superseded job leaves the grid to the one replacing it
+    if progress.is_some_and(Progress::is_cancelled) {
+        return RgbaImage::new(1, 1);
+    }
+
+    let preview_settings = AsciiSettings { font_size: PREVIEW_FONT_SIZE, ..settings.clone() };
+    let rendered = if result.is_empty() {
+        Err(ArtsifyError::InvalidSettings("the image has no pixels to convert".to_string()))
+    } else {
+        render_ascii_to_image(&result.colored_ascii, &preview_settings, font, Some(image))
+    };
+    if let Ok(mut grid) = grid.lock() {
+        grid.error = rendered.as_ref().err().map(|e| format!("Conversion failed: {}", e));
+        if !grid.result.as_ref().is_some_and(|current| Arc::ptr_eq(current, &result)) {
+            grid.result = Some(result);
+            grid.revision += 1;
+        }
+    }
+    rendered.unwrap_or_else(|_| RgbaImage::new(1, 1))
+}
+
+// The ASCII art as saved, at the exact export size when one is set
+fn render_ascii_export(colored_ascii: &[Vec<AsciiCell>], settings: &AsciiSettings, font: Option<&[u8]>, source: Option<&DynamicImage>) -> Result<RgbaImage, ArtsifyError> {
+    match settings.export_size {
+        Some(size) => render_ascii_to_size(colored_ascii, settings, font, source, size),
+        None => render_ascii_to_image(colored_ascii, settings, font, source),
+    }
+}
+
+// Asks where to save `text` and writes it there
+fn save_text(text: &str, filter_name: &str, extensions: &[&str], file_name: &str) -> Result<(), ArtsifyError> {
+    match rfd::FileDialog::new().add_filter(filter_name, extensions).set_file_name(file_name).save_file() {
+        Some(path) => std::fs::write(&path, text).map_err(ArtsifyError::from),
+        None => Err(ArtsifyError::Cancelled),
+    }
+}
+
+// One section per run of same-coloured characters, so large grids stay cheap to lay out
+fn ascii_layout_job(colored_ascii: &[Vec<AsciiCell>], in_color: bool, text_color: egui::Color32) -> egui::text::LayoutJob {
+    let mut job = egui::text::LayoutJob::default();
+    let font_id = egui::FontId::monospace(10.0);
+    for (row_idx, row) in colored_ascii.iter().enumerate() {
+        let mut run = String::new();
+        let mut run_color = None;
+        for cell in row {
+            let color = if in_color { egui::Color32::from_rgb(cell.fg[0], cell.fg[1], cell.fg[2]) } else { text_color };
+            if run_color.is_some_and(|c| c != color) {
+                job.append(&std::mem::take(&mut run), 0.0, egui::TextFormat::simple(font_id.clone(), run_color.unwrap_or(text_color)));
+            }
+            run_color = Some(color);
+            run.push(cell.ch);
+        }
+        if row_idx + 1 < colored_ascii.len() {
+            run.push('\n');
+        }
+        job.append(&run, 0.0, egui::TextFormat::simple(font_id.clone(), run_color.unwrap_or(text_color)));
+    }
+    job
+}
+
+impl AsciiFilter {
+    fn font_data(&self) -> Option<&[u8]> {
+        self.font.as_ref().map(|(_, data)| &data[..])
+    }
+
+    // The current grid, unless there is none or it has no characters
+    fn converted(&self) -> Option<Arc<ConversionResult>> {
+        self.grid.lock().ok()?.result.clone().filter(|result| !result.is_empty())
+    }
+
+    // Returns true when the new settings only recolour the current grid, which is quick
+    // enough to do at once; anything else waits for the controls to rest for UPDATE_DELAY
+    fn schedule_update(&mut self) -> bool {
+        let wanted = self.settings.grid_settings();
+        let recolors = self.grid.lock().ok()
+            .and_then(|grid| grid.result.as_ref().map(|result| result.settings.grid_settings().differs_only_in_color(&wanted)))
+            .unwrap_or(false);
+        if !recolors {
+            self.pending_since = Some(Instant::now());
+        }
+        recolors
+    }
+
+    // Sets the detail to as many preview glyphs as fit the panel; small changes are ignored
+    // so resizing by a few pixels doesn't reconvert. Returns true when the detail changed.
+    fn fit_detail_to_preview(&mut self) -> bool {
+        if self.settings.fit_height || self.preview_width <= 0.0 {
+            return false;
+        }
+        let preview_settings = AsciiSettings { font_size: PREVIEW_FONT_SIZE, ..self.settings.clone() };
+        let Ok((glyph_width, _)) = render_cell_size(self.font_data(), &preview_settings) else {
+            return false;
+        };
+        let width = ((self.preview_width / glyph_width) as u32).clamp(20, 400);
+        let current = self.settings.detail_level.get_width();
+        if width.abs_diff(current) <= (current / 20).max(2) {
+            return false;
+        }
+        self.settings.detail_level = DetailLevel::Custom(width);
+        true
+    }
+
+    fn check_font_dialog(&mut self, ui: &egui::Ui) -> bool {
+        let Some(receiver) = &self.font_dialog else { return false };
+        match receiver.try_recv() {
+            Ok(picked) => {
+                self.font_dialog = None;
+                let Some((name, result)) = picked else { return false };
+                match result {
+                    Ok(data) => {
+                        self.font = Some((name, data.into()));
+                        self.font_error = None;
+                    }
+                    Err(e) => {
+                        self.font = None;
+                        self.font_error = Some(format!("Failed to load font, using the bundled one: {}", e));
+                    }
+                }
+                true
+            }
+            Err(TryRecvError::Empty) => {
+                ui.ctx().request_repaint();
+                false
+            }
+            Err(TryRecvError::Disconnected) => {
+                self.font_dialog = None;
+                false
+            }
+        }
+    }
+
+    // Grid size, the size saved images will have and how long the conversion took
+    fn info_overlay(&self, ctx: &egui::Context, result: &ConversionResult) {
+        let (char_pixel_width, char_pixel_height) = render_cell_size(self.font_data(), &self.settings.export_settings()).unwrap_or_default();
+        let out_width = (result.char_width as f32 * char_pixel_width).ceil() as u32;
+        let caption_height = if self.settings.caption.is_empty() { 0 } else { char_pixel_height.ceil() as u32 };
+        let out_height = (result.char_height as f32 * char_pixel_height).ceil() as u32 + caption_height;
+        let (out_width, out_height) = self.settings.export_size.unwrap_or((out_width, out_height));
+        egui::Window::new("info_overlay").anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0)).title_bar(false).resizable(false).show(ctx, |ui| {
+            let auto = if self.auto_detail && !self.settings.fit_height { " (auto width)" } else { "" };
+            ui.label(egui::RichText::new(format!("ASCII: {}×{} chars{}", result.char_width, result.char_height, auto)).color(egui::Color32::WHITE).size(13.0));
+            ui.label(egui::RichText::new(format!("Output: {}×{} px", out_width, out_height)).color(egui::Color32::WHITE).size(13.0));
+            ui.label(egui::RichText::new(format!("Converted in {:.0} ms", result.elapsed.as_secs_f64() * 1000.0)).color(egui::Color32::WHITE).size(13.0));
+        });
+    }
+}
+
+impl Filter for AsciiFilter {
+    fn id(&self) -> &'static str {
+        "ascii"
+    }
+
+    fn name(&self) -> &'static str {
+        "ASCII Art"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        convert_and_render(&self.grid, image, &self.settings, self.font_data(), None)
+    }
+
+    fn clear_cache(&mut self) {
+        // A fresh cell, so a job still converting the old image can't store it here
+        self.grid = Arc::default();
+        self.text_galley = None;
+    }
+
+    fn background_job(&self) -> Option<FilterJob> {
+        let grid = self.grid.clone();
+        let settings = self.settings.clone();
+        let font = self.font.as_ref().map(|(_, data)| data.clone());
+        Some(Box::new(move |image, progress| convert_and_render(&grid, image, &settings, font.as_deref(), Some(progress))))
+    }
+
+    fn file_name(&self) -> String {
+        "ascii_art.png".to_string()
+    }
+
+    fn texture_options(&self) -> egui::TextureOptions {
+        egui::TextureOptions::NEAREST
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = AsciiSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, image: Option<&DynamicImage>) -> bool {
+        let mut changed = self.check_font_dialog(ui);
+        ui.label("Theme:");
+        let current_theme = self.settings.theme();
+        let mut theme = current_theme;
+        egui::ComboBox::from_id_salt("ascii_theme").selected_text(current_theme.as_ref().map_or("Custom", AsciiTheme::name)).show_ui(ui, |ui| {
+            for option in [AsciiTheme::DarkTerminal, AsciiTheme::LightPage] {
+                ui.selectable_value(&mut theme, Some(option), option.name());
+            }
+        });
+        if let Some(theme) = theme.filter(|_| theme != current_theme) {
+            self.settings.apply_theme(theme);
+            changed = true;
+        }
+        ui.add_space(5.0);
+        ui.label("Mode:");
+        let current_mode = self.settings.mode;
+        egui::ComboBox::from_id_salt("ascii_mode").selected_text(current_mode.name()).show_ui(ui, |ui| {
+            ui.selectable_value(&mut self.settings.mode, AsciiMode::Ramp, AsciiMode::Ramp.name());
+            ui.selectable_value(&mut self.settings.mode, AsciiMode::HalfBlock, AsciiMode::HalfBlock.name());
+            ui.selectable_value(&mut self.settings.mode, AsciiMode::ShadeBlocks, AsciiMode::ShadeBlocks.name());
+        });
+        if current_mode != self.settings.mode {
+            changed = true;
+        }
+        ui.add_space(5.0);
+        ui.horizontal(|ui| {
+            ui.label("Colors:");
+            if ui.checkbox(&mut self.settings.use_colors, "").changed() {
+                changed |= self.schedule_update();
+            }
+        });
+        if !self.settings.use_colors {
+            ui.horizontal(|ui| {
+                ui.label("Tint:");
+                let mut tinted = self.settings.tint.is_some();
+                if ui.checkbox(&mut tinted, "").changed() {
+                    self.settings.tint = tinted.then_some([51, 255, 102]);
+                    changed |= self.schedule_update();
+                }
+                if let Some([r, g, b]) = self.settings.tint {
+                    let mut tint = egui::Color32::from_rgb(r, g, b);
+                    if ui.color_edit_button_srgba(&mut tint).changed() {
+                        self.settings.tint = Some([tint.r(), tint.g(), tint.b()]);
+                        changed |= self.schedule_update();
+                    }
+                    if ui.button("Green").clicked() {
+                        self.settings.tint = Some([51, 255, 102]);
+                        changed |= self.schedule_update();
+                    }
+                    if ui.button("Amber").clicked() {
+                        self.settings.tint = Some([255, 176, 0]);
+                        changed |= self.schedule_update();
+                    }
+                }
+            });
+        }
+        ui.label("Palette:");
+        let current_color_mode = self.settings.color_mode;
+        egui::ComboBox::from_id_salt("ascii_color_mode").selected_text(current_color_mode.name()).show_ui(ui, |ui| {
+            for color_mode in [ColorMode::TrueColor, ColorMode::Xterm256, ColorMode::Ansi16] {
+                ui.selectable_value(&mut self.settings.color_mode, color_mode, color_mode.name());
+            }
+            let duotone = matches!(current_color_mode, ColorMode::Duotone { .. });
+            if ui.selectable_label(duotone, "Duotone").clicked() && !duotone {
+                self.settings.color_mode = ColorMode::Duotone { dark: [24, 20, 64], light: [255, 196, 72] };
+            }
+        });
+        if let ColorMode::Duotone { dark, light } = &mut self.settings.color_mode {
+            let mut recolored = false;
+            ui.horizontal(|ui| {
+                for (label, color) in [("Dark:", dark), ("Light:", light)] {
+                    ui.label(label);
+                    let [r, g, b] = *color;
+                    let mut picked = egui::Color32::from_rgb(r, g, b);
+                    if ui.color_edit_button_srgba(&mut picked).changed() {
+                        *color = [picked.r(), picked.g(), picked.b()];
+                        recolored = true;
+                    }
+                }
+            });
+            if recolored {
+                changed |= self.schedule_update();
+            }
+        }
+        if current_color_mode != self.settings.color_mode {
+            changed = true;
+        }
+        if self.settings.mode != AsciiMode::HalfBlock {
+            ui.horizontal(|ui| {
+                ui.label("Invert:");
+                if ui.checkbox(&mut self.settings.invert, "").on_hover_text("Dark pixels get dense characters, for light backgrounds").changed() {
+                    changed |= self.schedule_update();
+                }
+            });
+            if ui.checkbox(&mut self.settings.dither, "Dither characters").on_hover_text("Mix neighbouring characters to smooth gradients").changed() {
+                changed |= self.schedule_update();
+            }
+        }
+        if self.settings.mode == AsciiMode::Ramp && ui.checkbox(&mut self.settings.calibrated_ramp, "Calibrated ramp").on_hover_text("Order characters by the ink they actually cover").changed() {
+            changed |= self.schedule_update();
+        }
+        ui.add_space(5.0);
+        ui.label("Sampling:");
+        let current_sampling = self.settings.sampling;
+        egui::ComboBox::from_id_salt("ascii_sampling").selected_text(current_sampling.name()).show_ui(ui, |ui| {
+            for sampling in [SamplingQuality::Fast, SamplingQuality::Sharp, SamplingQuality::Best] {
+                ui.selectable_value(&mut self.settings.sampling, sampling, sampling.name());
+            }
+        }).response.on_hover_text("Sharp and Best keep line art crisper; Best is the slowest on large images");
+        if current_sampling != self.settings.sampling {
+            changed = true;
+        }
+        ui.label("Luminance:");
+        let current_luminance = self.settings.luminance;
+        egui::ComboBox::from_id_salt("ascii_luminance").selected_text(current_luminance.name()).show_ui(ui, |ui| {
+            for luminance in [LuminanceModel::Rec709, LuminanceModel::Rec601, LuminanceModel::Average, LuminanceModel::Lightness] {
+                ui.selectable_value(&mut self.settings.luminance, luminance, luminance.name());
+            }
+        }).response.on_hover_text("How colours are weighed when picking characters");
+        if current_luminance != self.settings.luminance {
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            let fit_width = ui.radio_value(&mut self.settings.fit_height, false, "Fit width");
+            let fit_height = ui.radio_value(&mut self.settings.fit_height, true, "Fit height");
+            if fit_width.changed() || fit_height.changed() {
+                changed = true;
+            }
+        });
+        let fit_height = self.settings.fit_height;
+        ui.add_enabled_ui(!fit_height, |ui| {
+            ui.label("Detail Level:");
+            let current_detail = self.settings.detail_level.clone();
+            let was_auto = self.auto_detail;
+            let selected_text = if self.auto_detail { "Auto (fit window)" } else { current_detail.name() };
+            egui::ComboBox::from_id_salt("detail_level").selected_text(selected_text).show_ui(ui, |ui| {
+                if ui.selectable_label(self.auto_detail, "Auto (fit window)").clicked() {
+                    self.auto_detail = true;
+                }
+                for level in [DetailLevel::Low, DetailLevel::Medium, DetailLevel::High, DetailLevel::VeryHigh, DetailLevel::Custom(100)] {
+                    let selected = !self.auto_detail && self.settings.detail_level == level;
+                    if ui.selectable_label(selected, level.name()).clicked() {
+                        self.settings.detail_level = level;
+                        self.auto_detail = false;
+                    }
+                }
+            });
+            if self.auto_detail {
+                if !was_auto {
+                    self.fit_detail_to_preview();
+                    changed = true;
+                }
+                ui.label(format!("{} chars", self.settings.detail_level.get_width()));
+            } else if let DetailLevel::Custom(width) = &mut self.settings.detail_level {
+                ui.add(egui::Slider::new(width, 50..=400).text("chars"));
+                if ui.button("Apply").clicked() {
+                    changed = true;
+                }
+            }
+            if !self.auto_detail && current_detail != self.settings.detail_level && !matches!(self.settings.detail_level, DetailLevel::Custom(_)) {
+                changed = true;
+            }
+        });
+        if fit_height {
+            ui.label("Rows:");
+            if ui.add(egui::Slider::new(&mut self.settings.max_rows, 10..=200).text("lines")).changed() {
+                changed |= self.schedule_update();
+            }
+        }
+        ui.add_space(5.0);
+        if self.settings.use_colors {
+            ui.label("Saturation:");
+            if ui.add(egui::Slider::new(&mut self.settings.saturation, 0.0..=3.0).step_by(0.1)).changed() {
+                changed |= self.schedule_update();
+            }
+            ui.label("Vibrance:");
+            if ui.add(egui::Slider::new(&mut self.settings.vibrance, 0.0..=1.0).step_by(0.05))
+                .on_hover_text("Boost dull colours more than vivid ones").changed() {
+                changed |= self.schedule_update();
+            }
+            ui.horizontal(|ui| {
+                let mut posterized = self.settings.posterize_levels.is_some();
+                if ui.checkbox(&mut posterized, "Posterize:").changed() {
+                    self.settings.posterize_levels = posterized.then_some(4);
+                    changed |= self.schedule_update();
+                }
+                if let Some(levels) = &mut self.settings.posterize_levels {
+                    if ui.add(egui::Slider::new(levels, 2..=32).text("levels"))
+                        .on_hover_text("Levels per channel; 4 gives 64 colours").changed() {
+                        changed |= self.schedule_update();
+                    }
+                }
+            });
+        }
+        ui.label("Sharpen:");
+        if ui.add(egui::Slider::new(&mut self.settings.sharpen, 0.0..=2.0).step_by(0.1))
+            .on_hover_text("Bring back detail lost when shrinking the image; 0 turns it off").changed() {
+            changed |= self.schedule_update();
+        }
+        ui.horizontal(|ui| {
+            ui.label("Brightness:");
+            if ui.add_enabled(image.is_some(), egui::Button::new("Auto"))
+                .on_hover_text("Set brightness and contrast to stretch the image's tones over all the characters").clicked() {
+                if let Some(image) = image {
+                    self.settings.auto_brightness_contrast(image);
+                    changed |= self.schedule_update();
+                }
+            }
+        });
+        if ui.add(egui::Slider::new(&mut self.settings.brightness, 0.1..=2.0).step_by(0.1)).changed() {
+            changed |= self.schedule_update();
+        }
+        ui.label("Contrast:");
+        if ui.add(egui::Slider::new(&mut self.settings.contrast, 0.1..=2.0).step_by(0.1)).changed() {
+            changed |= self.schedule_update();
+        }
+        ui.label("Gamma:");
+        if ui.add(egui::Slider::new(&mut self.settings.gamma, 0.3..=3.0).step_by(0.1))
+            .on_hover_text("Lower values keep more midtone detail").changed() {
+            changed |= self.schedule_update();
+        }
+        if self.settings.mode == AsciiMode::Ramp {
+            ui.label("Edges:");
+            if ui.add(egui::Slider::new(&mut self.settings.edge_strength, 0.0..=100.0).step_by(1.0))
+                .on_hover_text("Draw outlines with / \\ | - _; 0 turns them off").changed() {
+                changed |= self.schedule_update();
+            }
+            ui.label("Texture Weight:");
+            if ui.add(egui::Slider::new(&mut self.settings.texture_weight, 0.0..=1.0).step_by(0.05))
+                .on_hover_text("Give busy areas dense characters and flat areas sparse ones; 0 picks by brightness alone").changed() {
+                changed |= self.schedule_update();
+            }
+            ui.add_space(5.0);
+            ui.label("Characters (dense → sparse):");
+            ui.horizontal(|ui| {
+                if ui.text_edit_singleline(&mut self.settings.charset).changed() {
+                    changed |= self.schedule_update();
+                }
+                if ui.small_button("Reset").clicked() {
+                    self.settings.charset = DEFAULT_CHARSET.to_string();
+                    changed |= self.schedule_update();
+                }
+            });
+            if let Err(e) = check_charset(&self.settings.charset) {
+                ui.colored_label(ui.visuals().warn_fg_color, format!("{}; using the default", e));
+            }
+        }
+        ui.add_space(5.0);
+        ui.label("Export Font Size:");
+        ui.add_enabled(self.settings.export_size.is_none(), egui::Slider::new(&mut self.settings.export_font_size, 6.0..=48.0).text("pt").step_by(1.0))
+            .on_hover_text("Size of the characters in saved images; the preview keeps its own");
+        ui.horizontal(|ui| {
+            let mut exact = self.settings.export_size.is_some();
+            if ui.checkbox(&mut exact, "Exact Size").on_hover_text("Save images at exactly this size, with the largest font that fits and the rest padded").changed() {
+                self.settings.export_size = exact.then_some((1920, 1080));
+            }
+            if let Some((width, height)) = &mut self.settings.export_size {
+                ui.add(egui::DragValue::new(width).range(16..=16384).suffix(" px"));
+                ui.label("×");
+                ui.add(egui::DragValue::new(height).range(16..=16384).suffix(" px"));
+            }
+        });
+        ui.horizontal(|ui| {
+            let name = self.font.as_ref().map_or("DejaVu Sans Mono", |(name, _)| name.as_str());
+            ui.label(format!("Font: {}", name));
+            if ui.add_enabled(self.font_dialog.is_none(), egui::Button::new("Font…")).clicked() {
+                let (sender, receiver) = mpsc::channel();
+                self.font_dialog = Some(receiver);
+                thread::spawn(move || {
+                    let picked = rfd::FileDialog::new()
+                        .add_filter("Fonts", &["ttf", "otf"])
+                        .pick_file()
+                        .map(|path| {
+                            let name = path.file_stem().map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+                            let result = std::fs::read(&path).map_err(ArtsifyError::from)
+                                .and_then(|data| check_font(&data).map(|()| data));
+                            (name, result)
+                        });
+                    let _ = sender.send(picked);
+                });
+            }
+            if self.font.is_some() && ui.button("Reset").clicked() {
+                self.font = None;
+                self.font_error = None;
+                changed = true;
+            }
+        });
+        ui.label("Line Spacing:");
+        if ui.add(egui::Slider::new(&mut self.settings.line_spacing, 0.8..=2.0).step_by(0.05)).changed() {
+            changed = true;
+        }
+        ui.label("Character Spacing:");
+        if ui.add(egui::Slider::new(&mut self.settings.char_spacing, 0.8..=2.0).step_by(0.05)).changed() {
+            changed = true;
+        }
+        if ui.checkbox(&mut self.settings.fill_cells, "Fill cells").on_hover_text("Shade each character's cell with a dim version of its colour").changed() {
+            changed = true;
+        }
+        if ui.checkbox(&mut self.settings.overlay_source, "Overlay source").on_hover_text("Draw the characters over a dimmed copy of the original image").changed() {
+            changed = true;
+        }
+        if self.settings.overlay_source {
+            ui.label("Source Brightness:");
+            if ui.add(egui::Slider::new(&mut self.settings.overlay_brightness, 0.0..=1.0).step_by(0.05)).changed() {
+                changed = true;
+            }
+        }
+        ui.horizontal(|ui| {
+            ui.label("Background:");
+            let [r, g, b] = self.settings.background_color;
+            let mut background_color = egui::Color32::from_rgb(r, g, b);
+            if ui.color_edit_button_srgba(&mut background_color).changed() {
+                self.settings.background_color = [background_color.r(), background_color.g(), background_color.b()];
+                changed = true;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Caption:");
+            if ui.text_edit_singleline(&mut self.settings.caption).on_hover_text("Printed under the art in exports").changed() {
+                changed = true;
+            }
+        });
+        ui.label("Alpha Threshold:");
+        if ui.add(egui::Slider::new(&mut self.settings.alpha_threshold, 0..=255))
+            .on_hover_text("Leave areas more transparent than this empty; 0 ignores transparency").changed() {
+            changed |= self.schedule_update();
+        }
+        ui.label("Character Aspect:");
+        if ui.add(egui::Slider::new(&mut self.settings.char_aspect, 0.3..=1.0).step_by(0.05))
+            .on_hover_text("Width over height of a character in the font you'll view the text in").changed() {
+            changed |= self.schedule_update();
+        }
+
+        if let Some(error) = &self.font_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        if let Some(error) = self.grid.lock().ok().and_then(|grid| grid.error.clone()) {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        if changed {
+            // Converting now covers whatever was waiting
+            self.pending_since = None;
+        } else if let Some(since) = self.pending_since {
+            match UPDATE_DELAY.checked_sub(since.elapsed()) {
+                Some(remaining) => ui.ctx().request_repaint_after(remaining),
+                None => {
+                    self.pending_since = None;
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    // Follows the preview width when detail is automatic, switches between the rendered
+    // image and selectable text, and shows the grid and output sizes
+    fn preview_header(&mut self, ui: &mut egui::Ui) -> bool {
+        self.preview_width = ui.clip_rect().width();
+        if self.auto_detail && self.fit_detail_to_preview() {
+            self.pending_since = Some(Instant::now());
+            ui.ctx().request_repaint_after(UPDATE_DELAY);
+        }
+        let Some(result) = self.converted() else { return false };
+        self.info_overlay(ui.ctx(), &result);
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.show_text, false, "Image");
+            ui.selectable_value(&mut self.show_text, true, "Text").on_hover_text("Selectable text, for copying part of the output");
+        });
+        if self.show_text {
+            let revision = self.grid.lock().map_or(0, |grid| grid.revision);
+            let in_color = self.settings.renders_in_color();
+            if self.text_galley.as_ref().is_none_or(|(laid_out, colored, _)| (*laid_out, *colored) != (revision, in_color)) {
+                let job = ascii_layout_job(&result.colored_ascii, in_color, ui.visuals().text_color());
+                self.text_galley = Some((revision, in_color, ui.fonts(|fonts| fonts.layout_job(job))));
+            }
+            if let Some((_, _, galley)) = &self.text_galley {
+                ui.add(egui::Label::new(galley.clone()).selectable(true));
+            }
+        }
+        false
+    }
+
+    fn hides_output(&self) -> bool {
+        self.show_text || self.converted().is_none()
+    }
+
+    fn text_output(&self) -> Option<String> {
+        let mut text = self.converted()?.ascii_art.clone();
+        if !self.settings.caption.is_empty() {
+            text.push('\n');
+            text.push_str(&self.settings.caption);
+            text.push('\n');
+        }
+        Some(text)
+    }
+
+    // The note gives the font size an exact-size export is rendered at
+    fn export_job(&self, image: &DynamicImage) -> Option<(ExportJob, Option<String>)> {
+        let result = self.converted()?;
+        let settings = self.settings.export_settings();
+        let font = self.font.as_ref().map(|(_, data)| data.clone());
+        let note = settings.export_size.and_then(|size| {
+            let char_width = result.colored_ascii.first()?.len();
+            let font_size = fit_font_size(char_width, result.colored_ascii.len(), size, font.as_deref(), &settings).ok()?;
+            Some(format!("{}×{} px at {:.1} pt", size.0, size.1, font_size))
+        });
+        let source = settings.overlay_source.then(|| image.clone());
+        let job: ExportJob = Box::new(move || render_ascii_export(&result.colored_ascii, &settings, font.as_deref(), source.as_ref()));
+        Some((job, note))
+    }
+
+    fn export_menu(&mut self, ui: &mut egui::Ui, source_path: &Path) -> Option<SaveJob> {
+        let result = self.converted();
+        let mut job: Option<SaveJob> = None;
+        if ui.add_enabled(result.is_some(), egui::Button::new("✒ Export SVG")).clicked() {
+            if let Some(result) = &result {
+                let svg = to_svg(&result.colored_ascii, &self.settings.export_settings(), self.font_data());
+                job = Some(Box::new(move |_| save_text(&svg?, "SVG", &["svg"], "ascii_art.svg")));
+            }
+        }
+        if ui.add_enabled(result.is_some(), egui::Button::new("🌈 Export ANSI")).clicked() {
+            if let Some(result) = &result {
+                let ansi = to_ansi(&result.colored_ascii, self.settings.color_mode);
+                job = Some(Box::new(move |_| save_text(&ansi, "ANSI art", &["ans", "txt"], "ascii_art.ans")));
+            }
+        }
+        let is_gif = source_path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("gif"));
+        if ui.add_enabled(result.is_some() && is_gif, egui::Button::new("🎞 Export Animated GIF")).clicked() {
+            let source = source_path.to_path_buf();
+            let settings = self.settings.export_settings();
+            let font = self.font.as_ref().map(|(_, data)| data.clone());
+            job = Some(Box::new(move |progress| {
+                let _span = tracing::info_span!("export_ascii_gif").entered();
+                let Some(path) = rfd::FileDialog::new().add_filter("GIF", &["gif"]).set_file_name("ascii_art.gif").save_file() else {
+                    return Err(ArtsifyError::Cancelled);
+                };
+                let result = std::fs::File::open(&source).map_err(ArtsifyError::from).and_then(|input| {
+                    let output = std::fs::File::create(&path)?;
+                    render_ascii_gif(std::io::BufReader::new(input), std::io::BufWriter::new(output), &settings, font.as_deref(), Some(progress))
+                });
+                // Don't leave a half-written animation behind
+                if result.is_err() {
+                    let _ = std::fs::remove_file(&path);
+                }
+                result
+            }));
+        }
+        job
+    }
+}
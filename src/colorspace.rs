@@ -0,0 +1,82 @@
+//! Color space conversions on normalized 0..1 channels, hue in degrees 0..360.
+
+pub fn rgb_to_hsl(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g.max(b));
+    let min = r.min(g.min(b));
+    let delta = max - min;
+    let l = (max + min) / 2.0;
+
+    if delta <= f32::EPSILON {
+        return (0.0, 0.0, l);
+    }
+
+    let s = if l < 0.5 { delta / (max + min) } else { delta / (2.0 - max - min) };
+    (hue_from_rgb(r, g, b, max, delta), s, l)
+}
+
+pub fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (f32, f32, f32) {
+    if s <= 0.0 {
+        return (l, l, l);
+    }
+
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let m = l - c / 2.0;
+    let (r, g, b) = hue_to_rgb(h, c);
+    (r + m, g + m, b + m)
+}
+
+#[inline]
+fn hue_from_rgb(r: f32, g: f32, b: f32, max: f32, delta: f32) -> f32 {
+    let h = if max == r {
+        ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+    h * 60.0
+}
+
+// Pure-hue RGB with the given chroma, before the lightness offset is added
+#[inline]
+fn hue_to_rgb(h: f32, chroma: f32) -> (f32, f32, f32) {
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    }
+}
+
+// CIE L*a*b* under a D65 white, L in 0..100
+pub fn rgb_to_lab(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let (r, g, b) = (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b));
+    let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.950_47;
+    let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.088_83;
+    let f = |t: f32| if t > 216.0 / 24389.0 { t.cbrt() } else { (24389.0 / 27.0 * t + 16.0) / 116.0 };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}
+
+#[inline]
+pub fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[inline]
+pub fn linear_to_srgb(v: f32) -> f32 {
+    if v <= 0.003_130_8 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
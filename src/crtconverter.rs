@@ -1,13 +1,24 @@
 use image::{DynamicImage, RgbaImage, Rgba};
 
+use crate::progress::{self, Progress};
+use crate::tiling::render_bands;
+
+/// Settings for [`apply_crt`].
 #[derive(Clone, PartialEq)]
 pub struct CrtSettings {
+    /// Scanline period in pixels, at least 1.
     pub scanline_density: f32,
+    /// Multiplier compensating for the darkening of the scanlines.
     pub brightness_gain: f32,
+    /// Barrel distortion of the screen; 0 keeps it flat.
     pub curvature: f32,
+    /// Border added on each side, as a fraction of the image size.
     pub bezel_size: f32,
+    /// Darkening towards the screen corners, 0..1.
     pub vignette_strength: f32,
+    /// Alpha of the bezel and of the area outside the curved screen.
     pub bg_opacity: u8,
+    /// Colour of the bezel and of the area outside the curved screen.
     pub bg_color: [u8; 3],
 }
 
@@ -25,125 +36,178 @@ impl Default for CrtSettings {
     }
 }
 
-pub fn apply_crt(image: DynamicImage, settings: &CrtSettings) -> RgbaImage {
+/// Renders `image` as if shown on a curved CRT screen inside a bezel, reporting each
+/// screen row to `progress`.
+///
+/// The output is larger than the input by `bezel_size` on every side.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::crtconverter::{apply_crt, CrtSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let crt = apply_crt(image, &CrtSettings { bezel_size: 0.25, ..Default::default() }, None);
+/// assert_eq!(crt.dimensions(), (48, 48));
+/// ```
+pub fn apply_crt(image: DynamicImage, settings: &CrtSettings, progress: Option<&Progress>) -> RgbaImage {
+    let progress = progress::or_none(progress);
     let rgba_img = image.to_rgba8();
-    let (width, height) = rgba_img.dimensions();
-    
-    // Calculate final dimensions with bezel
-    let bezel_pixels_w = (width as f32 * settings.bezel_size) as u32;
-    let bezel_pixels_h = (height as f32 * settings.bezel_size) as u32;
-    let final_width = width + bezel_pixels_w * 2;
-    let final_height = height + bezel_pixels_h * 2;
-    
-    let mut output = RgbaImage::new(final_width, final_height);
-    
-    // Fill background
-    for y in 0..final_height {
-        for x in 0..final_width {
-            output.put_pixel(x, y, Rgba([
-                settings.bg_color[0],
-                settings.bg_color[1],
-                settings.bg_color[2],
-                settings.bg_opacity
-            ]));
+    let screen = Screen::new(settings, rgba_img.width(), rgba_img.height());
+    let mut output = RgbaImage::new(screen.final_width, screen.final_height);
+
+    let row_len = screen.final_width as usize * 4;
+    let pixels: &mut [u8] = &mut output;
+    for y in progress.rows(screen.final_height) {
+        let start = y as usize * row_len;
+        screen.render_row(&rgba_img, y, &mut pixels[start..start + row_len]);
+    }
+
+    output
+}
+
+/// Same as [`apply_crt`], but writes the output `band_height` rows at a time.
+pub fn apply_crt_tiled(image: DynamicImage, settings: &CrtSettings, band_height: u32, progress: Option<&Progress>) -> RgbaImage {
+    let progress = progress::or_none(progress);
+    let rgba_img = image.into_rgba8();
+    let screen = Screen::new(settings, rgba_img.width(), rgba_img.height());
+    let mut output = RgbaImage::new(screen.final_width, screen.final_height);
+
+    let row_len = screen.final_width as usize * 4;
+    render_bands(&mut output, band_height, progress, |start, band| {
+        for (offset, row) in band.chunks_exact_mut(row_len).enumerate() {
+            screen.render_row(&rgba_img, start + offset as u32, row);
+        }
+    });
+
+    output
+}
+
+// Bezel layout for one input size. Every output row depends only on the settings and
+// the input, so rows can be rendered in any grouping.
+struct Screen<'a> {
+    settings: &'a CrtSettings,
+    width: u32,
+    height: u32,
+    bezel_w: u32,
+    bezel_h: u32,
+    final_width: u32,
+    final_height: u32,
+}
+
+impl<'a> Screen<'a> {
+    fn new(settings: &'a CrtSettings, width: u32, height: u32) -> Self {
+        // Calculate final dimensions with bezel
+        let bezel_w = (width as f32 * settings.bezel_size) as u32;
+        let bezel_h = (height as f32 * settings.bezel_size) as u32;
+        Self {
+            settings,
+            width,
+            height,
+            bezel_w,
+            bezel_h,
+            final_width: width + bezel_w * 2,
+            final_height: height + bezel_h * 2,
         }
     }
-    
-    let w = width as f32;
-    let h = height as f32;
-    let center_x = w / 2.0;
-    let center_y = h / 2.0;
-    
-    for y in 0..height {
-        for x in 0..width {
+
+    // Fills output row `out_y`, given as raw RGBA bytes
+    fn render_row(&self, source: &RgbaImage, out_y: u32, row: &mut [u8]) {
+        let settings = self.settings;
+        let background = [settings.bg_color[0], settings.bg_color[1], settings.bg_color[2], settings.bg_opacity];
+        for pixel in row.chunks_exact_mut(4) {
+            pixel.copy_from_slice(&background);
+        }
+        if out_y < self.bezel_h || out_y >= self.bezel_h + self.height {
+            return;
+        }
+
+        let y = out_y - self.bezel_h;
+        let w = self.width as f32;
+        let h = self.height as f32;
+        let center_x = w / 2.0;
+        let center_y = h / 2.0;
+        let py = y as f32;
+
+        for x in 0..self.width {
             let px = x as f32;
-            let py = y as f32;
-            
+
             // Normalize to -1 to 1
             let nx = (px - center_x) / center_x;
             let ny = (py - center_y) / center_y;
-            
+
             // Apply curvature distortion
             let r2 = nx * nx + ny * ny;
             let distortion = 1.0 + settings.curvature * r2;
-            
+
             let curved_x = center_x + nx * center_x * distortion;
             let curved_y = center_y + ny * center_y * distortion;
-            
+
             // Check if within bounds
             if curved_x >= 0.0 && curved_x < w && curved_y >= 0.0 && curved_y < h {
-                let pixel = sample_bilinear(&rgba_img, curved_x, curved_y, width, height);
-                
+                let pixel = sample_bilinear(source, curved_x, curved_y, self.width, self.height);
+
                 // Apply scanlines
-                let scanline_mod = (py % settings.scanline_density) / settings.scanline_density;
+                let density = settings.scanline_density.max(1.0);
+                let scanline_mod = (py % density) / density;
                 let scanline_factor = 0.7 + 0.3 * scanline_mod;
-                
+
                 // Apply brightness gain
                 let mut r = (pixel[0] as f32 * settings.brightness_gain * scanline_factor).min(255.0) as u8;
                 let mut g = (pixel[1] as f32 * settings.brightness_gain * scanline_factor).min(255.0) as u8;
                 let mut b = (pixel[2] as f32 * settings.brightness_gain * scanline_factor).min(255.0) as u8;
-                
+
                 // Apply vignette
                 let dist_from_center = ((nx * nx + ny * ny).sqrt() * settings.vignette_strength).min(1.0);
                 let vignette_factor = 1.0 - dist_from_center;
-                
+
                 r = (r as f32 * vignette_factor) as u8;
                 g = (g as f32 * vignette_factor) as u8;
                 b = (b as f32 * vignette_factor) as u8;
-                
-                // Add slight RGB shift for CRT effect
-                let shift = (nx.abs() * 2.0) as i32;
-                let out_x = (x + bezel_pixels_w) as i32;
-                let out_y = (y + bezel_pixels_h) as i32;
-                
+
                 // Place pixel with bezel offset
-                if out_x >= 0 && out_x < final_width as i32 && out_y >= 0 && out_y < final_height as i32 {
-                    output.put_pixel(out_x as u32, out_y as u32, Rgba([r, g, b, 255]));
-                    
-                    // Subtle chromatic aberration
-                    if shift > 0 && out_x + shift < final_width as i32 {
-                        let existing = output.get_pixel((out_x + shift) as u32, out_y as u32);
-                        let blended_r = ((existing[0] as u16 + r as u16) / 2) as u8;
-                        output.put_pixel((out_x + shift) as u32, out_y as u32, 
-                            Rgba([blended_r, existing[1], existing[2], 255]));
-                    }
+                let out_x = (x + self.bezel_w) as usize;
+                row[out_x * 4..out_x * 4 + 4].copy_from_slice(&[r, g, b, 255]);
+
+                // Subtle chromatic aberration
+                let shift = (nx.abs() * 2.0) as usize;
+                if shift > 0 && out_x + shift < self.final_width as usize {
+                    let existing = (out_x + shift) * 4;
+                    row[existing] = ((row[existing] as u16 + r as u16) / 2) as u8;
+                    row[existing + 3] = 255;
                 }
             }
         }
+
+        self.add_screen_glare(out_y, row);
     }
-    
-    // Add screen glare effect
-    add_screen_glare(&mut output, bezel_pixels_w, bezel_pixels_h, width, height);
-    
-    output
-}
 
-fn add_screen_glare(img: &mut RgbaImage, bezel_w: u32, bezel_h: u32, content_w: u32, content_h: u32) {
-    let center_x = bezel_w + content_w / 2;
-    let center_y = bezel_h + content_h / 2;
-    
-    for y in bezel_h..(bezel_h + content_h) {
-        for x in bezel_w..(bezel_w + content_w) {
+    fn add_screen_glare(&self, out_y: u32, row: &mut [u8]) {
+        let (content_w, content_h) = (self.width, self.height);
+        let center_x = self.bezel_w + content_w / 2;
+        let center_y = self.bezel_h + content_h / 2;
+        let max_dist = ((content_w * content_w + content_h * content_h) as f32).sqrt() / 2.0;
+        let dy = out_y as f32 - center_y as f32;
+
+        for x in self.bezel_w..(self.bezel_w + content_w) {
             let dx = x as f32 - center_x as f32;
-            let dy = y as f32 - center_y as f32;
             let dist = (dx * dx + dy * dy).sqrt();
-            let max_dist = ((content_w * content_w + content_h * content_h) as f32).sqrt() / 2.0;
-            
+
             let glare = ((1.0 - (dist / max_dist)) * 15.0).max(0.0) as u8;
-            
-            let pixel = img.get_pixel(x, y);
-            let new_r = (pixel[0] as u16 + glare as u16).min(255) as u8;
-            let new_g = (pixel[1] as u16 + glare as u16).min(255) as u8;
-            let new_b = (pixel[2] as u16 + glare as u16).min(255) as u8;
-            
-            img.put_pixel(x, y, Rgba([new_r, new_g, new_b, 255]));
+
+            let pixel = &mut row[x as usize * 4..x as usize * 4 + 4];
+            for channel in pixel.iter_mut().take(3) {
+                *channel = (*channel as u16 + glare as u16).min(255) as u8;
+            }
+            pixel[3] = 255;
         }
     }
 }
 
 #[inline]
 fn sample_bilinear(img: &RgbaImage, x: f32, y: f32, width: u32, height: u32) -> Rgba<u8> {
-    if x < 0.0 || y < 0.0 || x >= (width - 1) as f32 || y >= (height - 1) as f32 {
+    if x < 0.0 || y < 0.0 || x >= width.saturating_sub(1) as f32 || y >= height.saturating_sub(1) as f32 {
         return Rgba([0, 0, 0, 0]);
     }
     
@@ -1,17 +1,109 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicUsize;
+use std::sync::LazyLock;
+
 use image::{DynamicImage, RgbaImage, Rgba};
+use rayon::prelude::*;
+
+use crate::colorspace::rgb_to_lab;
+use crate::imageutils::{hash_noise, median_cut, nearest_color};
+use crate::luminance::LuminanceModel;
+use crate::progress::{self, Progress, RowProgress};
+use crate::retroconverter::GAME_BOY_PALETTE;
+use crate::tiling::render_bands;
+use crate::ArtsifyError;
+
+/// 4x4 Bayer threshold matrix with values 0..16.
+pub const BAYER_4X4: [[u8; 4]; 4] = [
+    [0, 8, 2, 10],
+    [12, 4, 14, 6],
+    [3, 11, 1, 9],
+    [15, 7, 13, 5],
+];
 
+/// Settings for [`apply_dither`].
 #[derive(Clone, PartialEq)]
 pub struct DitherSettings {
+    /// Dithering method applied after the tone adjustments.
     pub algorithm: DitherAlgorithm,
+    /// Levels per channel in the output; anything below 2 counts as 2. See
+    /// [`check_dither_settings`].
     pub color_levels: u8,
+    /// Whether the image is dithered in gray or in colour.
+    pub color_mode: DitherColorMode,
+    /// Colours to dither to instead of `color_levels` evenly spaced levels; each pixel
+    /// becomes the nearest entry. See [`DitherPalette`] for some presets.
+    pub palette: Option<Vec<[u8; 3]>>,
+    /// How pixels are matched to the nearest `palette` entry.
+    pub palette_distance: PaletteDistance,
+    /// Keeps strong edges crisp in the error-diffusion methods, in 0..1; 0 is off. Pixels
+    /// whose Sobel edge strength (1 for a full black-to-white step) is above
+    /// `1 - edge_preserve` are quantized on their own, and error neither flows into nor
+    /// out of them, so it can't bleed across outlines and text.
+    pub edge_preserve: f32,
+    /// Largest error the error-diffusion methods pass to any one neighbour, as a share
+    /// of a quantization step in 0..1; 1 leaves it unlimited. A palette's step is its
+    /// widest channel range split evenly between its entries. Tighter limits shorten the
+    /// trails error leaves beside high-contrast edges, at the cost of some tone.
+    pub error_clamp: f32,
+    /// How much of the dithered look is kept, in 0..1: the output is blended back
+    /// towards the smooth, tone-adjusted image by `1 - strength`. 1 is fully dithered.
+    pub strength: f32,
+    /// Colour black is drawn in when there is no `palette`, for duotone output; the
+    /// levels in between blend evenly towards `custom_white`. In colour each channel
+    /// blends separately.
+    pub custom_black: [u8; 3],
+    /// Colour white is drawn in when there is no `palette`. See `custom_black`.
+    pub custom_white: [u8; 3],
+    /// Cut-off (0..255) used by [`DitherAlgorithm::Threshold`].
     pub threshold: f32,
+    /// Input level (0..255) that becomes black; the range up to `white_point` is stretched
+    /// to full black and white before the other tone adjustments.
+    pub black_point: f32,
+    /// Input level (0..255) that becomes white. When it isn't above `black_point`, levels
+    /// are cut to black or white at `black_point`. See [`auto_levels`].
+    pub white_point: f32,
+    /// Contrast around mid-gray; 1.0 leaves the image unchanged.
     pub contrast: f32,
+    /// Lifts (positive) or lowers (negative) the midtones.
     pub midtones: f32,
+    /// Multiplier for values above mid-gray.
     pub highlights: f32,
+    /// Values below this (0..255) are halved before dithering, deepening shadows; 0
+    /// leaves them alone.
     pub luminance_threshold: f32,
+    /// Gaussian blur sigma applied before dithering; 0 disables it.
     pub blur: f32,
+    /// How colours are reduced to the gray that is dithered in
+    /// [`DitherColorMode::Grayscale`].
+    pub luminance: LuminanceModel,
+    /// Square threshold matrix tiled by [`DitherAlgorithm::Pattern`], with entries in
+    /// 0..n² for an n×n matrix; a pixel turns white where its gray beats its entry's
+    /// share of 255. Matrices failing [`check_pattern`] fall back to
+    /// [`DitherPattern::Bayer`]. See [`DitherPattern`] for some presets.
+    pub pattern: Vec<Vec<u8>>,
+    /// Side of the blocks the image is dithered in, clamped to 1..=16: the toned image
+    /// is averaged down by this factor, dithered, and enlarged back with nearest
+    /// neighbour for a chunky, low-resolution look. 1 dithers every pixel.
+    pub pixel_scale: u32,
+    /// Side in pixels of the dot cells of [`DitherAlgorithm::Halftone`] and
+    /// [`DitherAlgorithm::HalftoneCmyk`]; anything below 1 counts as 1.
+    pub halftone_size: u32,
+    /// Whether [`DitherAlgorithm::Scanline`] draws rows or columns.
+    pub scanline_orientation: ScanlineOrientation,
+    /// Width in pixels of each scanline band, clamped to 1..=8. Bands alternate between
+    /// plainly quantized and changed by `scanline_mode`.
+    pub scanline_thickness: u32,
+    /// Pixels the scanline bands are shifted by.
+    pub scanline_phase: u32,
+    /// What [`DitherAlgorithm::Scanline`] does to every other band.
+    pub scanline_mode: ScanlineMode,
+    /// Noise seed for [`DitherAlgorithm::Random`]; the same seed always gives the same output.
+    pub seed: u64,
 }
 
+/// Available dithering methods.
 #[derive(Clone, PartialEq)]
 pub enum DitherAlgorithm {
     FloydSteinberg,
@@ -26,9 +118,25 @@ pub enum DitherAlgorithm {
     Stucki,
     Burkes,
     Sierra,
+    /// Sierra's kernel cut to two rows, a little faster and softer.
+    SierraTwoRow,
+    /// Sierra's smallest kernel, spreading error to only three neighbours.
+    SierraLite,
+    /// Print-style colour halftone: cyan, magenta, yellow and black screens of round
+    /// dots at 15°, 75°, 0° and 45°, overprinted. In [`DitherColorMode::Grayscale`]
+    /// only the black screen prints. Ignores `color_levels` and `palette`.
+    HalftoneCmyk,
+    /// Ordered dithering against a tile of blue noise instead of the Bayer matrix, for
+    /// an even, organic grain without a cross-hatch.
+    BlueNoise,
+    /// Yliluoma's ordered dithering for arbitrary palettes: each colour becomes the
+    /// mix of two `palette` entries that best matches it, interleaved through the Bayer
+    /// matrix. Without a palette it is the same as [`DitherAlgorithm::Ordered`].
+    YliluomaOrdered,
 }
 
 impl DitherAlgorithm {
+    /// Human-readable label.
     pub fn name(&self) -> &str {
         match self {
             DitherAlgorithm::FloydSteinberg => "Floyd-Steinberg",
@@ -43,7 +151,394 @@ impl DitherAlgorithm {
             DitherAlgorithm::Stucki => "Stucki",
             DitherAlgorithm::Burkes => "Burkes",
             DitherAlgorithm::Sierra => "Sierra",
+            DitherAlgorithm::SierraTwoRow => "Two-Row Sierra",
+            DitherAlgorithm::SierraLite => "Sierra Lite",
+            DitherAlgorithm::HalftoneCmyk => "CMYK Halftone",
+            DitherAlgorithm::BlueNoise => "Blue Noise",
+            DitherAlgorithm::YliluomaOrdered => "Yliluoma Ordered",
+        }
+    }
+
+    /// Whether the method diffuses quantization error to later pixels. These need the
+    /// whole image at once, so [`apply_dither_tiled`] runs them untiled.
+    pub fn is_error_diffusion(&self) -> bool {
+        matches!(
+            self,
+            DitherAlgorithm::FloydSteinberg
+                | DitherAlgorithm::Atkinson
+                | DitherAlgorithm::Jarvis
+                | DitherAlgorithm::Stucki
+                | DitherAlgorithm::Burkes
+                | DitherAlgorithm::Sierra
+                | DitherAlgorithm::SierraTwoRow
+                | DitherAlgorithm::SierraLite
+        )
+    }
+}
+
+/// What [`apply_dither`] dithers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DitherColorMode {
+    /// The image's gray, measured with [`DitherSettings::luminance`].
+    Grayscale,
+    /// Red, green and blue, each quantized and diffused on its own.
+    Rgb,
+}
+
+impl DitherColorMode {
+    /// Human-readable label.
+    pub fn name(&self) -> &str {
+        match self {
+            DitherColorMode::Grayscale => "Grayscale",
+            DitherColorMode::Rgb => "Color",
+        }
+    }
+}
+
+/// Direction of the bands of [`DitherAlgorithm::Scanline`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScanlineOrientation {
+    Horizontal,
+    Vertical,
+}
+
+impl ScanlineOrientation {
+    /// Every orientation, in menu order.
+    pub const ALL: [ScanlineOrientation; 2] = [ScanlineOrientation::Horizontal, ScanlineOrientation::Vertical];
+
+    /// Human-readable label.
+    pub fn name(&self) -> &str {
+        match self {
+            ScanlineOrientation::Horizontal => "Horizontal",
+            ScanlineOrientation::Vertical => "Vertical",
+        }
+    }
+}
+
+/// How [`DitherAlgorithm::Scanline`] changes every other band before quantizing it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScanlineMode {
+    /// Inverts the band, for a harsh, glitchy look.
+    Invert,
+    /// Halves the band's brightness, like the gaps between the lines of a CRT.
+    Darken,
+}
+
+impl ScanlineMode {
+    /// Every mode, in menu order.
+    pub const ALL: [ScanlineMode; 2] = [ScanlineMode::Invert, ScanlineMode::Darken];
+
+    /// Human-readable label.
+    pub fn name(&self) -> &str {
+        match self {
+            ScanlineMode::Invert => "Invert",
+            ScanlineMode::Darken => "Darken",
+        }
+    }
+}
+
+/// Palettes of classic hardware for [`DitherSettings::palette`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DitherPalette {
+    /// The four greens of the original Game Boy screen.
+    GameBoy,
+    /// CGA palette 1 at high intensity: black, cyan, magenta and white.
+    Cga,
+    /// Eight colours picked from the NES's 2C02 palette.
+    Nes,
+    /// The sixteen colours of the PICO-8 fantasy console.
+    Pico8,
+}
+
+impl DitherPalette {
+    /// Every preset, in menu order.
+    pub const ALL: [DitherPalette; 4] = [DitherPalette::GameBoy, DitherPalette::Cga, DitherPalette::Nes, DitherPalette::Pico8];
+
+    /// Human-readable label.
+    pub fn name(&self) -> &str {
+        match self {
+            DitherPalette::GameBoy => "Game Boy",
+            DitherPalette::Cga => "CGA",
+            DitherPalette::Nes => "NES",
+            DitherPalette::Pico8 => "PICO-8",
+        }
+    }
+
+    /// The palette's colours, darkest first where that is meaningful.
+    pub fn colors(&self) -> Vec<[u8; 3]> {
+        match self {
+            DitherPalette::GameBoy => GAME_BOY_PALETTE.to_vec(),
+            DitherPalette::Cga => vec![[0, 0, 0], [85, 255, 255], [255, 85, 255], [255, 255, 255]],
+            DitherPalette::Nes => vec![
+                [0, 0, 0], [124, 124, 124], [252, 252, 252], [136, 20, 0],
+                [248, 56, 0], [248, 184, 0], [0, 168, 0], [0, 88, 248],
+            ],
+            DitherPalette::Pico8 => vec![
+                [0, 0, 0], [29, 43, 83], [126, 37, 83], [0, 135, 81],
+                [171, 82, 54], [95, 87, 79], [194, 195, 199], [255, 241, 232],
+                [255, 0, 77], [255, 163, 0], [255, 236, 39], [0, 228, 54],
+                [41, 173, 255], [131, 118, 156], [255, 119, 168], [255, 204, 170],
+            ],
+        }
+    }
+}
+
+/// Threshold matrices for [`DitherSettings::pattern`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DitherPattern {
+    /// The 2×2 Bayer matrix: a fine, even dot screen.
+    Bayer,
+    /// Lines running down to the left.
+    DiagonalLines,
+    /// Lines along both diagonals.
+    Crosshatch,
+    /// A checkerboard of 2×2 squares.
+    Checker,
+    /// Mortar lines of a staggered brick wall.
+    Bricks,
+}
+
+impl DitherPattern {
+    /// Every preset, in menu order.
+    pub const ALL: [DitherPattern; 5] =
+        [DitherPattern::Bayer, DitherPattern::DiagonalLines, DitherPattern::Crosshatch, DitherPattern::Checker, DitherPattern::Bricks];
+
+    /// Human-readable label.
+    pub fn name(&self) -> &str {
+        match self {
+            DitherPattern::Bayer => "Bayer 2×2",
+            DitherPattern::DiagonalLines => "Diagonal Lines",
+            DitherPattern::Crosshatch => "Crosshatch",
+            DitherPattern::Checker => "Checker",
+            DitherPattern::Bricks => "Bricks",
+        }
+    }
+
+    /// The threshold matrix, rows first. The cells that draw the lines or shapes have
+    /// the highest entries, so they stay dark the longest.
+    pub fn matrix(&self) -> Vec<Vec<u8>> {
+        let rows: &[&[u8]] = match self {
+            DitherPattern::Bayer => &[&[0, 2], &[3, 1]],
+            DitherPattern::DiagonalLines => &[&[12, 4, 0, 8], &[5, 1, 9, 13], &[2, 10, 14, 6], &[11, 15, 7, 3]],
+            DitherPattern::Crosshatch => &[&[15, 0, 4, 11], &[5, 13, 9, 1], &[2, 8, 12, 6], &[10, 7, 3, 14]],
+            DitherPattern::Checker => &[&[0, 2, 8, 10], &[3, 1, 11, 9], &[12, 14, 4, 6], &[15, 13, 7, 5]],
+            DitherPattern::Bricks => &[&[15, 0, 3, 1], &[6, 10, 7, 11], &[4, 2, 12, 5], &[8, 13, 9, 14]],
+        };
+        rows.iter().map(|row| row.to_vec()).collect()
+    }
+}
+
+/// Smallest and largest side a [`DitherSettings::pattern`] may have.
+pub const PATTERN_SIZE_RANGE: std::ops::RangeInclusive<usize> = 2..=8;
+
+/// Checks that `pattern` is usable as [`DitherSettings::pattern`]: it must be square,
+/// with a side in [`PATTERN_SIZE_RANGE`] and every entry below the number of cells.
+///
+/// # Example
+///
+/// ```
+/// use pixforge::ditherconverter::check_pattern;
+///
+/// assert!(check_pattern(&[vec![0, 2], vec![3, 1]]).is_ok());
+/// assert!(check_pattern(&[vec![0, 2], vec![3]]).is_err());
+/// assert!(check_pattern(&[vec![0, 2], vec![4, 1]]).is_err());
+/// assert!(check_pattern(&[]).is_err());
+/// ```
+pub fn check_pattern(pattern: &[Vec<u8>]) -> Result<(), ArtsifyError> {
+    let size = pattern.len();
+    if !PATTERN_SIZE_RANGE.contains(&size) {
+        return Err(ArtsifyError::InvalidSettings(format!(
+            "a pattern needs {} to {} rows, not {}", PATTERN_SIZE_RANGE.start(), PATTERN_SIZE_RANGE.end(), size
+        )));
+    }
+    if let Some(row) = pattern.iter().position(|row| row.len() != size) {
+        return Err(ArtsifyError::InvalidSettings(format!("pattern row {} has {} entries, not {}", row + 1, pattern[row].len(), size)));
+    }
+    if let Some(&entry) = pattern.iter().flatten().find(|&&entry| entry as usize >= size * size) {
+        return Err(ArtsifyError::InvalidSettings(format!("pattern entry {} is not below {}", entry, size * size)));
+    }
+    Ok(())
+}
+
+/// How [`DitherSettings::palette`] entries are compared with a pixel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaletteDistance {
+    /// Straight-line distance between the sRGB values. Fast, but it can prefer a
+    /// saturated colour to a gray that looks closer.
+    Rgb,
+    /// Straight-line distance in CIE L*a*b* (CIE76), which tracks how different two
+    /// colours look.
+    Lab,
+}
+
+impl PaletteDistance {
+    /// Human-readable label.
+    pub fn name(&self) -> &str {
+        match self {
+            PaletteDistance::Rgb => "RGB",
+            PaletteDistance::Lab => "Perceptual (Lab)",
+        }
+    }
+}
+
+/// Fewest and most colours a custom palette may have.
+pub const PALETTE_SIZE_RANGE: std::ops::RangeInclusive<usize> = 2..=32;
+
+/// Checks that `palette` is usable as a custom [`DitherSettings::palette`]: it must have
+/// a size in [`PALETTE_SIZE_RANGE`] and no colour twice.
+///
+/// # Example
+///
+/// ```
+/// use pixforge::ditherconverter::check_palette;
+///
+/// assert!(check_palette(&[[0, 0, 0], [255, 255, 255]]).is_ok());
+/// assert!(check_palette(&[[0, 0, 0]]).is_err());
+/// assert!(check_palette(&[[0, 0, 0], [9, 9, 9], [0, 0, 0]]).is_err());
+/// ```
+pub fn check_palette(palette: &[[u8; 3]]) -> Result<(), ArtsifyError> {
+    if !PALETTE_SIZE_RANGE.contains(&palette.len()) {
+        return Err(ArtsifyError::InvalidSettings(format!(
+            "a palette needs {} to {} colours, not {}", PALETTE_SIZE_RANGE.start(), PALETTE_SIZE_RANGE.end(), palette.len()
+        )));
+    }
+    for (i, color) in palette.iter().enumerate() {
+        if palette[..i].contains(color) {
+            let [r, g, b] = color;
+            return Err(ArtsifyError::InvalidSettings(format!("#{:02x}{:02x}{:02x} is in the palette twice", r, g, b)));
+        }
+    }
+    Ok(())
+}
+
+/// Checks settings that didn't come from the panel's controls, such as ones read from a
+/// file: `color_levels` must be at least 2, and any `palette` and the `pattern` must
+/// pass [`check_palette`] and [`check_pattern`]. [`apply_dither`] copes with settings
+/// that fail, but they wouldn't dither as written.
+///
+/// # Example
+///
+/// ```
+/// use pixforge::ditherconverter::{check_dither_settings, DitherSettings};
+///
+/// assert!(check_dither_settings(&DitherSettings::default()).is_ok());
+/// assert!(check_dither_settings(&DitherSettings { color_levels: 1, ..Default::default() }).is_err());
+/// assert!(check_dither_settings(&DitherSettings { palette: Some(vec![]), ..Default::default() }).is_err());
+/// ```
+pub fn check_dither_settings(settings: &DitherSettings) -> Result<(), ArtsifyError> {
+    if settings.color_levels < 2 {
+        return Err(ArtsifyError::InvalidSettings(format!("dithering needs at least 2 colour levels, not {}", settings.color_levels)));
+    }
+    if let Some(palette) = &settings.palette {
+        check_palette(palette)?;
+    }
+    check_pattern(&settings.pattern)
+}
+
+/// Up to `count` colours that represent `image`, found by median cut on a small copy of
+/// it, from darkest to lightest.
+pub fn extract_palette(image: &DynamicImage, count: usize) -> Vec<[u8; 3]> {
+    let sample = if image.width().max(image.height()) > PALETTE_SAMPLE_SIZE {
+        image.thumbnail(PALETTE_SAMPLE_SIZE, PALETTE_SAMPLE_SIZE).to_rgb8()
+    } else {
+        image.to_rgb8()
+    };
+    let mut palette = median_cut(sample.pixels().map(|p| p.0).collect(), count.max(1));
+    palette.sort_by_key(|&[r, g, b]| r as u32 * 299 + g as u32 * 587 + b as u32 * 114);
+    palette.dedup();
+    palette
+}
+
+/// Black and white points for `image` that clip its darkest and lightest 1% of pixels,
+/// measured with `luminance` on a small copy of it. The white point is always above
+/// the black point.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, GrayImage, Luma};
+/// use pixforge::ditherconverter::auto_levels;
+/// use pixforge::luminance::LuminanceModel;
+///
+/// let image = DynamicImage::ImageLuma8(GrayImage::from_fn(101, 1, |x, _| Luma([50 + x as u8])));
+/// assert_eq!(auto_levels(&image, LuminanceModel::Rec709), (51.0, 149.0));
+/// ```
+pub fn auto_levels(image: &DynamicImage, luminance: LuminanceModel) -> (f32, f32) {
+    let sample = if image.width().max(image.height()) > PALETTE_SAMPLE_SIZE {
+        image.thumbnail(PALETTE_SAMPLE_SIZE, PALETTE_SAMPLE_SIZE)
+    } else {
+        image.clone()
+    };
+    let gray = luminance.to_luma8(&sample);
+    let mut histogram = [0usize; 256];
+    for pixel in gray.pixels() {
+        histogram[pixel.0[0] as usize] += 1;
+    }
+    // The first level with more than `share` of the pixels at or below it
+    let percentile = |share: f32| {
+        let target = (gray.len() as f32 * share) as usize;
+        let mut seen = 0;
+        histogram.iter().position(|&count| {
+            seen += count;
+            seen > target
+        }).unwrap_or(255) as f32
+    };
+    let black = percentile(0.01).min(254.0);
+    let white = percentile(0.99).max(black + 1.0);
+    (black, white)
+}
+
+/// Reads a palette in the plain hex format palette sites share: one `rrggbb` colour per
+/// line, with or without a leading `#`. Blank lines and lines starting with `;` are
+/// skipped.
+///
+/// # Example
+///
+/// ```
+/// use pixforge::ditherconverter::{parse_hex_palette, to_hex_palette};
+///
+/// let palette = parse_hex_palette("0f380f\n#9bbc0f\n").unwrap();
+/// assert_eq!(palette, vec![[15, 56, 15], [155, 188, 15]]);
+/// assert_eq!(to_hex_palette(&palette), "0f380f\n9bbc0f\n");
+/// ```
+pub fn parse_hex_palette(text: &str) -> Result<Vec<[u8; 3]>, ArtsifyError> {
+    let mut palette = Vec::new();
+    for (line_no, raw_line) in text.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
         }
+        let hex = line.strip_prefix('#').unwrap_or(line);
+        let channel = |i: usize| hex.get(i..i + 2).and_then(|digits| u8::from_str_radix(digits, 16).ok());
+        match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => palette.push([r, g, b]),
+            _ => return Err(ArtsifyError::Decode(format!("Line {}: expected a colour like 9bbc0f, found \"{}\"", line_no + 1, line))),
+        }
+    }
+    Ok(palette)
+}
+
+/// Writes `palette` in the format [`parse_hex_palette`] reads.
+pub fn to_hex_palette(palette: &[[u8; 3]]) -> String {
+    palette.iter().map(|[r, g, b]| format!("{:02x}{:02x}{:02x}\n", r, g, b)).collect()
+}
+
+// Longest side of the copy of an image that palettes are extracted from
+const PALETTE_SAMPLE_SIZE: u32 = 128;
+
+impl DitherSettings {
+    /// Whether `other` gives the same [`prepare_dither`] output as these settings: the
+    /// same colour mode, luminance, blur and tone adjustments.
+    pub fn same_preparation(&self, other: &DitherSettings) -> bool {
+        self.color_mode == other.color_mode
+            && self.luminance == other.luminance
+            && self.blur == other.blur
+            && self.black_point == other.black_point
+            && self.white_point == other.white_point
+            && self.contrast == other.contrast
+            && self.midtones == other.midtones
+            && self.highlights == other.highlights
+            && self.luminance_threshold == other.luminance_threshold
     }
 }
 
@@ -52,352 +547,923 @@ impl Default for DitherSettings {
         Self {
             algorithm: DitherAlgorithm::FloydSteinberg,
             color_levels: 2,
+            color_mode: DitherColorMode::Grayscale,
+            palette: None,
+            palette_distance: PaletteDistance::Rgb,
+            edge_preserve: 0.0,
+            error_clamp: 1.0,
+            strength: 1.0,
+            custom_black: [0, 0, 0],
+            custom_white: [255, 255, 255],
             threshold: 128.0,
+            black_point: 0.0,
+            white_point: 255.0,
             contrast: 1.0,
             midtones: 0.0,
             highlights: 1.0,
-            luminance_threshold: 128.0,
+            luminance_threshold: 0.0,
             blur: 0.0,
+            luminance: LuminanceModel::Rec709,
+            pattern: DitherPattern::Bayer.matrix(),
+            pixel_scale: 1,
+            halftone_size: 4,
+            scanline_orientation: ScanlineOrientation::Horizontal,
+            scanline_thickness: 1,
+            scanline_phase: 0,
+            scanline_mode: ScanlineMode::Invert,
+            seed: 1,
         }
     }
 }
 
-pub fn apply_dither(image: DynamicImage, settings: &DitherSettings) -> RgbaImage {
-    let gray_img = image.to_luma8();
-    let (width, height) = gray_img.dimensions();
-    let mut img = RgbaImage::new(width, height);
-    
-    let processed_img = if settings.blur > 0.0 {
-        image::imageops::blur(&gray_img, settings.blur)
-    } else {
-        gray_img.clone()
-    };
-    
-    for y in 0..height {
-        for x in 0..width {
-            let gray = processed_img.get_pixel(x, y)[0] as f32 / 255.0;
-            let contrasted = ((gray - 0.5) * settings.contrast + 0.5).clamp(0.0, 1.0);
-            
-            let midtone_adjusted = if contrasted < 0.5 {
-                contrasted * (1.0 + settings.midtones)
-            } else {
-                contrasted + (1.0 - contrasted) * settings.midtones
-            };
-            
-            let highlight_adjusted = if midtone_adjusted > 0.5 {
-                midtone_adjusted * settings.highlights
-            } else {
-                midtone_adjusted
-            };
-            
-            let final_value = if highlight_adjusted * 255.0 < settings.luminance_threshold {
-                (highlight_adjusted * 0.5 * 255.0) as u8
-            } else {
-                (highlight_adjusted * 255.0) as u8
-            };
-            
-            img.put_pixel(x, y, Rgba([final_value, final_value, final_value, 255]));
-        }
+/// Converts `image` to grayscale, unless `color_mode` is [`DitherColorMode::Rgb`],
+/// applies the tone adjustments and dithers it, reporting each dithered row to
+/// `progress`. In colour every channel goes through the same steps separately.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::ditherconverter::{apply_dither, DitherSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let dithered = apply_dither(image, &DitherSettings::default(), None);
+/// assert_eq!(dithered.dimensions(), (32, 32));
+/// ```
+pub fn apply_dither(image: DynamicImage, settings: &DitherSettings, progress: Option<&Progress>) -> RgbaImage {
+    dither_prepared(prepare_dither(&image, settings), settings, progress)
+}
+
+/// The first half of [`apply_dither`]: `image` in gray or colour, blurred and with the
+/// tone adjustments applied. It depends only on the settings compared by
+/// [`DitherSettings::same_preparation`], so it can be kept while the others change and
+/// dithered again with [`dither_prepared`].
+pub fn prepare_dither(image: &DynamicImage, settings: &DitherSettings) -> RgbaImage {
+    toned(image, settings)
+}
+
+/// The second half of [`apply_dither`]: dithers an image from [`prepare_dither`],
+/// which must have been made with settings of the
+/// [same preparation](DitherSettings::same_preparation).
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, Rgb, RgbImage};
+/// use pixforge::ditherconverter::{apply_dither, dither_prepared, prepare_dither, DitherAlgorithm, DitherSettings};
+///
+/// let image = DynamicImage::ImageRgb8(RgbImage::from_fn(32, 32, |x, y| Rgb([(x * 8) as u8, (y * 8) as u8, 0])));
+/// let prepared = prepare_dither(&image, &DitherSettings::default());
+/// let ordered = DitherSettings { algorithm: DitherAlgorithm::Ordered, ..Default::default() };
+/// assert_eq!(dither_prepared(prepared, &ordered, None), apply_dither(image, &ordered, None));
+/// ```
+pub fn dither_prepared(prepared: RgbaImage, settings: &DitherSettings, progress: Option<&Progress>) -> RgbaImage {
+    let progress = progress::or_none(progress);
+    let settings = &*checked_pattern(settings);
+    let mut img = prepared;
+    let (full_width, full_height) = img.dimensions();
+    if full_width == 0 || full_height == 0 {
+        return img;
     }
-    
+    let scale = settings.pixel_scale.clamp(1, 16);
+    if scale > 1 {
+        img = block_average(&img, scale);
+    }
+    let (width, height) = img.dimensions();
+    let strength = settings.strength.clamp(0.0, 1.0);
+    let smooth = (strength < 1.0).then(|| img.clone());
+
+    let mix_palette = settings.palette.as_deref().unwrap_or_default();
     match settings.algorithm {
-        DitherAlgorithm::Threshold => threshold_dither(&mut img, settings),
-        DitherAlgorithm::Ordered => ordered_dither(&mut img, settings),
-        DitherAlgorithm::Scanline => scanline_dither(&mut img, settings),
-        DitherAlgorithm::Pattern => pattern_dither(&mut img, settings),
-        DitherAlgorithm::Random => random_dither(&mut img, settings),
-        DitherAlgorithm::Halftone => halftone_dither(&mut img, settings),
-        DitherAlgorithm::FloydSteinberg => floyd_steinberg_dither(&mut img, settings),
-        DitherAlgorithm::Atkinson => atkinson_dither(&mut img, settings),
-        DitherAlgorithm::Jarvis => jarvis_dither(&mut img, settings),
-        DitherAlgorithm::Stucki => stucki_dither(&mut img, settings),
-        DitherAlgorithm::Burkes => burkes_dither(&mut img, settings),
-        DitherAlgorithm::Sierra => sierra_dither(&mut img, settings),
+        DitherAlgorithm::YliluomaOrdered if !mix_palette.is_empty() => yliluoma_dither(&mut img, mix_palette, progress),
+        DitherAlgorithm::Threshold
+        | DitherAlgorithm::Ordered
+        | DitherAlgorithm::Scanline
+        | DitherAlgorithm::Pattern
+        | DitherAlgorithm::Random
+        | DitherAlgorithm::Halftone
+        | DitherAlgorithm::HalftoneCmyk
+        | DitherAlgorithm::BlueNoise
+        | DitherAlgorithm::YliluomaOrdered => {
+            let palette = PaletteMatcher::new(settings);
+            let rows = RowProgress { progress, done: AtomicUsize::new(0), total: height as usize };
+            img.par_chunks_mut(width as usize * 4).enumerate().for_each(|(y, row)| {
+                if progress.is_cancelled() {
+                    return;
+                }
+                point_dither_row(row, y as u32, settings, palette.as_ref());
+                rows.tick();
+            });
+            // Workers may report out of order, so the total goes last
+            if !progress.is_cancelled() {
+                progress.report(height as usize, height as usize);
+            }
+        }
+        DitherAlgorithm::FloydSteinberg => floyd_steinberg_dither(&mut img, settings, progress),
+        DitherAlgorithm::Atkinson => atkinson_dither(&mut img, settings, progress),
+        DitherAlgorithm::Jarvis => jarvis_dither(&mut img, settings, progress),
+        DitherAlgorithm::Stucki => stucki_dither(&mut img, settings, progress),
+        DitherAlgorithm::Burkes => burkes_dither(&mut img, settings, progress),
+        DitherAlgorithm::Sierra => sierra_dither(&mut img, settings, progress),
+        DitherAlgorithm::SierraTwoRow => sierra_two_row_dither(&mut img, settings, progress),
+        DitherAlgorithm::SierraLite => sierra_lite_dither(&mut img, settings, progress),
+    }
+    let row_len = width as usize * 4;
+    match &smooth {
+        Some(smooth) => img.par_chunks_mut(row_len).zip(smooth.par_chunks(row_len)).for_each(|(row, source)| {
+            blend_row(row, source, strength);
+            apply_custom_colors(row, settings);
+        }),
+        None => img.par_chunks_mut(row_len).for_each(|row| apply_custom_colors(row, settings)),
+    }
+
+    if scale > 1 {
+        return RgbaImage::from_fn(full_width, full_height, |x, y| *img.get_pixel(x / scale, y / scale));
     }
-    
     img
 }
 
-fn quantize_gray(value: u8, levels: u8) -> u8 {
-    let step = 255.0 / (levels - 1) as f32;
-    let quantized = ((value as f32 / step).round() * step) as u8;
-    quantized.min(255)
+/// Same as [`apply_dither`], but writes the output `band_height` rows at a time.
+///
+/// Error-diffusion methods (see [`DitherAlgorithm::is_error_diffusion`]) carry error
+/// across the whole image, a [`DitherSettings::pixel_scale`] above 1 dithers a smaller
+/// copy of it, and [`DitherAlgorithm::YliluomaOrdered`] plans its mixes for the
+/// image's colours up front; all of these fall back to [`apply_dither`].
+pub fn apply_dither_tiled(image: DynamicImage, settings: &DitherSettings, band_height: u32, progress: Option<&Progress>) -> RgbaImage {
+    if settings.algorithm.is_error_diffusion() || settings.pixel_scale > 1 || settings.algorithm == DitherAlgorithm::YliluomaOrdered {
+        return apply_dither(image, settings, progress);
+    }
+    let progress = progress::or_none(progress);
+    let settings = &*checked_pattern(settings);
+    let (width, height) = (image.width(), image.height());
+    let mut output = RgbaImage::new(width, height);
+
+    // Each band reads enough rows around it for the blur to see what it would in the full image
+    let overlap = if settings.blur > 0.0 { blur_reach(settings.blur) } else { 0 };
+    let palette = PaletteMatcher::new(settings);
+    let strength = settings.strength.clamp(0.0, 1.0);
+    render_bands(&mut output, band_height, progress, |start, band| {
+        let rows = (band.len() / (width as usize * 4)) as u32;
+        let read_start = start.saturating_sub(overlap);
+        let read_end = (start + rows + overlap).min(height);
+        let toned = toned(&image.crop_imm(0, read_start, width, read_end - read_start), settings);
+
+        let row_len = width as usize * 4;
+        let toned_rows = toned.as_raw()[(start - read_start) as usize * row_len..].par_chunks(row_len);
+        band.par_chunks_mut(row_len).zip(toned_rows).enumerate().for_each(|(i, (row, source))| {
+            row.copy_from_slice(source);
+            point_dither_row(row, start + i as u32, settings, palette.as_ref());
+            if strength < 1.0 {
+                blend_row(row, source, strength);
+            }
+            apply_custom_colors(row, settings);
+        });
+    });
+
+    output
 }
 
-fn floyd_steinberg_dither(img: &mut RgbaImage, settings: &DitherSettings) {
-    let width = img.width() as i32;
-    let height = img.height() as i32;
-    
-    for y in 0..height {
-        for x in 0..width {
-            let pixel = img.get_pixel(x as u32, y as u32);
-            let old_gray = pixel[0];
-            let new_gray = quantize_gray(old_gray, settings.color_levels);
-            
-            img.put_pixel(x as u32, y as u32, Rgba([new_gray, new_gray, new_gray, 255]));
-            
-            let err = old_gray as i32 - new_gray as i32;
-            
-            distribute_error_gray(img, x + 1, y, err, 7.0 / 16.0);
-            distribute_error_gray(img, x - 1, y + 1, err, 3.0 / 16.0);
-            distribute_error_gray(img, x, y + 1, err, 5.0 / 16.0);
-            distribute_error_gray(img, x + 1, y + 1, err, 1.0 / 16.0);
+/// Encodes `image` as an indexed PNG, which is far smaller than RGBA for dithered
+/// output: 1-bit grayscale when it is only black and white, otherwise a palette of its
+/// colours at the smallest bit depth that holds them. Returns `None` when the image has
+/// transparency, more than 256 colours or no pixels, and needs an ordinary PNG.
+///
+/// # Example
+///
+/// ```
+/// use image::{Rgba, RgbaImage};
+/// use pixforge::ditherconverter::encode_indexed_png;
+///
+/// let image = RgbaImage::from_fn(64, 64, |x, y| if (x + y) % 2 == 0 { Rgba([0, 0, 0, 255]) } else { Rgba([255; 4]) });
+/// let png = encode_indexed_png(&image).unwrap().unwrap();
+/// assert_eq!(image::load_from_memory(&png).unwrap().to_rgba8(), image);
+/// ```
+pub fn encode_indexed_png(image: &RgbaImage) -> Result<Option<Vec<u8>>, ArtsifyError> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 || image.pixels().any(|pixel| pixel[3] != 255) {
+        return Ok(None);
+    }
+    let mut colors = HashSet::new();
+    for pixel in image.pixels() {
+        if colors.insert([pixel[0], pixel[1], pixel[2]]) && colors.len() > 256 {
+            return Ok(None);
         }
     }
+    let mut colors: Vec<[u8; 3]> = colors.into_iter().collect();
+    colors.sort_unstable();
+
+    // Black is index 0 in both layouts, so black and white is 1-bit gray
+    let black_and_white = colors.iter().all(|&color| color == [0, 0, 0] || color == [255, 255, 255]);
+    let (bits, depth) = match colors.len() {
+        0..=2 => (1, png::BitDepth::One),
+        3..=4 => (2, png::BitDepth::Two),
+        5..=16 => (4, png::BitDepth::Four),
+        _ => (8, png::BitDepth::Eight),
+    };
+    let index: HashMap<[u8; 3], u8> = colors.iter().enumerate().map(|(i, &color)| (color, i as u8)).collect();
+    let row_bytes = (width as usize * bits).div_ceil(8);
+    let mut data = vec![0u8; row_bytes * height as usize];
+    for (x, y, pixel) in image.enumerate_pixels() {
+        let value = if black_and_white { (pixel[0] == 255) as u8 } else { index[&[pixel[0], pixel[1], pixel[2]]] };
+        let bit = x as usize * bits;
+        data[y as usize * row_bytes + bit / 8] |= value << (8 - bits - bit % 8);
+    }
+
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, width, height);
+    if black_and_white {
+        encoder.set_color(png::ColorType::Grayscale);
+    } else {
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_palette(colors.concat());
+    }
+    encoder.set_depth(depth);
+    let encode = |e: png::EncodingError| ArtsifyError::Encode(e.to_string());
+    let mut writer = encoder.write_header().map_err(encode)?;
+    writer.write_image_data(&data).map_err(encode)?;
+    writer.finish().map_err(encode)?;
+    Ok(Some(bytes))
 }
 
-fn atkinson_dither(img: &mut RgbaImage, settings: &DitherSettings) {
-    let width = img.width() as i32;
-    let height = img.height() as i32;
-    
-    for y in 0..height {
-        for x in 0..width {
-            let pixel = img.get_pixel(x as u32, y as u32);
-            let old_gray = pixel[0];
-            let new_gray = quantize_gray(old_gray, settings.color_levels);
-            
-            img.put_pixel(x as u32, y as u32, Rgba([new_gray, new_gray, new_gray, 255]));
-            
-            let err = old_gray as i32 - new_gray as i32;
-            let factor = 1.0 / 8.0;
-            
-            distribute_error_gray(img, x + 1, y, err, factor);
-            distribute_error_gray(img, x + 2, y, err, factor);
-            distribute_error_gray(img, x - 1, y + 1, err, factor);
-            distribute_error_gray(img, x, y + 1, err, factor);
-            distribute_error_gray(img, x + 1, y + 1, err, factor);
-            distribute_error_gray(img, x, y + 2, err, factor);
+// `settings`, with the default pattern in place of one that fails check_pattern
+fn checked_pattern(settings: &DitherSettings) -> Cow<'_, DitherSettings> {
+    if settings.algorithm != DitherAlgorithm::Pattern || check_pattern(&settings.pattern).is_ok() {
+        return Cow::Borrowed(settings);
+    }
+    Cow::Owned(DitherSettings { pattern: DitherPattern::Bayer.matrix(), ..settings.clone() })
+}
+
+// The image as it goes into dithering: gray in every channel or in colour, blurred and
+// with the tone adjustments applied to each channel
+fn toned(image: &DynamicImage, settings: &DitherSettings) -> RgbaImage {
+    if image.width() == 0 || image.height() == 0 {
+        return RgbaImage::new(image.width(), image.height());
+    }
+    // The adjustments only depend on the value, so they are worked out once per level
+    let curve: Vec<u8> = (0..=255).map(|value| adjust_tone(value, settings)).collect();
+    let mut output = RgbaImage::new(image.width(), image.height());
+    let row_len = image.width() as usize;
+    match settings.color_mode {
+        DitherColorMode::Grayscale => {
+            let gray = settings.luminance.to_luma8(image);
+            let gray = if settings.blur > 0.0 { image::imageops::blur(&gray, settings.blur) } else { gray };
+            output.par_chunks_mut(row_len * 4).zip(gray.par_chunks(row_len)).for_each(|(row, source)| {
+                for (pixel, &value) in row.chunks_exact_mut(4).zip(source) {
+                    let value = curve[value as usize];
+                    pixel.copy_from_slice(&[value, value, value, 255]);
+                }
+            });
+        }
+        DitherColorMode::Rgb => {
+            let rgb = image.to_rgb8();
+            let rgb = if settings.blur > 0.0 { image::imageops::blur(&rgb, settings.blur) } else { rgb };
+            output.par_chunks_mut(row_len * 4).zip(rgb.par_chunks(row_len * 3)).for_each(|(row, source)| {
+                for (pixel, color) in row.chunks_exact_mut(4).zip(source.chunks_exact(3)) {
+                    pixel.copy_from_slice(&[curve[color[0] as usize], curve[color[1] as usize], curve[color[2] as usize], 255]);
+                }
+            });
         }
     }
+    output
 }
 
-fn ordered_dither(img: &mut RgbaImage, settings: &DitherSettings) {
-    let bayer_matrix = [
-        [0, 8, 2, 10],
-        [12, 4, 14, 6],
-        [3, 11, 1, 9],
-        [15, 7, 13, 5],
-    ];
-    
-    let width = img.width();
-    let height = img.height();
-    
-    for y in 0..height {
-        for x in 0..width {
-            let pixel = img.get_pixel(x, y);
-            let threshold = (bayer_matrix[(y % 4) as usize][(x % 4) as usize] as f32 / 16.0 - 0.5) * 255.0 / (settings.color_levels as f32);
-            let new_gray = quantize_gray((pixel[0] as f32 + threshold) as u8, settings.color_levels);
-            img.put_pixel(x, y, Rgba([new_gray, new_gray, new_gray, 255]));
+// Averages each `scale`-pixel square, those along the right and bottom edges cut short
+fn block_average(img: &RgbaImage, scale: u32) -> RgbaImage {
+    let (width, height) = img.dimensions();
+    RgbaImage::from_fn(width.div_ceil(scale), height.div_ceil(scale), |bx, by| {
+        let (left, top) = (bx * scale, by * scale);
+        let (right, bottom) = ((left + scale).min(width), (top + scale).min(height));
+        let mut sum = [0u32; 3];
+        for y in top..bottom {
+            for x in left..right {
+                let pixel = img.get_pixel(x, y);
+                for c in 0..3 {
+                    sum[c] += pixel[c] as u32;
+                }
+            }
+        }
+        let count = (right - left) * (bottom - top);
+        let [r, g, b] = sum.map(|total| ((total + count / 2) / count) as u8);
+        Rgba([r, g, b, 255])
+    })
+}
+
+// Rows on either side that influence a pixel under `image::imageops::blur`, plus one
+// for safety; mirrors the kernel size the image crate derives from sigma
+fn blur_reach(sigma: f32) -> u32 {
+    let kernel_size = (((sigma - 0.8) / 0.3 + 1.0) * 2.0 + 1.0).max(3.0) as u32;
+    kernel_size / 2 + 1
+}
+
+// Contrast, midtone, highlight and shadow adjustments applied before dithering
+fn adjust_tone(gray: u8, settings: &DitherSettings) -> u8 {
+    // At least one level wide, so equal points make a hard cut instead of dividing by zero
+    let range = (settings.white_point - settings.black_point).max(1.0);
+    let gray = ((gray as f32 - settings.black_point) / range).clamp(0.0, 1.0);
+    let contrasted = ((gray - 0.5) * settings.contrast + 0.5).clamp(0.0, 1.0);
+
+    let midtone_adjusted = if contrasted < 0.5 {
+        contrasted * (1.0 + settings.midtones)
+    } else {
+        contrasted + (1.0 - contrasted) * settings.midtones
+    };
+
+    let highlight_adjusted = if midtone_adjusted > 0.5 {
+        midtone_adjusted * settings.highlights
+    } else {
+        midtone_adjusted
+    };
+
+    // Rounded, so that neutral settings give back exactly the gray they were given
+    let value = highlight_adjusted * 255.0;
+    if value < settings.luminance_threshold {
+        (value * 0.5).round() as u8
+    } else {
+        value.round().clamp(0.0, 255.0) as u8
+    }
+}
+
+// Moves a dithered row of raw RGBA bytes back towards the `smooth` row it was dithered
+// from, keeping `strength` of the difference
+fn blend_row(row: &mut [u8], smooth: &[u8], strength: f32) {
+    for (value, &source) in row.iter_mut().zip(smooth) {
+        *value = (source as f32 + (*value as f32 - source as f32) * strength).round() as u8;
+    }
+}
+
+// Redraws a dithered row of raw RGBA bytes from black..white to custom_black..custom_white
+fn apply_custom_colors(row: &mut [u8], settings: &DitherSettings) {
+    let (black, white) = (settings.custom_black, settings.custom_white);
+    if settings.palette.is_some() || (black == [0, 0, 0] && white == [255, 255, 255]) {
+        return;
+    }
+    for pixel in row.chunks_exact_mut(4) {
+        for c in 0..3 {
+            let t = pixel[c] as f32 / 255.0;
+            pixel[c] = (black[c] as f32 + (white[c] as f32 - black[c] as f32) * t).round() as u8;
         }
     }
 }
 
-fn threshold_dither(img: &mut RgbaImage, settings: &DitherSettings) {
-    let width = img.width();
-    let height = img.height();
-    
-    for y in 0..height {
-        for x in 0..width {
-            let pixel = img.get_pixel(x, y);
-            let gray = pixel[0];
-            let value = if gray as f32 > settings.threshold { 255 } else { 0 };
-            img.put_pixel(x, y, Rgba([value, value, value, 255]));
+// Dithers one row of raw RGBA bytes in place with a point method
+fn point_dither_row(row: &mut [u8], y: u32, settings: &DitherSettings, palette: Option<&PaletteMatcher>) {
+    for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+        let [r, g, b] = point_dither_pixel([pixel[0], pixel[1], pixel[2]], x as u32, y, settings, palette);
+        pixel.copy_from_slice(&[r, g, b, 255]);
+    }
+}
+
+// A point method on a whole pixel: each channel on its own, or onto the palette if there
+// is one. Ordered dithering spreads by the palette's size; the other methods snap their
+// result to the nearest entry.
+fn point_dither_pixel(color: [u8; 3], x: u32, y: u32, settings: &DitherSettings, palette: Option<&PaletteMatcher>) -> [u8; 3] {
+    if settings.algorithm == DitherAlgorithm::HalftoneCmyk {
+        return cmyk_halftone(color, x, y, settings.halftone_size.max(1) as f32);
+    }
+    match (palette, threshold_map(&settings.algorithm, x, y)) {
+        (None, _) => color.map(|c| point_dither(c, x, y, settings)),
+        (Some(palette), Some(level)) => {
+            let spread = (level - 0.5) * 255.0 / palette.colors.len().max(2) as f32;
+            palette.nearest(color.map(|c| (c as f32 + spread).clamp(0.0, 255.0) as u8))
         }
+        (Some(palette), None) => palette.nearest(color.map(|c| point_dither(c, x, y, settings))),
     }
 }
 
-fn scanline_dither(img: &mut RgbaImage, settings: &DitherSettings) {
-    let width = img.width();
-    let height = img.height();
-    
-    for y in 0..height {
-        for x in 0..width {
-            let pixel = img.get_pixel(x, y);
-            let gray = pixel[0];
-            let value = if y % 2 == 0 {
-                quantize_gray(gray, settings.color_levels)
-            } else {
-                quantize_gray(255 - gray, settings.color_levels)
+// The threshold of ordered methods at (x, y), in 0..1
+fn threshold_map(algorithm: &DitherAlgorithm, x: u32, y: u32) -> Option<f32> {
+    match algorithm {
+        DitherAlgorithm::Ordered | DitherAlgorithm::YliluomaOrdered => Some(BAYER_4X4[(y % 4) as usize][(x % 4) as usize] as f32 / 16.0),
+        DitherAlgorithm::BlueNoise => Some(BLUE_NOISE[(y as usize % BLUE_NOISE_SIZE) * BLUE_NOISE_SIZE + x as usize % BLUE_NOISE_SIZE]),
+        _ => None,
+    }
+}
+
+// The palette pixels are matched against, with its entries in Lab when matching in Lab
+struct PaletteMatcher<'a> {
+    colors: &'a [[u8; 3]],
+    lab: Option<Vec<(f32, f32, f32)>>,
+}
+
+impl<'a> PaletteMatcher<'a> {
+    // None without a palette; an empty palette counts as none
+    fn new(settings: &'a DitherSettings) -> Option<Self> {
+        let colors = settings.palette.as_deref().filter(|palette| !palette.is_empty())?;
+        let lab = (settings.palette_distance == PaletteDistance::Lab).then(|| colors.iter().map(|&color| lab(color)).collect());
+        Some(Self { colors, lab })
+    }
+
+    fn nearest(&self, color: [u8; 3]) -> [u8; 3] {
+        let Some(entries) = &self.lab else { return nearest_color(self.colors, color) };
+        let (l, a, b) = lab(color);
+        let distance = |&(el, ea, eb): &(f32, f32, f32)| (el - l).powi(2) + (ea - a).powi(2) + (eb - b).powi(2);
+        let best = (0..entries.len()).min_by(|&i, &j| distance(&entries[i]).total_cmp(&distance(&entries[j]))).unwrap_or(0);
+        self.colors[best]
+    }
+}
+
+fn lab(color: [u8; 3]) -> (f32, f32, f32) {
+    let [r, g, b] = color.map(|c| c as f32 / 255.0);
+    rgb_to_lab(r, g, b)
+}
+
+// Methods whose output at (x, y) depends only on the gray value there
+fn point_dither(gray: u8, x: u32, y: u32, settings: &DitherSettings) -> u8 {
+    match settings.algorithm {
+        DitherAlgorithm::Ordered | DitherAlgorithm::BlueNoise | DitherAlgorithm::YliluomaOrdered => {
+            // Spread over one whole quantization step, so there are as many dither patterns
+            // between each pair of levels at any level count
+            let level = threshold_map(&settings.algorithm, x, y).unwrap_or(0.5);
+            let step = 255.0 / (settings.color_levels.max(2) - 1) as f32;
+            let perturbed = (gray as f32 + (level - 0.5) * step).round().clamp(0.0, 255.0);
+            quantize_channel(perturbed as u8, settings.color_levels)
+        }
+        DitherAlgorithm::Scanline => {
+            let position = match settings.scanline_orientation {
+                ScanlineOrientation::Horizontal => y,
+                ScanlineOrientation::Vertical => x,
+            };
+            let band = position.wrapping_add(settings.scanline_phase) / settings.scanline_thickness.clamp(1, 8);
+            let gray = match settings.scanline_mode {
+                _ if band.is_multiple_of(2) => gray,
+                ScanlineMode::Invert => 255 - gray,
+                ScanlineMode::Darken => gray / 2,
             };
-            img.put_pixel(x, y, Rgba([value, value, value, 255]));
+            quantize_channel(gray, settings.color_levels)
+        }
+        DitherAlgorithm::Pattern => {
+            let size = settings.pattern.len();
+            let threshold = (settings.pattern[y as usize % size][x as usize % size] as f32 / (size * size) as f32) * 255.0;
+            if gray as f32 > threshold { 255 } else { 0 }
+        }
+        DitherAlgorithm::Random => {
+            let random = hash_noise(x as i32, y as i32, settings.seed) * 255.0;
+            if gray as f32 > random { 255 } else { 0 }
+        }
+        DitherAlgorithm::Halftone => {
+            let dot_size = settings.halftone_size.max(1);
+            let cell_x = x % dot_size;
+            let cell_y = y % dot_size;
+            let dist = ((cell_x as f32 - dot_size as f32 / 2.0).powi(2) +
+                       (cell_y as f32 - dot_size as f32 / 2.0).powi(2)).sqrt();
+            let threshold = (dist / (dot_size as f32 / 2.0)) * 255.0;
+            if gray as f32 > threshold { 255 } else { 0 }
         }
+        DitherAlgorithm::Threshold => {
+            if gray as f32 > settings.threshold { 255 } else { 0 }
+        }
+        // Error-diffusion methods are handled on the whole image
+        _ => gray,
     }
 }
 
-fn pattern_dither(img: &mut RgbaImage, _settings: &DitherSettings) {
-    let pattern = [
-        [0, 2],
-        [3, 1],
-    ];
-    
-    let width = img.width();
-    let height = img.height();
-    
-    for y in 0..height {
-        for x in 0..width {
-            let pixel = img.get_pixel(x, y);
-            let threshold = (pattern[(y % 2) as usize][(x % 2) as usize] as f32 / 4.0) * 255.0;
-            let value = if pixel[0] as f32 > threshold { 255 } else { 0 };
-            img.put_pixel(x, y, Rgba([value, value, value, 255]));
+// Screen angles in degrees of the cyan, magenta, yellow and black plates, as in print
+const CMYK_SCREEN_ANGLES: [f32; 4] = [15.0, 75.0, 0.0, 45.0];
+
+// Separates `color` into inks, screens each on its own rotated grid of `cell`-pixel dots
+// and multiplies the inked plates back together
+fn cmyk_halftone(color: [u8; 3], x: u32, y: u32, cell: f32) -> [u8; 3] {
+    let [r, g, b] = color.map(|c| c as f32 / 255.0);
+    let k = 1.0 - r.max(g).max(b);
+    let ink = |c: f32| if k < 1.0 { (1.0 - c - k) / (1.0 - k) } else { 0.0 };
+    let coverage = [ink(r), ink(g), ink(b), k];
+
+    let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+    let inked = [0, 1, 2, 3].map(|plate| {
+        let (sin, cos) = CMYK_SCREEN_ANGLES[plate].to_radians().sin_cos();
+        let u = (px * cos + py * sin) / cell;
+        let v = (py * cos - px * sin) / cell;
+        // Squared distance from the centre of the dot's cell, 0 there and 0.5 at the
+        // corners, so a dot grows from nothing at no ink to the whole cell at full ink
+        let (du, dv) = (u - u.floor() - 0.5, v - v.floor() - 0.5);
+        coverage[plate] > 2.0 * (du * du + dv * dv)
+    });
+    let black = if inked[3] { 0.0 } else { 1.0 };
+    [0, 1, 2].map(|c| if inked[c] { 0 } else { (255.0 * black) as u8 })
+}
+
+// Side of the blue-noise tile; it wraps around, so repeats meet without a seam
+const BLUE_NOISE_SIZE: usize = 64;
+
+// Thresholds in 0..1 for every cell of the tile, each used once
+static BLUE_NOISE: LazyLock<Vec<f32>> = LazyLock::new(void_and_cluster);
+
+// Ulichney's void-and-cluster method: points are ranked by repeatedly taking the one in
+// the tightest cluster away, or filling the largest void, as measured by a Gaussian of
+// the wrapped distance to every other point
+fn void_and_cluster() -> Vec<f32> {
+    const SIZE: usize = BLUE_NOISE_SIZE;
+    const CELLS: usize = SIZE * SIZE;
+    const SIGMA: f32 = 1.5;
+
+    let kernel: Vec<f32> = (0..CELLS)
+        .map(|i| {
+            let wrapped = |d: usize| d.min(SIZE - d) as f32;
+            let (dx, dy) = (wrapped(i % SIZE), wrapped(i / SIZE));
+            (-(dx * dx + dy * dy) / (2.0 * SIGMA * SIGMA)).exp()
+        })
+        .collect();
+    let toggle = |energy: &mut [f32], points: &mut [bool], i: usize| {
+        points[i] = !points[i];
+        let sign = if points[i] { 1.0 } else { -1.0 };
+        let (x, y) = (i % SIZE, i / SIZE);
+        for (j, e) in energy.iter_mut().enumerate() {
+            let (dx, dy) = ((j % SIZE + SIZE - x) % SIZE, (j / SIZE + SIZE - y) % SIZE);
+            *e += sign * kernel[dy * SIZE + dx];
+        }
+    };
+    let tightest_cluster = |energy: &[f32], points: &[bool]| {
+        (0..CELLS).filter(|&i| points[i]).max_by(|&a, &b| energy[a].total_cmp(&energy[b])).unwrap_or(0)
+    };
+    let largest_void = |energy: &[f32], points: &[bool]| {
+        (0..CELLS).filter(|&i| !points[i]).min_by(|&a, &b| energy[a].total_cmp(&energy[b])).unwrap_or(0)
+    };
+
+    // A sparse random start, relaxed until moving its tightest point would not help
+    let mut energy = vec![0.0f32; CELLS];
+    let mut points = vec![false; CELLS];
+    for i in 0..CELLS {
+        if hash_noise((i % SIZE) as i32, (i / SIZE) as i32, 0) < 0.1 {
+            toggle(&mut energy, &mut points, i);
+        }
+    }
+    for _ in 0..CELLS {
+        let cluster = tightest_cluster(&energy, &points);
+        toggle(&mut energy, &mut points, cluster);
+        let void = largest_void(&energy, &points);
+        toggle(&mut energy, &mut points, void);
+        if void == cluster {
+            break;
         }
     }
+
+    let mut ranks = vec![0usize; CELLS];
+    let initial = points.iter().filter(|&&p| p).count();
+    let (mut removing, mut removing_points) = (energy.clone(), points.clone());
+    for rank in (0..initial).rev() {
+        let cluster = tightest_cluster(&removing, &removing_points);
+        toggle(&mut removing, &mut removing_points, cluster);
+        ranks[cluster] = rank;
+    }
+    for rank in initial..CELLS {
+        let void = largest_void(&energy, &points);
+        toggle(&mut energy, &mut points, void);
+        ranks[void] = rank;
+    }
+    ranks.into_iter().map(|rank| (rank as f32 + 0.5) / CELLS as f32).collect()
+}
+
+// Two palette entries and how many of the 16 Bayer cells take the second
+#[derive(Clone, Copy)]
+struct MixingPlan {
+    first: usize,
+    second: usize,
+    ratio: u8,
+}
+
+// Yliluoma's algorithm 1: every pair of entries is tried at every ratio the Bayer matrix
+// can show, and the mix closest to `color` wins, with a penalty for mixing entries far
+// apart so that close ones interleave into a finer grain
+fn mixing_plan(color: [u8; 3], palette: &[[u8; 3]]) -> MixingPlan {
+    let mut best = (f32::INFINITY, MixingPlan { first: 0, second: 0, ratio: 0 });
+    for first in 0..palette.len() {
+        for second in first..palette.len() {
+            let spread = mixing_difference(palette[first], palette[second]);
+            let ratios = if first == second { 0..=0 } else { 0..=16 };
+            for ratio in ratios {
+                let share = ratio as f32 / 16.0;
+                let mix = [0, 1, 2].map(|c| palette[first][c] as f32 + (palette[second][c] as f32 - palette[first][c] as f32) * share);
+                let penalty = mixing_difference_f32(color.map(|c| c as f32), mix) + spread * 0.1 * ((share - 0.5).abs() + 0.5);
+                if penalty < best.0 {
+                    best = (penalty, MixingPlan { first, second, ratio });
+                }
+            }
+        }
+    }
+    best.1
+}
+
+fn mixing_difference(a: [u8; 3], b: [u8; 3]) -> f32 {
+    mixing_difference_f32(a.map(|c| c as f32), b.map(|c| c as f32))
+}
+
+// Yliluoma's colour distance: weighted RGB plus the difference in luma, so mixes keep
+// their brightness
+fn mixing_difference_f32(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let luma = |[r, g, b]: [f32; 3]| (r * 0.299 + g * 0.587 + b * 0.114) / 255.0;
+    let [dr, dg, db] = [0, 1, 2].map(|c| (a[c] - b[c]) / 255.0);
+    (dr * dr * 0.299 + dg * dg * 0.587 + db * db * 0.114) * 0.75 + (luma(a) - luma(b)).powi(2)
+}
+
+// Plans a mix once per distinct colour of `img`, then lays each one out over the Bayer
+// matrix row by row
+fn yliluoma_dither(img: &mut RgbaImage, palette: &[[u8; 3]], progress: &Progress) {
+    let colors: HashSet<[u8; 3]> = img.pixels().map(|pixel| [pixel[0], pixel[1], pixel[2]]).collect();
+    let plans: HashMap<[u8; 3], MixingPlan> = colors.into_par_iter().map(|color| (color, mixing_plan(color, palette))).collect();
+    let (row_len, height) = (img.width() as usize * 4, img.height() as usize);
+    let rows = RowProgress { progress, done: AtomicUsize::new(0), total: height };
+    img.par_chunks_mut(row_len).enumerate().for_each(|(y, row)| {
+        if progress.is_cancelled() {
+            return;
+        }
+        for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+            let plan = plans[&[pixel[0], pixel[1], pixel[2]]];
+            let entry = if BAYER_4X4[y % 4][x % 4] < plan.ratio { plan.second } else { plan.first };
+            let [r, g, b] = palette[entry];
+            pixel.copy_from_slice(&[r, g, b, 255]);
+        }
+        rows.tick();
+    });
+    if !progress.is_cancelled() {
+        progress.report(height, height);
+    }
+}
+
+fn quantize_channel(value: u8, levels: u8) -> u8 {
+    let step = 255.0 / (levels.max(2) - 1) as f32;
+    // Rounded rather than truncated, as steps like 255/254 land just short of a whole level
+    ((value as f32 / step).round() * step).round() as u8
 }
 
-fn random_dither(img: &mut RgbaImage, _settings: &DitherSettings) {
-    let width = img.width();
-    let height = img.height();
+fn floyd_steinberg_dither(img: &mut RgbaImage, settings: &DitherSettings, progress: &Progress) {
+    let width = img.width() as i32;
+    let height = img.height() as i32;
+    let palette = PaletteMatcher::new(settings);
+    let mut buffer = ErrorBuffer::new(img, settings);
     
-    for y in 0..height {
+    for y in progress.rows(height as u32).map(|y| y as i32) {
         for x in 0..width {
-            let pixel = img.get_pixel(x, y);
-            let random = ((x.wrapping_mul(1664525).wrapping_add(y.wrapping_mul(1013904223))) % 256) as f32;
-            let value = if pixel[0] as f32 > random { 255 } else { 0 };
-            img.put_pixel(x, y, Rgba([value, value, value, 255]));
+            let err = quantize_pixel(&mut buffer, x, y, settings, palette.as_ref());
+            
+            distribute_error(&mut buffer, x + 1, y, err, 7.0 / 16.0);
+            distribute_error(&mut buffer, x - 1, y + 1, err, 3.0 / 16.0);
+            distribute_error(&mut buffer, x, y + 1, err, 5.0 / 16.0);
+            distribute_error(&mut buffer, x + 1, y + 1, err, 1.0 / 16.0);
         }
     }
+    buffer.write_to(img);
 }
 
-fn halftone_dither(img: &mut RgbaImage, _settings: &DitherSettings) {
-    let width = img.width();
-    let height = img.height();
-    let dot_size = 4;
+fn atkinson_dither(img: &mut RgbaImage, settings: &DitherSettings, progress: &Progress) {
+    let width = img.width() as i32;
+    let height = img.height() as i32;
+    let palette = PaletteMatcher::new(settings);
+    let mut buffer = ErrorBuffer::new(img, settings);
     
-    for y in 0..height {
+    for y in progress.rows(height as u32).map(|y| y as i32) {
         for x in 0..width {
-            let pixel = img.get_pixel(x, y);
-            let cell_x = x % dot_size;
-            let cell_y = y % dot_size;
-            let dist = ((cell_x as f32 - dot_size as f32 / 2.0).powi(2) + 
-                       (cell_y as f32 - dot_size as f32 / 2.0).powi(2)).sqrt();
+            let err = quantize_pixel(&mut buffer, x, y, settings, palette.as_ref());
+            let factor = 1.0 / 8.0;
             
-            let threshold = (dist / (dot_size as f32 / 2.0)) * 255.0;
-            let value = if pixel[0] as f32 > threshold { 255 } else { 0 };
-            img.put_pixel(x, y, Rgba([value, value, value, 255]));
+            distribute_error(&mut buffer, x + 1, y, err, factor);
+            distribute_error(&mut buffer, x + 2, y, err, factor);
+            distribute_error(&mut buffer, x - 1, y + 1, err, factor);
+            distribute_error(&mut buffer, x, y + 1, err, factor);
+            distribute_error(&mut buffer, x + 1, y + 1, err, factor);
+            distribute_error(&mut buffer, x, y + 2, err, factor);
         }
     }
+    buffer.write_to(img);
 }
 
-fn jarvis_dither(img: &mut RgbaImage, settings: &DitherSettings) {
+fn jarvis_dither(img: &mut RgbaImage, settings: &DitherSettings, progress: &Progress) {
     let width = img.width() as i32;
     let height = img.height() as i32;
+    let palette = PaletteMatcher::new(settings);
+    let mut buffer = ErrorBuffer::new(img, settings);
     
-    for y in 0..height {
+    for y in progress.rows(height as u32).map(|y| y as i32) {
         for x in 0..width {
-            let pixel = img.get_pixel(x as u32, y as u32);
-            let old_gray = pixel[0];
-            let new_gray = quantize_gray(old_gray, settings.color_levels);
-            
-            img.put_pixel(x as u32, y as u32, Rgba([new_gray, new_gray, new_gray, 255]));
-            
-            let err = old_gray as i32 - new_gray as i32;
+            let err = quantize_pixel(&mut buffer, x, y, settings, palette.as_ref());
             let factor = 1.0 / 48.0;
             
-            distribute_error_gray(img, x + 1, y, err, 7.0 * factor);
-            distribute_error_gray(img, x + 2, y, err, 5.0 * factor);
-            distribute_error_gray(img, x - 2, y + 1, err, 3.0 * factor);
-            distribute_error_gray(img, x - 1, y + 1, err, 5.0 * factor);
-            distribute_error_gray(img, x, y + 1, err, 7.0 * factor);
-            distribute_error_gray(img, x + 1, y + 1, err, 5.0 * factor);
-            distribute_error_gray(img, x + 2, y + 1, err, 3.0 * factor);
-            distribute_error_gray(img, x - 2, y + 2, err, 1.0 * factor);
-            distribute_error_gray(img, x - 1, y + 2, err, 3.0 * factor);
-            distribute_error_gray(img, x, y + 2, err, 5.0 * factor);
-            distribute_error_gray(img, x + 1, y + 2, err, 3.0 * factor);
-            distribute_error_gray(img, x + 2, y + 2, err, 1.0 * factor);
+            distribute_error(&mut buffer, x + 1, y, err, 7.0 * factor);
+            distribute_error(&mut buffer, x + 2, y, err, 5.0 * factor);
+            distribute_error(&mut buffer, x - 2, y + 1, err, 3.0 * factor);
+            distribute_error(&mut buffer, x - 1, y + 1, err, 5.0 * factor);
+            distribute_error(&mut buffer, x, y + 1, err, 7.0 * factor);
+            distribute_error(&mut buffer, x + 1, y + 1, err, 5.0 * factor);
+            distribute_error(&mut buffer, x + 2, y + 1, err, 3.0 * factor);
+            distribute_error(&mut buffer, x - 2, y + 2, err, 1.0 * factor);
+            distribute_error(&mut buffer, x - 1, y + 2, err, 3.0 * factor);
+            distribute_error(&mut buffer, x, y + 2, err, 5.0 * factor);
+            distribute_error(&mut buffer, x + 1, y + 2, err, 3.0 * factor);
+            distribute_error(&mut buffer, x + 2, y + 2, err, 1.0 * factor);
         }
     }
+    buffer.write_to(img);
 }
 
-fn stucki_dither(img: &mut RgbaImage, settings: &DitherSettings) {
+fn stucki_dither(img: &mut RgbaImage, settings: &DitherSettings, progress: &Progress) {
     let width = img.width() as i32;
     let height = img.height() as i32;
+    let palette = PaletteMatcher::new(settings);
+    let mut buffer = ErrorBuffer::new(img, settings);
     
-    for y in 0..height {
+    for y in progress.rows(height as u32).map(|y| y as i32) {
         for x in 0..width {
-            let pixel = img.get_pixel(x as u32, y as u32);
-            let old_gray = pixel[0];
-            let new_gray = quantize_gray(old_gray, settings.color_levels);
-            
-            img.put_pixel(x as u32, y as u32, Rgba([new_gray, new_gray, new_gray, 255]));
-            
-            let err = old_gray as i32 - new_gray as i32;
+            let err = quantize_pixel(&mut buffer, x, y, settings, palette.as_ref());
             let factor = 1.0 / 42.0;
             
-            distribute_error_gray(img, x + 1, y, err, 8.0 * factor);
-            distribute_error_gray(img, x + 2, y, err, 4.0 * factor);
-            distribute_error_gray(img, x - 2, y + 1, err, 2.0 * factor);
-            distribute_error_gray(img, x - 1, y + 1, err, 4.0 * factor);
-            distribute_error_gray(img, x, y + 1, err, 8.0 * factor);
-            distribute_error_gray(img, x + 1, y + 1, err, 4.0 * factor);
-            distribute_error_gray(img, x + 2, y + 1, err, 2.0 * factor);
-            distribute_error_gray(img, x - 2, y + 2, err, 1.0 * factor);
-            distribute_error_gray(img, x - 1, y + 2, err, 2.0 * factor);
-            distribute_error_gray(img, x, y + 2, err, 4.0 * factor);
-            distribute_error_gray(img, x + 1, y + 2, err, 2.0 * factor);
-            distribute_error_gray(img, x + 2, y + 2, err, 1.0 * factor);
+            distribute_error(&mut buffer, x + 1, y, err, 8.0 * factor);
+            distribute_error(&mut buffer, x + 2, y, err, 4.0 * factor);
+            distribute_error(&mut buffer, x - 2, y + 1, err, 2.0 * factor);
+            distribute_error(&mut buffer, x - 1, y + 1, err, 4.0 * factor);
+            distribute_error(&mut buffer, x, y + 1, err, 8.0 * factor);
+            distribute_error(&mut buffer, x + 1, y + 1, err, 4.0 * factor);
+            distribute_error(&mut buffer, x + 2, y + 1, err, 2.0 * factor);
+            distribute_error(&mut buffer, x - 2, y + 2, err, 1.0 * factor);
+            distribute_error(&mut buffer, x - 1, y + 2, err, 2.0 * factor);
+            distribute_error(&mut buffer, x, y + 2, err, 4.0 * factor);
+            distribute_error(&mut buffer, x + 1, y + 2, err, 2.0 * factor);
+            distribute_error(&mut buffer, x + 2, y + 2, err, 1.0 * factor);
         }
     }
+    buffer.write_to(img);
 }
 
-fn burkes_dither(img: &mut RgbaImage, settings: &DitherSettings) {
+fn burkes_dither(img: &mut RgbaImage, settings: &DitherSettings, progress: &Progress) {
     let width = img.width() as i32;
     let height = img.height() as i32;
+    let palette = PaletteMatcher::new(settings);
+    let mut buffer = ErrorBuffer::new(img, settings);
     
-    for y in 0..height {
+    for y in progress.rows(height as u32).map(|y| y as i32) {
         for x in 0..width {
-            let pixel = img.get_pixel(x as u32, y as u32);
-            let old_gray = pixel[0];
-            let new_gray = quantize_gray(old_gray, settings.color_levels);
-            
-            img.put_pixel(x as u32, y as u32, Rgba([new_gray, new_gray, new_gray, 255]));
-            
-            let err = old_gray as i32 - new_gray as i32;
+            let err = quantize_pixel(&mut buffer, x, y, settings, palette.as_ref());
             let factor = 1.0 / 32.0;
             
-            distribute_error_gray(img, x + 1, y, err, 8.0 * factor);
-            distribute_error_gray(img, x + 2, y, err, 4.0 * factor);
-            distribute_error_gray(img, x - 2, y + 1, err, 2.0 * factor);
-            distribute_error_gray(img, x - 1, y + 1, err, 4.0 * factor);
-            distribute_error_gray(img, x, y + 1, err, 8.0 * factor);
-            distribute_error_gray(img, x + 1, y + 1, err, 4.0 * factor);
-            distribute_error_gray(img, x + 2, y + 1, err, 2.0 * factor);
+            distribute_error(&mut buffer, x + 1, y, err, 8.0 * factor);
+            distribute_error(&mut buffer, x + 2, y, err, 4.0 * factor);
+            distribute_error(&mut buffer, x - 2, y + 1, err, 2.0 * factor);
+            distribute_error(&mut buffer, x - 1, y + 1, err, 4.0 * factor);
+            distribute_error(&mut buffer, x, y + 1, err, 8.0 * factor);
+            distribute_error(&mut buffer, x + 1, y + 1, err, 4.0 * factor);
+            distribute_error(&mut buffer, x + 2, y + 1, err, 2.0 * factor);
         }
     }
+    buffer.write_to(img);
 }
 
-fn sierra_dither(img: &mut RgbaImage, settings: &DitherSettings) {
+fn sierra_dither(img: &mut RgbaImage, settings: &DitherSettings, progress: &Progress) {
     let width = img.width() as i32;
     let height = img.height() as i32;
+    let palette = PaletteMatcher::new(settings);
+    let mut buffer = ErrorBuffer::new(img, settings);
     
-    for y in 0..height {
+    for y in progress.rows(height as u32).map(|y| y as i32) {
         for x in 0..width {
-            let pixel = img.get_pixel(x as u32, y as u32);
-            let old_gray = pixel[0];
-            let new_gray = quantize_gray(old_gray, settings.color_levels);
+            let err = quantize_pixel(&mut buffer, x, y, settings, palette.as_ref());
+            let factor = 1.0 / 32.0;
             
-            img.put_pixel(x as u32, y as u32, Rgba([new_gray, new_gray, new_gray, 255]));
+            distribute_error(&mut buffer, x + 1, y, err, 5.0 * factor);
+            distribute_error(&mut buffer, x + 2, y, err, 3.0 * factor);
+            distribute_error(&mut buffer, x - 2, y + 1, err, 2.0 * factor);
+            distribute_error(&mut buffer, x - 1, y + 1, err, 4.0 * factor);
+            distribute_error(&mut buffer, x, y + 1, err, 5.0 * factor);
+            distribute_error(&mut buffer, x + 1, y + 1, err, 4.0 * factor);
+            distribute_error(&mut buffer, x + 2, y + 1, err, 2.0 * factor);
+            distribute_error(&mut buffer, x - 1, y + 2, err, 2.0 * factor);
+            distribute_error(&mut buffer, x, y + 2, err, 3.0 * factor);
+            distribute_error(&mut buffer, x + 1, y + 2, err, 2.0 * factor);
+        }
+    }
+    buffer.write_to(img);
+}
+
+fn sierra_two_row_dither(img: &mut RgbaImage, settings: &DitherSettings, progress: &Progress) {
+    let width = img.width() as i32;
+    let height = img.height() as i32;
+    let palette = PaletteMatcher::new(settings);
+    let mut buffer = ErrorBuffer::new(img, settings);
+    
+    for y in progress.rows(height as u32).map(|y| y as i32) {
+        for x in 0..width {
+            let err = quantize_pixel(&mut buffer, x, y, settings, palette.as_ref());
+            let factor = 1.0 / 16.0;
             
-            let err = old_gray as i32 - new_gray as i32;
-            let factor = 1.0 / 32.0;
+            distribute_error(&mut buffer, x + 1, y, err, 4.0 * factor);
+            distribute_error(&mut buffer, x + 2, y, err, 3.0 * factor);
+            distribute_error(&mut buffer, x - 2, y + 1, err, 1.0 * factor);
+            distribute_error(&mut buffer, x - 1, y + 1, err, 2.0 * factor);
+            distribute_error(&mut buffer, x, y + 1, err, 3.0 * factor);
+            distribute_error(&mut buffer, x + 1, y + 1, err, 2.0 * factor);
+            distribute_error(&mut buffer, x + 2, y + 1, err, 1.0 * factor);
+        }
+    }
+    buffer.write_to(img);
+}
+
+fn sierra_lite_dither(img: &mut RgbaImage, settings: &DitherSettings, progress: &Progress) {
+    let width = img.width() as i32;
+    let height = img.height() as i32;
+    let palette = PaletteMatcher::new(settings);
+    let mut buffer = ErrorBuffer::new(img, settings);
+    
+    for y in progress.rows(height as u32).map(|y| y as i32) {
+        for x in 0..width {
+            let err = quantize_pixel(&mut buffer, x, y, settings, palette.as_ref());
+            let factor = 1.0 / 4.0;
             
-            distribute_error_gray(img, x + 1, y, err, 5.0 * factor);
-            distribute_error_gray(img, x + 2, y, err, 3.0 * factor);
-            distribute_error_gray(img, x - 2, y + 1, err, 2.0 * factor);
-            distribute_error_gray(img, x - 1, y + 1, err, 4.0 * factor);
-            distribute_error_gray(img, x, y + 1, err, 5.0 * factor);
-            distribute_error_gray(img, x + 1, y + 1, err, 4.0 * factor);
-            distribute_error_gray(img, x + 2, y + 1, err, 2.0 * factor);
-            distribute_error_gray(img, x - 1, y + 2, err, 2.0 * factor);
-            distribute_error_gray(img, x, y + 2, err, 3.0 * factor);
-            distribute_error_gray(img, x + 1, y + 2, err, 2.0 * factor);
+            distribute_error(&mut buffer, x + 1, y, err, 2.0 * factor);
+            distribute_error(&mut buffer, x - 1, y + 1, err, factor);
+            distribute_error(&mut buffer, x, y + 1, err, factor);
         }
     }
+    buffer.write_to(img);
 }
 
-fn distribute_error_gray(img: &mut RgbaImage, x: i32, y: i32, err: i32, factor: f32) {
-    if x >= 0 && x < img.width() as i32 && y >= 0 && y < img.height() as i32 {
-        let pixel = img.get_pixel(x as u32, y as u32);
-        let new_gray = (pixel[0] as i32 + (err as f32 * factor) as i32).clamp(0, 255) as u8;
-        img.put_pixel(x as u32, y as u32, Rgba([new_gray, new_gray, new_gray, 255]));
+// The image being error-diffused, in floats: fractional error is carried in full and
+// values may leave 0..255 until the result is written back
+struct ErrorBuffer {
+    width: i32,
+    height: i32,
+    values: Vec<[f32; 3]>,
+    // Pixels on strong edges, which neither take nor pass on error; empty when
+    // edge_preserve is off
+    edges: Vec<bool>,
+    // Most error any one neighbour receives per channel, from error_clamp
+    error_limit: f32,
+}
+
+impl ErrorBuffer {
+    fn new(img: &RgbaImage, settings: &DitherSettings) -> Self {
+        let values = img.pixels().map(|pixel| [pixel[0] as f32, pixel[1] as f32, pixel[2] as f32]).collect();
+        let edges = if settings.edge_preserve > 0.0 { edge_mask(img, settings.edge_preserve.min(1.0)) } else { Vec::new() };
+        let error_limit = if settings.error_clamp < 1.0 { settings.error_clamp.max(0.0) * quantization_step(settings) } else { f32::INFINITY };
+        Self { width: img.width() as i32, height: img.height() as i32, values, edges, error_limit }
+    }
+
+    fn is_edge(&self, index: usize) -> bool {
+        self.edges.get(index).copied().unwrap_or(false)
     }
-}
\ No newline at end of file
+
+    fn write_to(&self, img: &mut RgbaImage) {
+        for (pixel, value) in img.pixels_mut().zip(&self.values) {
+            let [r, g, b] = value.map(|c| c.round().clamp(0.0, 255.0) as u8);
+            pixel.0 = [r, g, b, 255];
+        }
+    }
+}
+
+// Quantizes the pixel in place, to the palette or each channel to `color_levels`, and
+// returns what each channel lost
+fn quantize_pixel(buffer: &mut ErrorBuffer, x: i32, y: i32, settings: &DitherSettings, palette: Option<&PaletteMatcher>) -> [f32; 3] {
+    let index = (y * buffer.width + x) as usize;
+    let edge = buffer.is_edge(index);
+    let value = &mut buffer.values[index];
+    let old = value.map(|c| c.round().clamp(0.0, 255.0) as u8);
+    let new = match palette {
+        Some(palette) => palette.nearest(old),
+        None => old.map(|c| quantize_channel(c, settings.color_levels)),
+    };
+    // Levels always reach 0 and 255, so the error they leave stays small; a palette
+    // may not, and error towards colours it lacks would pile up without the clamp
+    let source = if palette.is_some() { old.map(|c| c as f32) } else { *value };
+    *value = new.map(|c| c as f32);
+    if edge {
+        return [0.0; 3];
+    }
+    [0, 1, 2].map(|c| source[c] - new[c] as f32)
+}
+
+// The gap between output levels; for a palette, whose entries are spaced unevenly, the
+// widest channel range it covers split evenly between its entries
+fn quantization_step(settings: &DitherSettings) -> f32 {
+    match settings.palette.as_deref().filter(|palette| palette.len() >= 2) {
+        Some(palette) => {
+            let range = (0..3).map(|c| {
+                let (min, max) = palette.iter().fold((255, 0), |(min, max), color| (color[c].min(min), color[c].max(max)));
+                max.saturating_sub(min)
+            });
+            range.max().unwrap_or(0) as f32 / (palette.len() - 1) as f32
+        }
+        None => 255.0 / (settings.color_levels.max(2) - 1) as f32,
+    }
+}
+
+fn distribute_error(buffer: &mut ErrorBuffer, x: i32, y: i32, err: [f32; 3], factor: f32) {
+    if x >= 0 && x < buffer.width && y >= 0 && y < buffer.height && !buffer.is_edge((y * buffer.width + x) as usize) {
+        let value = &mut buffer.values[(y * buffer.width + x) as usize];
+        for c in 0..3 {
+            value[c] += (err[c] * factor).clamp(-buffer.error_limit, buffer.error_limit);
+        }
+    }
+}
+
+// Which pixels have a Sobel gradient above `1 - preserve`, measured on the mean of the
+// channels and scaled so a full black-to-white step is 1
+fn edge_mask(img: &RgbaImage, preserve: f32) -> Vec<bool> {
+    let (width, height) = (img.width() as i32, img.height() as i32);
+    let gray: Vec<f32> = img.pixels().map(|pixel| (pixel[0] as f32 + pixel[1] as f32 + pixel[2] as f32) / 3.0).collect();
+    let at = |x: i32, y: i32| gray[(y.clamp(0, height - 1) * width + x.clamp(0, width - 1)) as usize];
+    let cutoff = (1.0 - preserve) * 4.0 * 255.0;
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let gx = at(x + 1, y - 1) + 2.0 * at(x + 1, y) + at(x + 1, y + 1) - at(x - 1, y - 1) - 2.0 * at(x - 1, y) - at(x - 1, y + 1);
+            let gy = at(x - 1, y + 1) + 2.0 * at(x, y + 1) + at(x + 1, y + 1) - at(x - 1, y - 1) - 2.0 * at(x, y - 1) - at(x + 1, y - 1);
+            gx.hypot(gy) > cutoff
+        })
+        .collect()
+}
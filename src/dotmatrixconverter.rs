@@ -0,0 +1,158 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+
+use crate::ditherconverter::BAYER_4X4;
+use crate::imageutils::hash_noise;
+
+const PAPER_WHITE: [f32; 3] = [252.0, 251.0, 246.0];
+const GREENBAR: [f32; 3] = [222.0, 238.0, 220.0];
+const FEED_HOLE: [f32; 3] = [60.0, 60.0, 62.0];
+
+#[derive(Clone, PartialEq)]
+pub struct DotMatrixSettings {
+    pub pin_size: f32,
+    pub band_height: u32,
+    pub ink_darkness: f32,
+    pub misregistration: f32,
+    pub ribbon: RibbonColor,
+    pub tractor_feed: bool,
+    pub seed: u64,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum RibbonColor {
+    Black,
+    Blue,
+}
+
+impl RibbonColor {
+    pub fn name(&self) -> &str {
+        match self {
+            RibbonColor::Black => "Black Ribbon",
+            RibbonColor::Blue => "Blue Ribbon",
+        }
+    }
+
+    fn ink(&self) -> [f32; 3] {
+        match self {
+            RibbonColor::Black => [28.0, 28.0, 32.0],
+            RibbonColor::Blue => [30.0, 42.0, 125.0],
+        }
+    }
+}
+
+impl Default for DotMatrixSettings {
+    fn default() -> Self {
+        Self {
+            pin_size: 4.0,
+            band_height: 9,
+            ink_darkness: 85.0,
+            misregistration: 1.0,
+            ribbon: RibbonColor::Black,
+            tractor_feed: false,
+            seed: 1,
+        }
+    }
+}
+
+/// Prints `image` as a dot-matrix printout: ink pins, banding and optional tractor-feed margins.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::dotmatrixconverter::{apply_dot_matrix, DotMatrixSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_dot_matrix(image, &DotMatrixSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_dot_matrix(image: DynamicImage, settings: &DotMatrixSettings) -> RgbaImage {
+    let rgba_img = image.to_rgba8();
+    let (width, height) = rgba_img.dimensions();
+    let pitch = settings.pin_size.max(2.0);
+    let band_height = settings.band_height.max(1);
+
+    // Tractor-feed paper loses a perforated strip down each side
+    let strip = if settings.tractor_feed { (width as f32 * 0.05).max(pitch * 3.0).min(width as f32 / 4.0) } else { 0.0 };
+    let print_left = strip;
+    let print_width = (width as f32 - 2.0 * strip).max(pitch);
+
+    // Each pin position samples the darkness of its cell in the printable area
+    let cols = (print_width / pitch).ceil() as u32;
+    let rows = (height as f32 / pitch).ceil() as u32;
+    let small = image::imageops::resize(&image.to_luma8(), cols.max(1), rows.max(1), image::imageops::FilterType::Triangle);
+
+    let ink = settings.ribbon.ink();
+    let radius = pitch * 0.45;
+    let darkness = settings.ink_darkness / 100.0;
+
+    // Per-pass ribbon strength and horizontal misregistration, seeded per band
+    let bands = rows.div_ceil(band_height) as usize;
+    let band_strength: Vec<f32> = (0..bands).map(|b| 0.8 + 0.2 * hash_noise(b as i32, 0, settings.seed)).collect();
+    let band_offset: Vec<f32> = (0..bands)
+        .map(|b| (hash_noise(b as i32, 1, settings.seed) - 0.5) * 2.0 * settings.misregistration)
+        .collect();
+
+    let fires = |col: i32, row: u32| -> bool {
+        if col < 0 || col as u32 >= cols || row >= rows {
+            return false;
+        }
+        let dark = 1.0 - small.get_pixel(col as u32, row)[0] as f32 / 255.0;
+        let threshold = (BAYER_4X4[(row % 4) as usize][(col % 4) as usize] as f32 + 0.5) / 16.0;
+        dark > threshold
+    };
+
+    let mut output = RgbaImage::new(width, height);
+    for (x, y, pixel) in rgba_img.enumerate_pixels() {
+        let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+        let paper = paper_color(px, py, width as f32, strip, pitch, settings.tractor_feed);
+
+        let mut coverage = 0.0f32;
+        let row = (py / pitch) as u32;
+        if px >= print_left && px < print_left + print_width && row < rows {
+            let band = (row / band_height) as usize;
+            // Pins near the bottom of the head strike a little lighter
+            let head_fade = 1.0 - 0.15 * (row % band_height) as f32 / band_height as f32;
+            let strength = darkness * band_strength[band] * head_fade;
+            let local_x = px - print_left - band_offset[band];
+            let col = (local_x / pitch).floor() as i32;
+            let center_y = (row as f32 + 0.5) * pitch;
+
+            let mut clear = 1.0f32;
+            for c in col - 1..=col + 1 {
+                if !fires(c, row) {
+                    continue;
+                }
+                let center_x = (c as f32 + 0.5) * pitch;
+                let distance = ((local_x - center_x).powi(2) + (py - center_y).powi(2)).sqrt();
+                clear *= 1.0 - (radius - distance + 0.5).clamp(0.0, 1.0) * strength;
+            }
+            coverage = 1.0 - clear;
+        }
+
+        let channel = |c: usize| (paper[c] + (ink[c] - paper[c]) * coverage).round() as u8;
+        output.put_pixel(x, y, Rgba([channel(0), channel(1), channel(2), pixel[3]]));
+    }
+
+    output
+}
+
+fn paper_color(px: f32, py: f32, width: f32, strip: f32, pitch: f32, tractor_feed: bool) -> [f32; 3] {
+    if !tractor_feed {
+        return PAPER_WHITE;
+    }
+
+    if px < strip || px >= width - strip {
+        // Round feed holes down the middle of each strip
+        let hole_spacing = strip * 1.2;
+        let center_x = if px < strip { strip / 2.0 } else { width - strip / 2.0 };
+        let center_y = ((py / hole_spacing).floor() + 0.5) * hole_spacing;
+        let distance = ((px - center_x).powi(2) + (py - center_y).powi(2)).sqrt();
+        let hole = (strip * 0.22 - distance + 0.5).clamp(0.0, 1.0);
+        return [0, 1, 2].map(|c| PAPER_WHITE[c] + (FEED_HOLE[c] - PAPER_WHITE[c]) * hole);
+    }
+
+    // Alternating green bars three print bands tall
+    let bar = pitch * 9.0 * 3.0;
+    if (py / bar) as u32 % 2 == 1 { GREENBAR } else { PAPER_WHITE }
+}
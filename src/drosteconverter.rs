@@ -0,0 +1,102 @@
+use std::f32::consts::TAU;
+
+use image::{DynamicImage, RgbaImage, Rgba};
+
+use crate::imageutils::{reflect_coord, sample_bilinear};
+
+// Radii below this (in units of the half-diagonal) are treated as the center point
+const MIN_RADIUS: f32 = 1e-4;
+
+#[derive(Clone, PartialEq)]
+pub struct DrosteSettings {
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub turns: i32,
+    pub strength: f32,
+    pub center_x: f32,
+    pub center_y: f32,
+}
+
+impl Default for DrosteSettings {
+    fn default() -> Self {
+        Self {
+            inner_radius: 0.3,
+            outer_radius: 1.0,
+            turns: 1,
+            strength: 100.0,
+            center_x: 0.5,
+            center_y: 0.5,
+        }
+    }
+}
+
+// Maps a point (relative to the center, in units of the half-diagonal) into the annulus
+// between the inner and outer radius. In log-polar space the annulus is a strip of width
+// ln(outer/inner) that repeats forever; `turns` shears the strip so the copies form a spiral.
+pub fn droste_map(x: f32, y: f32, settings: &DrosteSettings) -> (f32, f32) {
+    let inner = settings.inner_radius.max(MIN_RADIUS);
+    let outer = settings.outer_radius.max(inner * 1.01);
+    let period = (outer / inner).ln();
+
+    let radius = (x * x + y * y).sqrt();
+    // Every copy shrinks towards the center, so the center is the one fixed point
+    if radius < MIN_RADIUS {
+        return (x, y);
+    }
+    let a = (radius / inner).ln();
+    let b = y.atan2(x);
+
+    // Multiply by (1 - i*t); with t = turns*period/2π a full revolution shifts by whole periods,
+    // which keeps the spiral seamless across the branch cut
+    let t = settings.turns as f32 * period / TAU;
+    let log_r = (a + b * t).rem_euclid(period);
+    let theta = b - a * t;
+
+    let r = inner * log_r.exp();
+    (r * theta.cos(), r * theta.sin())
+}
+
+/// Wraps `image` into a Droste spiral, repeating it inside itself between the two radii.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::drosteconverter::{apply_droste, DrosteSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_droste(image, &DrosteSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_droste(image: DynamicImage, settings: &DrosteSettings) -> RgbaImage {
+    let rgba_img = image.to_rgba8();
+    let (width, height) = rgba_img.dimensions();
+    if width < 2 || height < 2 {
+        return rgba_img;
+    }
+    let mut output = RgbaImage::new(width, height);
+
+    let w = width as f32;
+    let h = height as f32;
+    let cx = w * settings.center_x;
+    let cy = h * settings.center_y;
+    let scale = ((w * w + h * h) / 4.0).sqrt();
+    let max_x = w - 1.001;
+    let max_y = h - 1.001;
+    let mix = settings.strength / 100.0;
+
+    for y in 0..height {
+        for x in 0..width {
+            let (mx, my) = droste_map((x as f32 - cx) / scale, (y as f32 - cy) / scale, settings);
+            let src_x = reflect_coord(cx + mx * scale, max_x);
+            let src_y = reflect_coord(cy + my * scale, max_y);
+            let recursed = sample_bilinear(&rgba_img, src_x, src_y, width, height);
+
+            let original = rgba_img.get_pixel(x, y);
+            let blend = |c: usize| (original[c] as f32 + (recursed[c] as f32 - original[c] as f32) * mix).round() as u8;
+            output.put_pixel(x, y, Rgba([blend(0), blend(1), blend(2), blend(3)]));
+        }
+    }
+
+    output
+}
@@ -0,0 +1,111 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+
+const PAPER: [f32; 3] = [250.0, 247.0, 238.0];
+const INK: [f32; 3] = [22.0, 20.0, 26.0];
+
+// Darkness at which each successive hatch layer starts
+const LAYER_THRESHOLDS: [f32; 3] = [0.15, 0.45, 0.72];
+
+#[derive(Clone, PartialEq)]
+pub struct EngravingSettings {
+    pub line_spacing: f32,
+    pub angle: f32,
+    pub cross_angle: f32,
+    pub layers: u32,
+    pub min_thickness: f32,
+    pub max_thickness: f32,
+    pub follow_flow: bool,
+}
+
+impl Default for EngravingSettings {
+    fn default() -> Self {
+        Self {
+            line_spacing: 6.0,
+            angle: 45.0,
+            cross_angle: 90.0,
+            layers: 3,
+            min_thickness: 0.1,
+            max_thickness: 0.7,
+            follow_flow: true,
+        }
+    }
+}
+
+struct HatchLayer {
+    cos: f32,
+    sin: f32,
+    threshold: f32,
+}
+
+/// Renders `image` as banknote-style engraving lines whose thickness follows the tone.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::engravingconverter::{apply_engraving, EngravingSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_engraving(image, &EngravingSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_engraving(image: DynamicImage, settings: &EngravingSettings) -> RgbaImage {
+    let rgba_img = image.to_rgba8();
+    let gray = image.to_luma8();
+    let (width, height) = gray.dimensions();
+    if width == 0 || height == 0 {
+        return rgba_img;
+    }
+    let spacing = settings.line_spacing.max(2.0);
+
+    // Heavily smoothed luminance bends the lines along the image's tonal contours
+    let flow = if settings.follow_flow {
+        Some(image::imageops::blur(&gray, spacing * 2.0))
+    } else {
+        None
+    };
+
+    let angles = [
+        settings.angle,
+        settings.angle + settings.cross_angle,
+        settings.angle - settings.cross_angle / 2.0,
+    ];
+    let layers: Vec<HatchLayer> = angles
+        .iter()
+        .zip(LAYER_THRESHOLDS)
+        .take(settings.layers.clamp(1, 3) as usize)
+        .map(|(angle, threshold)| {
+            let radians = angle.to_radians();
+            HatchLayer { cos: radians.cos(), sin: radians.sin(), threshold }
+        })
+        .collect();
+
+    let mut output = RgbaImage::new(width, height);
+    for (x, y, pixel) in rgba_img.enumerate_pixels() {
+        let darkness = 1.0 - gray.get_pixel(x, y)[0] as f32 / 255.0;
+        let displacement = flow.as_ref().map_or(0.0, |f| f.get_pixel(x, y)[0] as f32 / 255.0 * 1.5);
+        let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+
+        // Coverage from each layer combines like stacked ink, never exceeding full black
+        let mut paper = 1.0f32;
+        for layer in &layers {
+            if darkness <= layer.threshold {
+                continue;
+            }
+            let ramp = ((darkness - layer.threshold) / (1.0 - layer.threshold)).clamp(0.0, 1.0);
+            let thickness = (settings.min_thickness + (settings.max_thickness - settings.min_thickness) * ramp) * spacing;
+
+            let u = (px * layer.cos + py * layer.sin) / spacing + displacement;
+            let distance = (u - u.round()).abs() * spacing;
+            // One pixel of analytic anti-aliasing around the stroke edge
+            let coverage = (thickness / 2.0 - distance + 0.5).clamp(0.0, 1.0);
+            paper *= 1.0 - coverage;
+        }
+
+        let ink = 1.0 - paper;
+        let channel = |c: usize| (PAPER[c] + (INK[c] - PAPER[c]) * ink).round() as u8;
+        output.put_pixel(x, y, Rgba([channel(0), channel(1), channel(2), pixel[3]]));
+    }
+
+    output
+}
@@ -0,0 +1,48 @@
+use std::io;
+
+use thiserror::Error;
+
+/// Errors reported by the converters and by loading, rendering and saving images.
+///
+/// # Example
+///
+/// ```
+/// use pixforge::ArtsifyError;
+///
+/// let error = ArtsifyError::InvalidSettings("tile size must be positive".to_string());
+/// assert_eq!(error.to_string(), "invalid settings: tile size must be positive");
+/// ```
+#[derive(Debug, Error)]
+pub enum ArtsifyError {
+    /// Reading or writing a file failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// An input file (image, LUT) could not be parsed.
+    #[error("could not decode: {0}")]
+    Decode(String),
+    /// An output image could not be written in the requested format.
+    #[error("could not encode: {0}")]
+    Encode(String),
+    /// The bundled font could not be loaded.
+    #[error("font error: {0}")]
+    Font(String),
+    /// The settings cannot produce an output.
+    #[error("invalid settings: {0}")]
+    InvalidSettings(String),
+    /// The user dismissed a file dialog.
+    #[error("operation cancelled")]
+    Cancelled,
+}
+
+impl From<image::ImageError> for ArtsifyError {
+    fn from(error: image::ImageError) -> Self {
+        use image::ImageError;
+        match error {
+            ImageError::IoError(e) => ArtsifyError::Io(e),
+            ImageError::Decoding(e) => ArtsifyError::Decode(e.to_string()),
+            ImageError::Encoding(e) => ArtsifyError::Encode(e.to_string()),
+            // Unknown extensions, unsupported color types, size limits
+            other => ArtsifyError::InvalidSettings(other.to_string()),
+        }
+    }
+}
@@ -0,0 +1,2590 @@
+use std::path::Path;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use eframe::egui;
+use image::{DynamicImage, GenericImageView, RgbaImage};
+
+use pixforge::crtconverter::{CrtSettings, apply_crt_tiled};
+use pixforge::ditherconverter::{
+    DitherAlgorithm, DitherColorMode, DitherPalette, DitherPattern, DitherSettings, PaletteDistance, PALETTE_SIZE_RANGE,
+    PATTERN_SIZE_RANGE, ScanlineMode, ScanlineOrientation, auto_levels, check_palette, check_pattern, dither_prepared, extract_palette, parse_hex_palette,
+    prepare_dither, to_hex_palette,
+};
+use pixforge::fisheyeconverter::{FisheyeSettings, apply_fisheye_tiled};
+use pixforge::luminance::LuminanceModel;
+use pixforge::popartconverter::{PopArtSettings, PopArtPalette, apply_pop_art};
+use pixforge::lutconverter::{Lut3D, LutSettings, LutLook, apply_lut, parse_cube};
+use pixforge::hslconverter::{HslSettings, HueBand, apply_hsl};
+use pixforge::levelsconverter::{LevelsSettings, LevelsChannelMode, apply_levels, evaluate_curve};
+use pixforge::whitebalanceconverter::{WhiteBalanceSettings, apply_white_balance};
+use pixforge::tiltshiftconverter::{TiltShiftSettings, apply_tilt_shift};
+use pixforge::solarizeconverter::{SolarizeDirection, SolarizeMode, SolarizeSettings, apply_solarize};
+use pixforge::watercolorconverter::{WatercolorSettings, apply_watercolor};
+use pixforge::sketchconverter::{SketchMode, SketchSettings, apply_sketch};
+use pixforge::mosaicconverter::{MosaicSettings, apply_mosaic};
+use pixforge::neonconverter::{NeonColorMode, NeonSettings, apply_neon};
+use pixforge::kaleidoscopeconverter::{KaleidoscopeSettings, apply_kaleidoscope};
+use pixforge::drosteconverter::{DrosteSettings, apply_droste};
+use pixforge::engravingconverter::{EngravingSettings, apply_engraving};
+use pixforge::dotmatrixconverter::{DotMatrixSettings, RibbonColor, apply_dot_matrix};
+use pixforge::stainedglassconverter::{StainedGlassSettings, apply_stained_glass};
+use pixforge::infraredconverter::{InfraredPreset, InfraredSettings, apply_infrared};
+use pixforge::lomoconverter::{LomoPreset, LomoSettings, apply_lomo};
+use pixforge::oldphotoconverter::{OldPhotoSettings, apply_old_photo};
+use pixforge::ortonconverter::{OrtonSettings, apply_orton};
+use pixforge::retroconverter::{RetroConsole, RetroSettings, apply_retro};
+use pixforge::progress::Progress;
+use pixforge::tiling::DEFAULT_BAND_HEIGHT;
+use pixforge::ArtsifyError;
+
+use crate::asciifilter::AsciiFilter;
+
+// How the preview is sized in the central panel
+#[derive(Clone, Copy, PartialEq)]
+pub enum PreviewScale {
+    // Follows the zoom buttons
+    Zoom,
+    // Fits the available space, up to 2x
+    FitWindow,
+}
+
+// Work a filter hands to a worker thread; its result is discarded once `Progress` is cancelled
+pub type FilterJob = Box<dyn FnOnce(&DynamicImage, &Progress) -> RgbaImage + Send>;
+
+// Renders the image Save Image writes, on the thread saving it
+pub type ExportJob = Box<dyn FnOnce() -> Result<RgbaImage, ArtsifyError> + Send>;
+
+// Asks where to save and writes the file, on a worker thread with the progress bar shown
+pub type SaveJob = Box<dyn FnOnce(&Progress) -> Result<(), ArtsifyError> + Send>;
+
+// A filter the app drives through its registry: menus, sidebar, preview, cache and export
+// are handled generically, keyed by `id`
+pub trait Filter {
+    fn id(&self) -> &'static str;
+    fn name(&self) -> &'static str;
+    fn apply(&self, image: &DynamicImage) -> RgbaImage;
+    // Draws the sidebar controls, with the loaded image for those that sample it; returns
+    // true when a setting changed and the filter should re-run
+    fn settings_ui(&mut self, ui: &mut egui::Ui, image: Option<&DynamicImage>) -> bool;
+    fn reset_settings(&mut self);
+
+    // Drops anything kept from earlier runs on the input image, once it is replaced,
+    // rotated or flipped
+    fn clear_cache(&mut self) {}
+
+    // The filter's work for a worker thread, for filters slow enough on large images to
+    // freeze the UI; None runs `apply` on the UI thread
+    fn background_job(&self) -> Option<FilterJob> {
+        None
+    }
+
+    fn file_name(&self) -> String {
+        format!("{}.png", self.id())
+    }
+
+    fn texture_options(&self) -> egui::TextureOptions {
+        egui::TextureOptions::LINEAR
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::FitWindow
+    }
+
+    // Whether PNG exports are written indexed when the output has few enough colours
+    fn indexed_png(&self) -> bool {
+        false
+    }
+
+    // Factor exports are enlarged by with nearest neighbour; the preview stays at 1x
+    fn export_scale(&self) -> u32 {
+        1
+    }
+
+    fn preview_sense(&self) -> egui::Sense {
+        egui::Sense::hover()
+    }
+
+    // Painting and pointer handling on top of the preview, with the loaded image for those
+    // that sample it; returns true when a setting changed
+    fn preview_overlay(&mut self, _ui: &egui::Ui, _rect: egui::Rect, _response: &egui::Response, _image: Option<&DynamicImage>) -> bool {
+        false
+    }
+
+    // Drawn in the preview above the output; returns true when a setting changed
+    fn preview_header(&mut self, _ui: &mut egui::Ui) -> bool {
+        false
+    }
+
+    // Whether the output is left out of the preview, for filters whose header shows the
+    // result some other way
+    fn hides_output(&self) -> bool {
+        false
+    }
+
+    // The result as text, for filters that produce characters; enables Export Text and
+    // Copy Text
+    fn text_output(&self) -> Option<String> {
+        None
+    }
+
+    // Save Image writes what this renders instead of the preview output, for filters that
+    // export at a different size than they preview, with the status bar note for it
+    fn export_job(&self, _image: &DynamicImage) -> Option<(ExportJob, Option<String>)> {
+        None
+    }
+
+    // Shown in the status bar once `output` is saved, for filters without an `export_job`
+    fn save_note(&self, output: &RgbaImage) -> Option<String> {
+        let scale = self.export_scale().max(1);
+        let size = format!("{}×{} px", output.width() * scale, output.height() * scale);
+        Some(if scale > 1 { format!("{}x, {}", scale, size) } else { size })
+    }
+
+    // Extra File menu entries for the filter's own formats, given the loaded file's path;
+    // returns the save to run when one is clicked
+    fn export_menu(&mut self, _ui: &mut egui::Ui, _source_path: &Path) -> Option<SaveJob> {
+        None
+    }
+}
+
+pub fn registry() -> Vec<Box<dyn Filter>> {
+    vec![
+        Box::new(AsciiFilter::default()),
+        Box::new(DitherFilter::default()),
+        Box::new(FisheyeFilter::default()),
+        Box::new(CrtFilter::default()),
+        Box::new(InvertFilter::default()),
+        Box::new(PopArtFilter::default()),
+        Box::new(LutFilter::default()),
+        Box::new(HslFilter::default()),
+        Box::new(LevelsFilter::default()),
+        Box::new(WhiteBalanceFilter::default()),
+        Box::new(TiltShiftFilter::default()),
+        Box::new(SolarizeFilter::default()),
+        Box::new(WatercolorFilter::default()),
+        Box::new(SketchFilter::default()),
+        Box::new(MosaicFilter::default()),
+        Box::new(NeonFilter::default()),
+        Box::new(KaleidoscopeFilter::default()),
+        Box::new(DrosteFilter::default()),
+        Box::new(EngravingFilter::default()),
+        Box::new(DotMatrixFilter::default()),
+        Box::new(StainedGlassFilter::default()),
+        Box::new(InfraredFilter::default()),
+        Box::new(LomoFilter::default()),
+        Box::new(OldPhotoFilter::default()),
+        Box::new(OrtonFilter::default()),
+        Box::new(RetroFilter::default()),
+    ]
+}
+
+// The seed a 🎲 button moves to
+pub fn next_seed(seed: u64) -> u64 {
+    seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407)
+}
+
+// Crosshair at a centre point given as fractions of `rect`, which can be clicked or dragged
+// into place; returns true when it moved
+fn drag_center(ui: &egui::Ui, rect: egui::Rect, response: &egui::Response, center_x: &mut f32, center_y: &mut f32) -> bool {
+    let center_pos = egui::pos2(rect.min.x + rect.width() * *center_x, rect.min.y + rect.height() * *center_y);
+    let painter = ui.painter();
+    let cross_size = 20.0;
+    let cross_color = egui::Color32::BLACK;
+    painter.line_segment([egui::pos2(center_pos.x - cross_size, center_pos.y), egui::pos2(center_pos.x + cross_size, center_pos.y)], egui::Stroke::new(2.0, cross_color));
+    painter.line_segment([egui::pos2(center_pos.x, center_pos.y - cross_size), egui::pos2(center_pos.x, center_pos.y + cross_size)], egui::Stroke::new(2.0, cross_color));
+    painter.circle_stroke(center_pos, 5.0, egui::Stroke::new(2.0, cross_color));
+    if response.hovered() {
+        ui.ctx().set_cursor_icon(egui::CursorIcon::Crosshair);
+    }
+    if response.dragged() || response.clicked() {
+        if let Some(mouse_pos) = response.interact_pointer_pos() {
+            let new_x = ((mouse_pos.x - rect.min.x) / rect.width()).clamp(0.0, 1.0);
+            let new_y = ((mouse_pos.y - rect.min.y) / rect.height()).clamp(0.0, 1.0);
+            if new_x != *center_x || new_y != *center_y {
+                *center_x = new_x;
+                *center_y = new_y;
+                return true;
+            }
+        }
+    }
+    false
+}
+
+// A loaded palette, Ok(None) once one is saved
+type PaletteFile = Result<Option<Vec<[u8; 3]>>, ArtsifyError>;
+
+pub struct DitherFilter {
+    pub settings: DitherSettings,
+    // The palette being edited while Custom is picked; applied whenever it passes check_palette
+    custom_palette: Option<Vec<[u8; 3]>>,
+    palette_dialog: Option<mpsc::Receiver<PaletteFile>>,
+    palette_error: Option<String>,
+    indexed_png: bool,
+    export_scale: u32,
+    // The input as last prepared for dithering and the settings it was prepared with;
+    // reused while only the dithering itself changes
+    prepared: Arc<Mutex<Option<(DitherSettings, RgbaImage)>>>,
+}
+
+impl Default for DitherFilter {
+    fn default() -> Self {
+        Self {
+            settings: DitherSettings::default(),
+            custom_palette: None,
+            palette_dialog: None,
+            palette_error: None,
+            indexed_png: false,
+            export_scale: 1,
+            prepared: Arc::default(),
+        }
+    }
+}
+
+// Dithers `image`, preparing it only when `prepared` doesn't already hold it for `settings`
+fn dither_with_cache(
+    prepared: &Mutex<Option<(DitherSettings, RgbaImage)>>,
+    image: &DynamicImage,
+    settings: &DitherSettings,
+    progress: Option<&Progress>,
+) -> RgbaImage {
+    let reusable = prepared.lock().ok().and_then(|prepared| {
+        prepared.as_ref()
+            .filter(|(prepared_with, toned)| {
+                prepared_with.same_preparation(settings) && toned.dimensions() == (image.width(), image.height())
+            })
+            .map(|(_, toned)| toned.clone())
+    });
+    let toned = reusable.unwrap_or_else(|| {
+        let toned = prepare_dither(image, settings);
+        if let Ok(mut prepared) = prepared.lock() {
+            *prepared = Some((settings.clone(), toned.clone()));
+        }
+        toned
+    });
+    dither_prepared(toned, settings, progress)
+}
+
+impl DitherFilter {
+    // Swatches of the custom palette with reordering, adding, removing, extraction from
+    // `image` and palette files; returns true when a valid edit was applied
+    fn custom_palette_ui(&mut self, ui: &mut egui::Ui, image: Option<&DynamicImage>) -> bool {
+        let Some(mut palette) = self.custom_palette.take() else { return false };
+        let mut edited = false;
+        if let Some(receiver) = &self.palette_dialog {
+            if let Ok(result) = receiver.try_recv() {
+                self.palette_dialog = None;
+                match result {
+                    Ok(Some(loaded)) => {
+                        palette = loaded;
+                        edited = true;
+                    }
+                    Ok(None) | Err(ArtsifyError::Cancelled) => {}
+                    Err(e) => self.palette_error = Some(e.to_string()),
+                }
+            }
+        }
+
+        let count = palette.len();
+        let mut swap = None;
+        let mut remove = None;
+        for (i, color) in palette.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                let mut swatch = egui::Color32::from_rgb(color[0], color[1], color[2]);
+                if ui.color_edit_button_srgba(&mut swatch).changed() {
+                    *color = [swatch.r(), swatch.g(), swatch.b()];
+                    edited = true;
+                }
+                ui.monospace(format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2]));
+                if ui.add_enabled(i > 0, egui::Button::new("⬆").small()).clicked() {
+                    swap = Some(i - 1);
+                }
+                if ui.add_enabled(i + 1 < count, egui::Button::new("⬇").small()).clicked() {
+                    swap = Some(i);
+                }
+                if ui.small_button("🗑").clicked() {
+                    remove = Some(i);
+                }
+            });
+        }
+        if let Some(i) = swap {
+            palette.swap(i, i + 1);
+            edited = true;
+        }
+        if let Some(i) = remove {
+            palette.remove(i);
+            edited = true;
+        }
+        ui.horizontal(|ui| {
+            if ui.add_enabled(count < *PALETTE_SIZE_RANGE.end(), egui::Button::new("➕ Add")).clicked() {
+                palette.push([128, 128, 128]);
+                edited = true;
+            }
+            let from_image = ui.add_enabled(image.is_some(), egui::Button::new("From Image"))
+                .on_hover_text("Replace the palette with as many colours picked from the image by median cut");
+            if let Some(image) = image.filter(|_| from_image.clicked()) {
+                palette = extract_palette(image, count.clamp(*PALETTE_SIZE_RANGE.start(), *PALETTE_SIZE_RANGE.end()));
+                edited = true;
+            }
+        });
+        ui.horizontal(|ui| {
+            let idle = self.palette_dialog.is_none();
+            if ui.add_enabled(idle, egui::Button::new("Save…")).clicked() {
+                let text = to_hex_palette(&palette);
+                let (sender, receiver) = mpsc::channel();
+                self.palette_dialog = Some(receiver);
+                thread::spawn(move || {
+                    let result = match rfd::FileDialog::new().add_filter("Hex palette", &["hex"]).set_file_name("palette.hex").save_file() {
+                        Some(path) => std::fs::write(&path, &text).map(|()| None).map_err(ArtsifyError::from),
+                        None => Err(ArtsifyError::Cancelled),
+                    };
+                    let _ = sender.send(result);
+                });
+            }
+            if ui.add_enabled(idle, egui::Button::new("Load…")).clicked() {
+                let (sender, receiver) = mpsc::channel();
+                self.palette_dialog = Some(receiver);
+                thread::spawn(move || {
+                    let result = match rfd::FileDialog::new().add_filter("Hex palette", &["hex", "txt"]).pick_file() {
+                        Some(path) => std::fs::read_to_string(&path).map_err(ArtsifyError::from)
+                            .and_then(|text| parse_hex_palette(&text)).map(Some),
+                        None => Err(ArtsifyError::Cancelled),
+                    };
+                    let _ = sender.send(result);
+                });
+            }
+        });
+
+        let mut changed = false;
+        match check_palette(&palette) {
+            Ok(()) => {
+                if edited {
+                    self.settings.palette = Some(palette.clone());
+                    self.palette_error = None;
+                    changed = true;
+                }
+            }
+            Err(e) => {
+                ui.colored_label(ui.visuals().warn_fg_color, format!("{}; using the last valid palette", e));
+            }
+        }
+        if let Some(error) = &self.palette_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        self.custom_palette = Some(palette);
+        changed
+    }
+
+    // Preset picker and an editable grid of the pattern's thresholds; returns true when
+    // the pattern changed
+    fn pattern_ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        ui.label("Pattern:");
+        let current_pattern = DitherPattern::ALL.into_iter().find(|preset| preset.matrix() == self.settings.pattern);
+        egui::ComboBox::from_id_salt("dither_pattern")
+            .selected_text(current_pattern.map_or("Custom".to_string(), |preset| preset.name().to_string()))
+            .show_ui(ui, |ui| {
+                for preset in DitherPattern::ALL {
+                    if ui.selectable_label(current_pattern == Some(preset), preset.name()).clicked() {
+                        self.settings.pattern = preset.matrix();
+                        changed = true;
+                    }
+                }
+            });
+        if let Err(e) = check_pattern(&self.settings.pattern) {
+            ui.horizontal(|ui| {
+                ui.colored_label(ui.visuals().warn_fg_color, format!("{}; using the default pattern", e));
+                if ui.button("Reset").clicked() {
+                    self.settings.pattern = DitherPattern::Bayer.matrix();
+                    changed = true;
+                }
+            });
+            return changed;
+        }
+
+        let mut size = self.settings.pattern.len();
+        ui.horizontal(|ui| {
+            ui.label("Size:");
+            if ui.add(egui::DragValue::new(&mut size).range(PATTERN_SIZE_RANGE)).changed() {
+                // Keep what fits of the old matrix and start new cells at 0
+                let max = (size * size - 1) as u8;
+                let old = std::mem::take(&mut self.settings.pattern);
+                self.settings.pattern = (0..size)
+                    .map(|y| (0..size).map(|x| old.get(y).and_then(|row| row.get(x)).map_or(0, |&entry| entry.min(max))).collect())
+                    .collect();
+                changed = true;
+            }
+        });
+        let max = (size * size - 1) as u8;
+        egui::Grid::new("dither_pattern_grid").show(ui, |ui| {
+            for row in self.settings.pattern.iter_mut() {
+                for entry in row.iter_mut() {
+                    if ui.add(egui::DragValue::new(entry).range(0..=max)).changed() {
+                        changed = true;
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        changed
+    }
+}
+
+impl Filter for DitherFilter {
+    fn id(&self) -> &'static str {
+        "dither"
+    }
+
+    fn name(&self) -> &'static str {
+        "Dither"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        dither_with_cache(&self.prepared, image, &self.settings, None)
+    }
+
+    fn clear_cache(&mut self) {
+        // A fresh cell, so a job still preparing the old image can't store it here
+        self.prepared = Arc::default();
+    }
+
+    fn background_job(&self) -> Option<FilterJob> {
+        let prepared = self.prepared.clone();
+        let settings = self.settings.clone();
+        Some(Box::new(move |image, progress| dither_with_cache(&prepared, image, &settings, Some(progress))))
+    }
+
+    fn file_name(&self) -> String {
+        "dithered.png".to_string()
+    }
+
+    fn indexed_png(&self) -> bool {
+        self.indexed_png
+    }
+
+    fn export_scale(&self) -> u32 {
+        self.export_scale
+    }
+
+    fn texture_options(&self) -> egui::TextureOptions {
+        egui::TextureOptions::NEAREST
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = DitherSettings::default();
+        self.custom_palette = None;
+        self.palette_error = None;
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            for mode in [DitherColorMode::Grayscale, DitherColorMode::Rgb] {
+                if ui.selectable_value(&mut self.settings.color_mode, mode, mode.name()).changed() {
+                    changed = true;
+                }
+            }
+        });
+        ui.add_space(5.0);
+        ui.label("Algorithm:");
+        let current_algo = self.settings.algorithm.clone();
+        egui::ComboBox::from_id_salt("dither_algorithm").selected_text(current_algo.name()).show_ui(ui, |ui| {
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::FloydSteinberg, DitherAlgorithm::FloydSteinberg.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::Atkinson, DitherAlgorithm::Atkinson.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::Jarvis, DitherAlgorithm::Jarvis.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::Stucki, DitherAlgorithm::Stucki.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::Burkes, DitherAlgorithm::Burkes.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::Sierra, DitherAlgorithm::Sierra.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::SierraTwoRow, DitherAlgorithm::SierraTwoRow.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::SierraLite, DitherAlgorithm::SierraLite.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::Ordered, DitherAlgorithm::Ordered.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::BlueNoise, DitherAlgorithm::BlueNoise.name());
+            if self.settings.palette.is_some() {
+                ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::YliluomaOrdered, DitherAlgorithm::YliluomaOrdered.name());
+            }
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::Threshold, DitherAlgorithm::Threshold.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::Scanline, DitherAlgorithm::Scanline.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::Pattern, DitherAlgorithm::Pattern.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::Random, DitherAlgorithm::Random.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::Halftone, DitherAlgorithm::Halftone.name());
+            ui.selectable_value(&mut self.settings.algorithm, DitherAlgorithm::HalftoneCmyk, DitherAlgorithm::HalftoneCmyk.name());
+        });
+        if current_algo != self.settings.algorithm {
+            changed = true;
+        }
+        ui.label("Strength:");
+        let mut strength = (self.settings.strength * 100.0).round() as i32;
+        if ui.add(egui::Slider::new(&mut strength, 0..=100).text("%"))
+            .on_hover_text("Blend the dithered output with the smooth image")
+            .changed()
+        {
+            self.settings.strength = strength as f32 / 100.0;
+            changed = true;
+        }
+        ui.add_space(5.0);
+        ui.label("Palette:");
+        let current_palette = DitherPalette::ALL.into_iter()
+            .find(|preset| self.custom_palette.is_none() && self.settings.palette.as_ref() == Some(&preset.colors()));
+        // Palettes that match no preset are edited as custom ones
+        if current_palette.is_none() && self.custom_palette.is_none() {
+            self.custom_palette = self.settings.palette.clone();
+        }
+        let palette_label = match (current_palette, &self.custom_palette) {
+            (Some(preset), _) => preset.name().to_string(),
+            (None, Some(_)) => "Custom".to_string(),
+            (None, None) => "Levels".to_string(),
+        };
+        egui::ComboBox::from_id_salt("dither_palette").selected_text(palette_label).show_ui(ui, |ui| {
+            if ui.selectable_label(self.settings.palette.is_none(), "Levels").clicked() {
+                self.settings.palette = None;
+                self.custom_palette = None;
+                // Yliluoma only differs from Bayer with a palette, and isn't offered without one
+                if self.settings.algorithm == DitherAlgorithm::YliluomaOrdered {
+                    self.settings.algorithm = DitherAlgorithm::Ordered;
+                }
+                changed = true;
+            }
+            for preset in DitherPalette::ALL {
+                if ui.selectable_label(current_palette == Some(preset), preset.name()).clicked() {
+                    self.settings.palette = Some(preset.colors());
+                    self.custom_palette = None;
+                    changed = true;
+                }
+            }
+            if ui.selectable_label(self.custom_palette.is_some(), "Custom").clicked() && self.custom_palette.is_none() {
+                // Start from whatever is in use, so a preset can be tweaked
+                let palette = self.settings.palette.clone().unwrap_or_else(|| vec![[0, 0, 0], [255, 255, 255]]);
+                self.settings.palette = Some(palette.clone());
+                self.custom_palette = Some(palette);
+                changed = true;
+            }
+        });
+        if self.settings.palette.is_some() {
+            ui.horizontal(|ui| {
+                ui.label("Match:");
+                egui::ComboBox::from_id_salt("dither_palette_distance").selected_text(self.settings.palette_distance.name()).show_ui(ui, |ui| {
+                    for distance in [PaletteDistance::Rgb, PaletteDistance::Lab] {
+                        if ui.selectable_value(&mut self.settings.palette_distance, distance, distance.name()).changed() {
+                            changed = true;
+                        }
+                    }
+                });
+            });
+        }
+        if self.custom_palette.is_some() && self.custom_palette_ui(ui, image) {
+            changed = true;
+        }
+        if self.settings.algorithm == DitherAlgorithm::Threshold {
+            ui.label("Threshold:");
+            let mut thresh = self.settings.threshold as i32;
+            if ui.add(egui::Slider::new(&mut thresh, 0..=255).text("value")).changed() {
+                self.settings.threshold = thresh as f32;
+                changed = true;
+            }
+        } else if self.settings.palette.is_none() {
+            ui.label("Color Levels:");
+            let mut levels = self.settings.color_levels as i32;
+            if ui.add(egui::Slider::new(&mut levels, 2..=16).text("levels")).changed() {
+                self.settings.color_levels = levels as u8;
+                changed = true;
+            }
+        }
+        if self.settings.palette.is_none() {
+            ui.horizontal(|ui| {
+                for (color, label) in [(&mut self.settings.custom_black, "Dark color"), (&mut self.settings.custom_white, "Light color")] {
+                    let mut swatch = egui::Color32::from_rgb(color[0], color[1], color[2]);
+                    if ui.color_edit_button_srgba(&mut swatch).on_hover_text(label).changed() {
+                        *color = [swatch.r(), swatch.g(), swatch.b()];
+                        changed = true;
+                    }
+                    ui.label(label);
+                }
+            });
+            let custom = self.settings.custom_black != [0, 0, 0] || self.settings.custom_white != [255, 255, 255];
+            if ui.add_enabled(custom, egui::Button::new("Reset colors")).clicked() {
+                self.settings.custom_black = [0, 0, 0];
+                self.settings.custom_white = [255, 255, 255];
+                changed = true;
+            }
+        }
+        if self.settings.algorithm == DitherAlgorithm::Pattern && self.pattern_ui(ui) {
+            changed = true;
+        }
+        if self.settings.algorithm == DitherAlgorithm::Random {
+            ui.horizontal(|ui| {
+                ui.label("Seed:");
+                if ui.add(egui::DragValue::new(&mut self.settings.seed)).changed() {
+                    changed = true;
+                }
+                if ui.button("🎲 New Seed").clicked() {
+                    self.settings.seed = next_seed(self.settings.seed);
+                    changed = true;
+                }
+            });
+        }
+        if matches!(self.settings.algorithm, DitherAlgorithm::Halftone | DitherAlgorithm::HalftoneCmyk) {
+            ui.label("Cell Size:");
+            if ui.add(egui::Slider::new(&mut self.settings.halftone_size, 2..=32).text("px")).changed() {
+                changed = true;
+            }
+        }
+        if self.settings.algorithm.is_error_diffusion() {
+            ui.label("Edge Preserve:");
+            let mut edge = (self.settings.edge_preserve * 100.0).round() as i32;
+            if ui.add(egui::Slider::new(&mut edge, 0..=100).text("%"))
+                .on_hover_text("Keep outlines and text solid by not diffusing error across strong edges")
+                .changed()
+            {
+                self.settings.edge_preserve = edge as f32 / 100.0;
+                changed = true;
+            }
+            ui.label("Error Limit:");
+            let mut clamp = (self.settings.error_clamp * 100.0).round() as i32;
+            if ui.add(egui::Slider::new(&mut clamp, 0..=100).text("% of a step"))
+                .on_hover_text("Cap the error passed to each neighbour, shortening trails beside sharp edges; 100% is unlimited")
+                .changed()
+            {
+                self.settings.error_clamp = clamp as f32 / 100.0;
+                changed = true;
+            }
+        }
+        if self.settings.algorithm == DitherAlgorithm::Scanline {
+            ui.horizontal(|ui| {
+                let current_orientation = self.settings.scanline_orientation;
+                egui::ComboBox::from_id_salt("dither_scanline_orientation").selected_text(current_orientation.name()).show_ui(ui, |ui| {
+                    for orientation in ScanlineOrientation::ALL {
+                        ui.selectable_value(&mut self.settings.scanline_orientation, orientation, orientation.name());
+                    }
+                });
+                let current_mode = self.settings.scanline_mode;
+                egui::ComboBox::from_id_salt("dither_scanline_mode").selected_text(current_mode.name()).show_ui(ui, |ui| {
+                    for mode in ScanlineMode::ALL {
+                        ui.selectable_value(&mut self.settings.scanline_mode, mode, mode.name());
+                    }
+                });
+                if current_orientation != self.settings.scanline_orientation || current_mode != self.settings.scanline_mode {
+                    changed = true;
+                }
+            });
+            ui.label("Line Thickness:");
+            if ui.add(egui::Slider::new(&mut self.settings.scanline_thickness, 1..=8).text("px")).changed() {
+                self.settings.scanline_phase = self.settings.scanline_phase.min(self.settings.scanline_thickness * 2 - 1);
+                changed = true;
+            }
+            ui.label("Line Phase:");
+            if ui.add(egui::Slider::new(&mut self.settings.scanline_phase, 0..=self.settings.scanline_thickness * 2 - 1).text("px")).changed() {
+                changed = true;
+            }
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Tone Adjustments:");
+
+        if self.settings.color_mode == DitherColorMode::Grayscale {
+            ui.label("Luminance:");
+            let current_luminance = self.settings.luminance;
+            egui::ComboBox::from_id_salt("dither_luminance").selected_text(current_luminance.name()).show_ui(ui, |ui| {
+                for luminance in [LuminanceModel::Rec709, LuminanceModel::Rec601, LuminanceModel::Average, LuminanceModel::Lightness] {
+                    ui.selectable_value(&mut self.settings.luminance, luminance, luminance.name());
+                }
+            });
+            if current_luminance != self.settings.luminance {
+                changed = true;
+            }
+        }
+
+        ui.label("Black Point:");
+        let mut black = self.settings.black_point as i32;
+        if ui.add(egui::Slider::new(&mut black, 0..=254).text("level")).changed() {
+            self.settings.black_point = black as f32;
+            self.settings.white_point = self.settings.white_point.max(black as f32 + 1.0);
+            changed = true;
+        }
+
+        ui.label("White Point:");
+        let mut white = self.settings.white_point as i32;
+        if ui.add(egui::Slider::new(&mut white, 1..=255).text("level")).changed() {
+            self.settings.white_point = white as f32;
+            self.settings.black_point = self.settings.black_point.min(white as f32 - 1.0);
+            changed = true;
+        }
+
+        if let Some(image) = image {
+            if ui.button("Auto levels")
+                .on_hover_text("Set the points to clip the darkest and lightest 1% of the image")
+                .clicked()
+            {
+                (self.settings.black_point, self.settings.white_point) = auto_levels(image, self.settings.luminance);
+                changed = true;
+            }
+        }
+
+        ui.label("Contrast:");
+        let mut contrast_int = (self.settings.contrast * 100.0) as i32;
+        if ui.add(egui::Slider::new(&mut contrast_int, 50..=200).text("%")).changed() {
+            self.settings.contrast = contrast_int as f32 / 100.0;
+            changed = true;
+        }
+
+        ui.label("Midtones:");
+        let mut midtones_int = (self.settings.midtones * 100.0) as i32;
+        if ui.add(egui::Slider::new(&mut midtones_int, -100..=100).text("shift")).changed() {
+            self.settings.midtones = midtones_int as f32 / 100.0;
+            changed = true;
+        }
+
+        ui.label("Highlights:");
+        let mut highlights_int = (self.settings.highlights * 100.0) as i32;
+        if ui.add(egui::Slider::new(&mut highlights_int, 50..=150).text("%")).changed() {
+            self.settings.highlights = highlights_int as f32 / 100.0;
+            changed = true;
+        }
+
+        ui.label("Luminance Threshold:");
+        let mut lum = self.settings.luminance_threshold as i32;
+        if ui.add(egui::Slider::new(&mut lum, 0..=255).text("level")).changed() {
+            self.settings.luminance_threshold = lum as f32;
+            changed = true;
+        }
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Blur:");
+        let mut blur_val = self.settings.blur as i32;
+        if ui.add(egui::Slider::new(&mut blur_val, 0..=5).text("amount")).changed() {
+            self.settings.blur = blur_val as f32;
+            changed = true;
+        }
+
+        ui.label("Pixel Scale:");
+        if ui.add(egui::Slider::new(&mut self.settings.pixel_scale, 1..=16).text("px"))
+            .on_hover_text("Dither at a fraction of the size and enlarge, so each dot is a visible block")
+            .changed()
+        {
+            changed = true;
+        }
+
+        ui.add_space(10.0);
+        ui.separator();
+        ui.checkbox(&mut self.indexed_png, "Save as indexed PNG")
+            .on_hover_text("1-bit for black and white, a palette of the output's colours otherwise; much smaller than RGBA");
+        ui.label("Export Scale:");
+        ui.add(egui::Slider::new(&mut self.export_scale, 1..=8).text("x"))
+            .on_hover_text("Enlarge saved images with nearest neighbour, so the dots stay crisp when shared");
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct FisheyeFilter {
+    pub settings: FisheyeSettings,
+}
+
+impl Filter for FisheyeFilter {
+    fn id(&self) -> &'static str {
+        "fisheye"
+    }
+
+    fn name(&self) -> &'static str {
+        "Fisheye"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_fisheye_tiled(image.clone(), &self.settings, DEFAULT_BAND_HEIGHT, None)
+    }
+
+    fn background_job(&self) -> Option<FilterJob> {
+        let settings = self.settings.clone();
+        Some(Box::new(move |image, progress| apply_fisheye_tiled(image.clone(), &settings, DEFAULT_BAND_HEIGHT, Some(progress))))
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = FisheyeSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Strength:");
+        if ui.add(egui::Slider::new(&mut self.settings.strength, -0.9..=0.9).text("distortion").step_by(0.05))
+            .on_hover_text("Positive = barrel (fisheye), Negative = pincushion").changed() {
+            changed = true;
+        }
+        ui.add_space(5.0);
+        ui.label("Zoom:");
+        if ui.add(egui::Slider::new(&mut self.settings.zoom, 0.5..=2.0).text("scale").step_by(0.05)).changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Center Point:");
+        ui.horizontal(|ui| {
+            ui.label("X:");
+            if ui.add(egui::Slider::new(&mut self.settings.center_x, 0.0..=1.0).text("position").step_by(0.01)).changed() {
+                changed = true;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Y:");
+            if ui.add(egui::Slider::new(&mut self.settings.center_y, 0.0..=1.0).text("position").step_by(0.01)).changed() {
+                changed = true;
+            }
+        });
+        ui.add_space(5.0);
+        if ui.button("Reset Center").clicked() {
+            self.settings.center_x = 0.5;
+            self.settings.center_y = 0.5;
+            changed = true;
+        }
+        changed
+    }
+
+    fn preview_sense(&self) -> egui::Sense {
+        egui::Sense::click_and_drag()
+    }
+
+    fn preview_overlay(&mut self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response, _image: Option<&DynamicImage>) -> bool {
+        drag_center(ui, rect, response, &mut self.settings.center_x, &mut self.settings.center_y)
+    }
+}
+
+#[derive(Default)]
+pub struct CrtFilter {
+    pub settings: CrtSettings,
+}
+
+impl Filter for CrtFilter {
+    fn id(&self) -> &'static str {
+        "crt"
+    }
+
+    fn name(&self) -> &'static str {
+        "CRT Monitor"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_crt_tiled(image.clone(), &self.settings, DEFAULT_BAND_HEIGHT, None)
+    }
+
+    fn background_job(&self) -> Option<FilterJob> {
+        let settings = self.settings.clone();
+        Some(Box::new(move |image, progress| apply_crt_tiled(image.clone(), &settings, DEFAULT_BAND_HEIGHT, Some(progress))))
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = CrtSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Scanline Density:");
+        if ui.add(egui::Slider::new(&mut self.settings.scanline_density, 1.0..=5.0).text("density").step_by(0.5)).changed() {
+            changed = true;
+        }
+        ui.add_space(5.0);
+        ui.label("Brightness Gain:");
+        if ui.add(egui::Slider::new(&mut self.settings.brightness_gain, 0.5..=2.0).text("gain").step_by(0.1)).changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Screen Curvature:");
+        if ui.add(egui::Slider::new(&mut self.settings.curvature, 0.0..=0.3).text("curve").step_by(0.05)).changed() {
+            changed = true;
+        }
+        ui.add_space(5.0);
+        ui.label("Bezel Size:");
+        if ui.add(egui::Slider::new(&mut self.settings.bezel_size, 0.0..=0.2).text("size").step_by(0.01)).changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Vignette:");
+        if ui.add(egui::Slider::new(&mut self.settings.vignette_strength, 0.0..=1.0).text("strength").step_by(0.05)).changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Background:");
+        ui.horizontal(|ui| {
+            ui.label("Color:");
+            let mut bg_color = egui::Color32::from_rgb(
+                self.settings.bg_color[0],
+                self.settings.bg_color[1],
+                self.settings.bg_color[2]
+            );
+            if ui.color_edit_button_srgba(&mut bg_color).changed() {
+                self.settings.bg_color = [bg_color.r(), bg_color.g(), bg_color.b()];
+                changed = true;
+            }
+        });
+        ui.label("Background Opacity:");
+        let mut opacity_f32 = self.settings.bg_opacity as f32;
+        if ui.add(egui::Slider::new(&mut opacity_f32, 0.0..=255.0).text("alpha")).changed() {
+            self.settings.bg_opacity = opacity_f32 as u8;
+            changed = true;
+        }
+        changed
+    }
+}
+
+// Colour negative blended with the original; only ever wired through the registry
+pub struct InvertFilter {
+    pub amount: f32,
+}
+
+impl Default for InvertFilter {
+    fn default() -> Self {
+        Self { amount: 1.0 }
+    }
+}
+
+impl Filter for InvertFilter {
+    fn id(&self) -> &'static str {
+        "invert"
+    }
+
+    fn name(&self) -> &'static str {
+        "Invert"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        let mut output = image.to_rgba8();
+        for pixel in output.pixels_mut() {
+            for c in 0..3 {
+                let value = pixel[c] as f32;
+                pixel[c] = (value + (255.0 - 2.0 * value) * self.amount).round() as u8;
+            }
+        }
+        output
+    }
+
+    fn reset_settings(&mut self) {
+        *self = Self::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        ui.label("Amount:");
+        ui.add(egui::Slider::new(&mut self.amount, 0.0..=1.0).step_by(0.05)).changed()
+    }
+}
+
+#[derive(Default)]
+pub struct PopArtFilter {
+    pub settings: PopArtSettings,
+}
+
+impl Filter for PopArtFilter {
+    fn id(&self) -> &'static str {
+        "pop_art"
+    }
+
+    fn name(&self) -> &'static str {
+        "Pop Art"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_pop_art(image.clone(), &self.settings)
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = PopArtSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Palette:");
+        let current_palette = self.settings.palette.clone();
+        egui::ComboBox::from_id_salt("pop_art_palette").selected_text(current_palette.name()).show_ui(ui, |ui| {
+            ui.selectable_value(&mut self.settings.palette, PopArtPalette::Primary, PopArtPalette::Primary.name());
+            ui.selectable_value(&mut self.settings.palette, PopArtPalette::Warhol, PopArtPalette::Warhol.name());
+            ui.selectable_value(&mut self.settings.palette, PopArtPalette::Newsprint, PopArtPalette::Newsprint.name());
+            ui.selectable_value(&mut self.settings.palette, PopArtPalette::Adaptive, PopArtPalette::Adaptive.name());
+        });
+        if current_palette != self.settings.palette {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Halftone Dots:");
+        if ui.add(egui::Slider::new(&mut self.settings.dot_size, 3.0..=16.0).text("size").step_by(1.0)).changed() {
+            changed = true;
+        }
+        if ui.add(egui::Slider::new(&mut self.settings.dot_angle, 0.0..=90.0).text("angle").step_by(5.0)).changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Outlines:");
+        if ui.add(egui::Slider::new(&mut self.settings.outline_thickness, 0..=5).text("thickness")).changed() {
+            changed = true;
+        }
+        if ui.add(egui::Slider::new(&mut self.settings.edge_threshold, 10.0..=200.0).text("threshold").step_by(5.0))
+            .on_hover_text("Lower values draw more outlines").changed() {
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct LutFilter {
+    pub settings: LutSettings,
+    cube_dialog: Option<mpsc::Receiver<Result<Lut3D, ArtsifyError>>>,
+    cube_error: Option<String>,
+}
+
+impl Filter for LutFilter {
+    fn id(&self) -> &'static str {
+        "lut"
+    }
+
+    fn name(&self) -> &'static str {
+        "Color Grading (LUT)"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_lut(image.clone(), &self.settings)
+    }
+
+    fn file_name(&self) -> String {
+        "graded.png".to_string()
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = LutSettings::default();
+        self.cube_error = None;
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Look:");
+        let current_look = self.settings.look.clone();
+        egui::ComboBox::from_id_salt("lut_look").selected_text(current_look.name()).show_ui(ui, |ui| {
+            ui.selectable_value(&mut self.settings.look, LutLook::TealOrange, LutLook::TealOrange.name());
+            ui.selectable_value(&mut self.settings.look, LutLook::BleachBypass, LutLook::BleachBypass.name());
+            ui.selectable_value(&mut self.settings.look, LutLook::FadedFilm, LutLook::FadedFilm.name());
+            if self.settings.custom_lut.is_some() {
+                ui.selectable_value(&mut self.settings.look, LutLook::Custom, LutLook::Custom.name());
+            }
+        });
+        if current_look != self.settings.look {
+            changed = true;
+        }
+        ui.add_space(5.0);
+        if let Some(receiver) = &self.cube_dialog {
+            match receiver.try_recv() {
+                Ok(result) => {
+                    self.cube_dialog = None;
+                    match result {
+                        Ok(lut) => {
+                            self.settings.custom_lut = Some(lut);
+                            self.settings.look = LutLook::Custom;
+                            self.cube_error = None;
+                            changed = true;
+                        }
+                        Err(ArtsifyError::Cancelled) => {}
+                        Err(e) => self.cube_error = Some(format!("Failed to load LUT: {}", e)),
+                    }
+                }
+                Err(_) => ui.ctx().request_repaint(),
+            }
+        }
+        if ui.add_enabled(self.cube_dialog.is_none(), egui::Button::new("Import .cube…")).clicked() {
+            let (sender, receiver) = mpsc::channel();
+            self.cube_dialog = Some(receiver);
+            thread::spawn(move || {
+                let result = match rfd::FileDialog::new().add_filter("Cube LUT", &["cube"]).pick_file() {
+                    Some(path) => std::fs::read_to_string(&path).map_err(ArtsifyError::from).and_then(|text| parse_cube(&text)),
+                    None => Err(ArtsifyError::Cancelled),
+                };
+                let _ = sender.send(result);
+            });
+        }
+        if let Some(lut) = &self.settings.custom_lut {
+            let title = if lut.title.is_empty() { "Untitled" } else { lut.title.as_str() };
+            ui.label(format!("Loaded: {} ({}³)", title, lut.size));
+        }
+        if let Some(error) = &self.cube_error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Strength:");
+        if ui.add(egui::Slider::new(&mut self.settings.strength, 0.0..=1.0).text("blend").step_by(0.05)).changed() {
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct HslFilter {
+    pub settings: HslSettings,
+}
+
+impl Filter for HslFilter {
+    fn id(&self) -> &'static str {
+        "hsl"
+    }
+
+    fn name(&self) -> &'static str {
+        "Hue / Saturation"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_hsl(image.clone(), &self.settings)
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = HslSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Hue:");
+        if ui.add(egui::Slider::new(&mut self.settings.hue, -180.0..=180.0).text("degrees").step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.label("Saturation:");
+        if ui.add(egui::Slider::new(&mut self.settings.saturation, -100.0..=100.0).text("%").step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.label("Lightness:");
+        if ui.add(egui::Slider::new(&mut self.settings.lightness, -100.0..=100.0).text("%").step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        if ui.checkbox(&mut self.settings.use_bands, "Per-hue adjustments").changed() {
+            changed = true;
+        }
+        if self.settings.use_bands {
+            for (band, adjustment) in HueBand::ALL.iter().zip(self.settings.bands.iter_mut()) {
+                egui::CollapsingHeader::new(band.name()).id_salt(("hsl_band", band.name())).show(ui, |ui| {
+                    changed |= ui.add(egui::Slider::new(&mut adjustment.hue, -180.0..=180.0).text("hue").step_by(1.0)).changed();
+                    changed |= ui.add(egui::Slider::new(&mut adjustment.saturation, -100.0..=100.0).text("saturation").step_by(1.0)).changed();
+                    changed |= ui.add(egui::Slider::new(&mut adjustment.lightness, -100.0..=100.0).text("lightness").step_by(1.0)).changed();
+                });
+            }
+        }
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct LevelsFilter {
+    pub settings: LevelsSettings,
+    // Control point being dragged in the curve editor
+    curve_drag_index: Option<usize>,
+}
+
+impl Filter for LevelsFilter {
+    fn id(&self) -> &'static str {
+        "levels"
+    }
+
+    fn name(&self) -> &'static str {
+        "Levels & Curves"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_levels(image.clone(), &self.settings)
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = LevelsSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Apply To:");
+        let current_mode = self.settings.channel_mode.clone();
+        egui::ComboBox::from_id_salt("levels_channel_mode").selected_text(current_mode.name()).show_ui(ui, |ui| {
+            ui.selectable_value(&mut self.settings.channel_mode, LevelsChannelMode::PerChannel, LevelsChannelMode::PerChannel.name());
+            ui.selectable_value(&mut self.settings.channel_mode, LevelsChannelMode::Luminance, LevelsChannelMode::Luminance.name());
+        });
+        if current_mode != self.settings.channel_mode {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Input Levels:");
+        let input_white = self.settings.input_white;
+        if ui.add(egui::Slider::new(&mut self.settings.input_black, 0.0..=input_white - 1.0).text("black").step_by(1.0)).changed() {
+            changed = true;
+        }
+        let input_black = self.settings.input_black;
+        if ui.add(egui::Slider::new(&mut self.settings.input_white, input_black + 1.0..=255.0).text("white").step_by(1.0)).changed() {
+            changed = true;
+        }
+        if ui.add(egui::Slider::new(&mut self.settings.gamma, 0.1..=5.0).text("gamma").logarithmic(true)).changed() {
+            changed = true;
+        }
+        ui.label("Output Levels:");
+        if ui.add(egui::Slider::new(&mut self.settings.output_black, 0.0..=255.0).text("black").step_by(1.0)).changed() {
+            changed = true;
+        }
+        if ui.add(egui::Slider::new(&mut self.settings.output_white, 0.0..=255.0).text("white").step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        egui::CollapsingHeader::new("Tone Curve").default_open(true).show(ui, |ui| {
+            ui.label("Drag points to shape the curve, click to add, right-click to remove.");
+            if curve_editor(ui, &mut self.settings.curve, &mut self.curve_drag_index) {
+                changed = true;
+            }
+            if ui.button("Reset Curve").clicked() {
+                self.settings.curve = LevelsSettings::default().curve;
+                changed = true;
+            }
+        });
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct WhiteBalanceFilter {
+    pub settings: WhiteBalanceSettings,
+    // While set, a click on the preview picks the neutral point
+    eyedropper: bool,
+}
+
+impl Filter for WhiteBalanceFilter {
+    fn id(&self) -> &'static str {
+        "white_balance"
+    }
+
+    fn name(&self) -> &'static str {
+        "White Balance"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_white_balance(image.clone(), &self.settings)
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = WhiteBalanceSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Temperature:");
+        if ui.add(egui::Slider::new(&mut self.settings.temperature, -100.0..=100.0).text("warm").step_by(1.0))
+            .on_hover_text("Positive = warmer, Negative = cooler").changed() {
+            changed = true;
+        }
+        ui.label("Tint:");
+        if ui.add(egui::Slider::new(&mut self.settings.tint, -100.0..=100.0).text("magenta").step_by(1.0))
+            .on_hover_text("Positive = magenta, Negative = green").changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Neutral Point:");
+        let eyedropper_label = if self.eyedropper { "Click a neutral area…" } else { "Pick Neutral Gray" };
+        if ui.selectable_label(self.eyedropper, eyedropper_label).clicked() {
+            self.eyedropper = !self.eyedropper;
+        }
+        if let Some([r, g, b]) = self.settings.picked_white {
+            ui.horizontal(|ui| {
+                let (rect, _) = ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::hover());
+                ui.painter().rect_filled(rect, 2.0, egui::Color32::from_rgb(r, g, b));
+                ui.label(format!("Picked: {}, {}, {}", r, g, b));
+            });
+            if ui.button("Clear Neutral Point").clicked() {
+                self.settings.picked_white = None;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn preview_sense(&self) -> egui::Sense {
+        egui::Sense::click()
+    }
+
+    // With the eyedropper on, a click samples the input under the pointer as the neutral point
+    fn preview_overlay(&mut self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response, image: Option<&DynamicImage>) -> bool {
+        if !self.eyedropper {
+            return false;
+        }
+        if response.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::Crosshair);
+        }
+        if !response.clicked() {
+            return false;
+        }
+        // The allocated rect already includes scroll offset, so only zoom needs undoing
+        let (Some(pos), Some(image)) = (response.interact_pointer_pos(), image) else { return false };
+        let (img_w, img_h) = (image.width(), image.height());
+        let px = (((pos.x - rect.min.x) / rect.width()) * img_w as f32).clamp(0.0, img_w as f32 - 1.0) as u32;
+        let py = (((pos.y - rect.min.y) / rect.height()) * img_h as f32).clamp(0.0, img_h as f32 - 1.0) as u32;
+        self.settings.picked_white = Some(sample_neutral_patch(image, px, py));
+        self.eyedropper = false;
+        true
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum TiltShiftGuide {
+    Top,
+    Center,
+    Bottom,
+}
+
+#[derive(Default)]
+pub struct TiltShiftFilter {
+    pub settings: TiltShiftSettings,
+    // Guide being dragged on the preview
+    drag: Option<TiltShiftGuide>,
+}
+
+impl Filter for TiltShiftFilter {
+    fn id(&self) -> &'static str {
+        "tilt_shift"
+    }
+
+    fn name(&self) -> &'static str {
+        "Tilt-Shift"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_tilt_shift(image.clone(), &self.settings)
+    }
+
+    fn background_job(&self) -> Option<FilterJob> {
+        let settings = self.settings.clone();
+        Some(Box::new(move |image, _| apply_tilt_shift(image.clone(), &settings)))
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = TiltShiftSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Focus Position:");
+        if ui.add(egui::Slider::new(&mut self.settings.focus_position, 0.0..=1.0).step_by(0.01))
+            .on_hover_text("Vertical center of the sharp band (drag the guides on the preview)").changed() {
+            changed = true;
+        }
+        ui.label("Band Height:");
+        if ui.add(egui::Slider::new(&mut self.settings.band_height, 0.0..=1.0).step_by(0.01)).changed() {
+            changed = true;
+        }
+        ui.label("Transition:");
+        if ui.add(egui::Slider::new(&mut self.settings.transition, 0.01..=1.0).step_by(0.01))
+            .on_hover_text("How far from the band the blur reaches full strength").changed() {
+            changed = true;
+        }
+        ui.label("Max Blur:");
+        if ui.add(egui::Slider::new(&mut self.settings.max_blur, 0.0..=30.0).text("px").step_by(0.5)).changed() {
+            changed = true;
+        }
+        ui.label("Saturation Boost:");
+        if ui.add(egui::Slider::new(&mut self.settings.saturation, 0.0..=100.0).text("%").step_by(1.0)).changed() {
+            changed = true;
+        }
+        changed
+    }
+
+    fn preview_sense(&self) -> egui::Sense {
+        egui::Sense::drag()
+    }
+
+    // Guides at the edges and centre of the sharp band, dragged to move or resize it
+    fn preview_overlay(&mut self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response, _image: Option<&DynamicImage>) -> bool {
+        let (band_top, band_bottom) = self.settings.band_edges();
+        let guide_y = |fraction: f32| rect.min.y + fraction * rect.height();
+        let guides = [
+            (TiltShiftGuide::Top, guide_y(band_top)),
+            (TiltShiftGuide::Center, guide_y(self.settings.focus_position)),
+            (TiltShiftGuide::Bottom, guide_y(band_bottom)),
+        ];
+
+        if response.drag_started() {
+            if let Some(pos) = response.interact_pointer_pos() {
+                self.drag = guides
+                    .iter()
+                    .filter(|(_, y)| (pos.y - y).abs() < 10.0)
+                    .min_by(|a, b| (pos.y - a.1).abs().total_cmp(&(pos.y - b.1).abs()))
+                    .map(|(guide, _)| *guide);
+            }
+        }
+        if let (Some(guide), Some(pos)) = (self.drag, response.interact_pointer_pos()) {
+            let fraction = ((pos.y - rect.min.y) / rect.height()).clamp(0.0, 1.0);
+            match guide {
+                TiltShiftGuide::Center => self.settings.focus_position = fraction,
+                TiltShiftGuide::Top | TiltShiftGuide::Bottom => {
+                    self.settings.band_height = ((fraction - self.settings.focus_position).abs() * 2.0).min(1.0);
+                }
+            }
+        }
+        // Only re-render once the guide is released; the blur is too slow to follow the pointer
+        let released = response.drag_stopped() && self.drag.take().is_some();
+
+        let hovering_guide = response.hover_pos().is_some_and(|pos| guides.iter().any(|(_, y)| (pos.y - y).abs() < 10.0));
+        if self.drag.is_some() || hovering_guide {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeVertical);
+        }
+
+        let painter = ui.painter_at(rect);
+        for (guide, y) in guides {
+            let stroke = if guide == TiltShiftGuide::Center {
+                egui::Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(255, 255, 255, 140))
+            } else {
+                egui::Stroke::new(1.5, egui::Color32::from_rgb(255, 200, 60))
+            };
+            painter.hline(rect.x_range(), y, stroke);
+        }
+        released
+    }
+}
+
+#[derive(Default)]
+pub struct SolarizeFilter {
+    pub settings: SolarizeSettings,
+}
+
+impl Filter for SolarizeFilter {
+    fn id(&self) -> &'static str {
+        "solarize"
+    }
+
+    fn name(&self) -> &'static str {
+        "Solarize"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_solarize(image.clone(), &self.settings)
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = SolarizeSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Apply To:");
+        let current_mode = self.settings.mode.clone();
+        egui::ComboBox::from_id_salt("solarize_mode").selected_text(current_mode.name()).show_ui(ui, |ui| {
+            ui.selectable_value(&mut self.settings.mode, SolarizeMode::PerChannel, SolarizeMode::PerChannel.name());
+            ui.selectable_value(&mut self.settings.mode, SolarizeMode::Luminance, SolarizeMode::Luminance.name());
+        });
+        if current_mode != self.settings.mode {
+            changed = true;
+        }
+        let current_direction = self.settings.direction.clone();
+        ui.radio_value(&mut self.settings.direction, SolarizeDirection::Highlights, SolarizeDirection::Highlights.name());
+        ui.radio_value(&mut self.settings.direction, SolarizeDirection::Shadows, SolarizeDirection::Shadows.name());
+        if current_direction != self.settings.direction {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.label("Threshold:");
+        if ui.add(egui::Slider::new(&mut self.settings.threshold, 0.0..=255.0).step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.label("Rolloff:");
+        if ui.add(egui::Slider::new(&mut self.settings.rolloff, 1.0..=128.0).step_by(1.0))
+            .on_hover_text("Width of the transition around the threshold").changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        if ui.checkbox(&mut self.settings.double, "Double Solarize")
+            .on_hover_text("Solarize a second time for a metallic look").changed() {
+            changed = true;
+        }
+        if self.settings.double {
+            ui.label("Secondary Threshold:");
+            if ui.add(egui::Slider::new(&mut self.settings.secondary_threshold, 0.0..=255.0).step_by(1.0)).changed() {
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct WatercolorFilter {
+    pub settings: WatercolorSettings,
+}
+
+impl Filter for WatercolorFilter {
+    fn id(&self) -> &'static str {
+        "watercolor"
+    }
+
+    fn name(&self) -> &'static str {
+        "Watercolor"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_watercolor(image.clone(), &self.settings, None)
+    }
+
+    fn background_job(&self) -> Option<FilterJob> {
+        let settings = self.settings.clone();
+        Some(Box::new(move |image, progress| apply_watercolor(image.clone(), &settings, Some(progress))))
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = WatercolorSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Smoothing:");
+        if ui.add(egui::Slider::new(&mut self.settings.smoothing, 1..=6).text("passes"))
+            .on_hover_text("Edge-preserving smoothing passes (slower when higher)").changed() {
+            changed = true;
+        }
+        ui.label("Color Bleed:");
+        if ui.add(egui::Slider::new(&mut self.settings.bleed, 0.0..=100.0).text("%").step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.label("Paper Texture:");
+        if ui.add(egui::Slider::new(&mut self.settings.texture, 0.0..=100.0).text("%").step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            ui.label(format!("Seed: {}", self.settings.seed));
+            if ui.button("🎲 New Seed").clicked() {
+                self.settings.seed = next_seed(self.settings.seed);
+                changed = true;
+            }
+        });
+        ui.label("Edge Darkening:");
+        if ui.add(egui::Slider::new(&mut self.settings.edge_darkening, 0.0..=100.0).text("%").step_by(1.0))
+            .on_hover_text("Pigment pooling along edges").changed() {
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct SketchFilter {
+    pub settings: SketchSettings,
+}
+
+impl Filter for SketchFilter {
+    fn id(&self) -> &'static str {
+        "sketch"
+    }
+
+    fn name(&self) -> &'static str {
+        "Pencil Sketch"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_sketch(image.clone(), &self.settings)
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = SketchSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Style:");
+        let current_mode = self.settings.mode.clone();
+        egui::ComboBox::from_id_salt("sketch_mode").selected_text(current_mode.name()).show_ui(ui, |ui| {
+            ui.selectable_value(&mut self.settings.mode, SketchMode::Pencil, SketchMode::Pencil.name());
+            ui.selectable_value(&mut self.settings.mode, SketchMode::ColoredPencil, SketchMode::ColoredPencil.name());
+            ui.selectable_value(&mut self.settings.mode, SketchMode::LineArt, SketchMode::LineArt.name());
+        });
+        if current_mode != self.settings.mode {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        if self.settings.mode == SketchMode::LineArt {
+            ui.label("Edge Threshold:");
+            if ui.add(egui::Slider::new(&mut self.settings.edge_threshold, 5.0..=150.0).step_by(1.0))
+                .on_hover_text("Higher = fewer, bolder lines").changed() {
+                changed = true;
+            }
+        } else {
+            ui.label("Stroke Softness:");
+            if ui.add(egui::Slider::new(&mut self.settings.blur_radius, 0.5..=30.0).text("px").step_by(0.5)).changed() {
+                changed = true;
+            }
+            ui.label("Darkness:");
+            if ui.add(egui::Slider::new(&mut self.settings.darkness, 0.0..=100.0).text("%").step_by(1.0)).changed() {
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct MosaicFilter {
+    pub settings: MosaicSettings,
+}
+
+impl Filter for MosaicFilter {
+    fn id(&self) -> &'static str {
+        "mosaic"
+    }
+
+    fn name(&self) -> &'static str {
+        "Mosaic Tiles"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_mosaic(image.clone(), &self.settings)
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = MosaicSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Tile Size:");
+        if ui.add(egui::Slider::new(&mut self.settings.tile_size, 4..=96).text("px")).changed() {
+            changed = true;
+        }
+        ui.label("Variation:");
+        if ui.add(egui::Slider::new(&mut self.settings.variation, 0.0..=100.0).text("%").step_by(1.0))
+            .on_hover_text("Per-tile color and rotation jitter").changed() {
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            ui.label(format!("Seed: {}", self.settings.seed));
+            if ui.button("🎲 New Seed").clicked() {
+                self.settings.seed = next_seed(self.settings.seed);
+                changed = true;
+            }
+        });
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Grout:");
+        let max_grout = self.settings.tile_size as f32 / 3.0;
+        if ui.add(egui::Slider::new(&mut self.settings.grout_width, 0.0..=max_grout).text("px").step_by(0.5)).changed() {
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            ui.label("Color:");
+            let [r, g, b] = self.settings.grout_color;
+            let mut grout_color = egui::Color32::from_rgb(r, g, b);
+            if ui.color_edit_button_srgba(&mut grout_color).changed() {
+                self.settings.grout_color = [grout_color.r(), grout_color.g(), grout_color.b()];
+                changed = true;
+            }
+        });
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct NeonFilter {
+    pub settings: NeonSettings,
+}
+
+impl Filter for NeonFilter {
+    fn id(&self) -> &'static str {
+        "neon"
+    }
+
+    fn name(&self) -> &'static str {
+        "Neon Glow"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_neon(image.clone(), &self.settings)
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = NeonSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Tube Color:");
+        let current_mode = self.settings.color_mode.clone();
+        egui::ComboBox::from_id_salt("neon_color_mode").selected_text(current_mode.name()).show_ui(ui, |ui| {
+            ui.selectable_value(&mut self.settings.color_mode, NeonColorMode::Single, NeonColorMode::Single.name());
+            ui.selectable_value(&mut self.settings.color_mode, NeonColorMode::SourceHue, NeonColorMode::SourceHue.name());
+            ui.selectable_value(&mut self.settings.color_mode, NeonColorMode::RainbowDirection, NeonColorMode::RainbowDirection.name());
+        });
+        if current_mode != self.settings.color_mode {
+            changed = true;
+        }
+        if self.settings.color_mode == NeonColorMode::Single {
+            ui.horizontal(|ui| {
+                ui.label("Color:");
+                let [r, g, b] = self.settings.neon_color;
+                let mut neon_color = egui::Color32::from_rgb(r, g, b);
+                if ui.color_edit_button_srgba(&mut neon_color).changed() {
+                    self.settings.neon_color = [neon_color.r(), neon_color.g(), neon_color.b()];
+                    changed = true;
+                }
+            });
+        }
+        ui.add_space(10.0);
+        ui.label("Edge Threshold:");
+        if ui.add(egui::Slider::new(&mut self.settings.edge_threshold, 5.0..=150.0).step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.label("Glow Radius:");
+        if ui.add(egui::Slider::new(&mut self.settings.glow_radius, 0.0..=30.0).text("px").step_by(0.5)).changed() {
+            changed = true;
+        }
+        ui.label("Glow Intensity:");
+        if ui.add(egui::Slider::new(&mut self.settings.glow_intensity, 0.0..=400.0).text("%").step_by(5.0)).changed() {
+            changed = true;
+        }
+        ui.label("Background Dimming:");
+        if ui.add(egui::Slider::new(&mut self.settings.background_dim, 0.0..=100.0).text("%").step_by(1.0)).changed() {
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct KaleidoscopeFilter {
+    pub settings: KaleidoscopeSettings,
+}
+
+impl Filter for KaleidoscopeFilter {
+    fn id(&self) -> &'static str {
+        "kaleidoscope"
+    }
+
+    fn name(&self) -> &'static str {
+        "Kaleidoscope"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_kaleidoscope(image.clone(), &self.settings)
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = KaleidoscopeSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Segments:");
+        if ui.add(egui::Slider::new(&mut self.settings.segments, 3..=24)).changed() {
+            changed = true;
+        }
+        ui.label("Rotation:");
+        if ui.add(egui::Slider::new(&mut self.settings.rotation, 0.0..=360.0).text("°").step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.label("Zoom:");
+        if ui.add(egui::Slider::new(&mut self.settings.zoom, 0.25..=4.0).logarithmic(true)).changed() {
+            changed = true;
+        }
+        if ui.checkbox(&mut self.settings.mirrored, "Mirrored Segments")
+            .on_hover_text("Reflect alternate wedges instead of rotating copies").changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Center Point:");
+        ui.horizontal(|ui| {
+            ui.label("X:");
+            if ui.add(egui::Slider::new(&mut self.settings.center_x, 0.0..=1.0).text("position").step_by(0.01)).changed() {
+                changed = true;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Y:");
+            if ui.add(egui::Slider::new(&mut self.settings.center_y, 0.0..=1.0).text("position").step_by(0.01)).changed() {
+                changed = true;
+            }
+        });
+        ui.add_space(5.0);
+        if ui.button("Reset Center").clicked() {
+            self.settings.center_x = 0.5;
+            self.settings.center_y = 0.5;
+            changed = true;
+        }
+        changed
+    }
+
+    fn preview_sense(&self) -> egui::Sense {
+        egui::Sense::click_and_drag()
+    }
+
+    fn preview_overlay(&mut self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response, _image: Option<&DynamicImage>) -> bool {
+        drag_center(ui, rect, response, &mut self.settings.center_x, &mut self.settings.center_y)
+    }
+}
+
+#[derive(Default)]
+pub struct DrosteFilter {
+    pub settings: DrosteSettings,
+}
+
+impl Filter for DrosteFilter {
+    fn id(&self) -> &'static str {
+        "droste"
+    }
+
+    fn name(&self) -> &'static str {
+        "Droste Effect"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_droste(image.clone(), &self.settings)
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = DrosteSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Radii:");
+        let outer_radius = self.settings.outer_radius;
+        if ui.add(egui::Slider::new(&mut self.settings.inner_radius, 0.05..=outer_radius - 0.05).text("inner").step_by(0.01)).changed() {
+            changed = true;
+        }
+        let inner_radius = self.settings.inner_radius;
+        if ui.add(egui::Slider::new(&mut self.settings.outer_radius, inner_radius + 0.05..=1.5).text("outer").step_by(0.01)).changed() {
+            changed = true;
+        }
+        ui.label("Spiral Turns:");
+        if ui.add(egui::Slider::new(&mut self.settings.turns, -3..=3))
+            .on_hover_text("0 = plain nesting, negative = counter-clockwise").changed() {
+            changed = true;
+        }
+        ui.label("Strength:");
+        if ui.add(egui::Slider::new(&mut self.settings.strength, 0.0..=100.0).text("%").step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Center Point:");
+        ui.horizontal(|ui| {
+            ui.label("X:");
+            if ui.add(egui::Slider::new(&mut self.settings.center_x, 0.0..=1.0).text("position").step_by(0.01)).changed() {
+                changed = true;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Y:");
+            if ui.add(egui::Slider::new(&mut self.settings.center_y, 0.0..=1.0).text("position").step_by(0.01)).changed() {
+                changed = true;
+            }
+        });
+        ui.add_space(5.0);
+        if ui.button("Reset Center").clicked() {
+            self.settings.center_x = 0.5;
+            self.settings.center_y = 0.5;
+            changed = true;
+        }
+        changed
+    }
+
+    fn preview_sense(&self) -> egui::Sense {
+        egui::Sense::click_and_drag()
+    }
+
+    fn preview_overlay(&mut self, ui: &egui::Ui, rect: egui::Rect, response: &egui::Response, _image: Option<&DynamicImage>) -> bool {
+        drag_center(ui, rect, response, &mut self.settings.center_x, &mut self.settings.center_y)
+    }
+}
+
+#[derive(Default)]
+pub struct EngravingFilter {
+    pub settings: EngravingSettings,
+}
+
+impl Filter for EngravingFilter {
+    fn id(&self) -> &'static str {
+        "engraving"
+    }
+
+    fn name(&self) -> &'static str {
+        "Engraving"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_engraving(image.clone(), &self.settings)
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = EngravingSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Line Spacing:");
+        if ui.add(egui::Slider::new(&mut self.settings.line_spacing, 3.0..=24.0).text("px").step_by(0.5)).changed() {
+            changed = true;
+        }
+        ui.label("Angles:");
+        if ui.add(egui::Slider::new(&mut self.settings.angle, 0.0..=180.0).text("° main").step_by(1.0)).changed() {
+            changed = true;
+        }
+        if ui.add(egui::Slider::new(&mut self.settings.cross_angle, 15.0..=90.0).text("° cross").step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.label("Hatch Layers:");
+        if ui.add(egui::Slider::new(&mut self.settings.layers, 1..=3))
+            .on_hover_text("Extra layers cross-hatch the darker tones").changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Line Thickness:");
+        let max_thickness = self.settings.max_thickness;
+        if ui.add(egui::Slider::new(&mut self.settings.min_thickness, 0.0..=max_thickness).text("min").step_by(0.01)).changed() {
+            changed = true;
+        }
+        let min_thickness = self.settings.min_thickness;
+        if ui.add(egui::Slider::new(&mut self.settings.max_thickness, min_thickness..=1.0).text("max").step_by(0.01)).changed() {
+            changed = true;
+        }
+        if ui.checkbox(&mut self.settings.follow_flow, "Follow Image Contours")
+            .on_hover_text("Bend the lines with the image's tones").changed() {
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct DotMatrixFilter {
+    pub settings: DotMatrixSettings,
+}
+
+impl Filter for DotMatrixFilter {
+    fn id(&self) -> &'static str {
+        "dot_matrix"
+    }
+
+    fn name(&self) -> &'static str {
+        "Dot Matrix"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_dot_matrix(image.clone(), &self.settings)
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = DotMatrixSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Ribbon:");
+        let current_ribbon = self.settings.ribbon.clone();
+        egui::ComboBox::from_id_salt("dot_matrix_ribbon").selected_text(current_ribbon.name()).show_ui(ui, |ui| {
+            ui.selectable_value(&mut self.settings.ribbon, RibbonColor::Black, RibbonColor::Black.name());
+            ui.selectable_value(&mut self.settings.ribbon, RibbonColor::Blue, RibbonColor::Blue.name());
+        });
+        if current_ribbon != self.settings.ribbon {
+            changed = true;
+        }
+        ui.label("Pin Size:");
+        if ui.add(egui::Slider::new(&mut self.settings.pin_size, 2.0..=16.0).text("px").step_by(0.5)).changed() {
+            changed = true;
+        }
+        ui.label("Band Height:");
+        if ui.add(egui::Slider::new(&mut self.settings.band_height, 1..=24).text("rows"))
+            .on_hover_text("Pin rows per print head pass").changed() {
+            changed = true;
+        }
+        ui.label("Ink Darkness:");
+        if ui.add(egui::Slider::new(&mut self.settings.ink_darkness, 10.0..=100.0).text("%").step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.label("Misregistration:");
+        if ui.add(egui::Slider::new(&mut self.settings.misregistration, 0.0..=8.0).text("px").step_by(0.25)).changed() {
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            ui.label(format!("Seed: {}", self.settings.seed));
+            if ui.button("🎲 New Seed").clicked() {
+                self.settings.seed = next_seed(self.settings.seed);
+                changed = true;
+            }
+        });
+        ui.add_space(10.0);
+        ui.separator();
+        if ui.checkbox(&mut self.settings.tractor_feed, "Tractor-Feed Paper")
+            .on_hover_text("Green-bar paper with perforated feed strips").changed() {
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct StainedGlassFilter {
+    pub settings: StainedGlassSettings,
+}
+
+impl Filter for StainedGlassFilter {
+    fn id(&self) -> &'static str {
+        "stained_glass"
+    }
+
+    fn name(&self) -> &'static str {
+        "Stained Glass"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_stained_glass(image.clone(), &self.settings)
+    }
+
+    fn background_job(&self) -> Option<FilterJob> {
+        let settings = self.settings.clone();
+        Some(Box::new(move |image, _| apply_stained_glass(image.clone(), &settings)))
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = StainedGlassSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Cell Count:");
+        if ui.add(egui::Slider::new(&mut self.settings.cell_count, 20..=4000).logarithmic(true)).changed() {
+            changed = true;
+        }
+        ui.label("Lead Width:");
+        if ui.add(egui::Slider::new(&mut self.settings.border_width, 0.0..=16.0).text("px").step_by(0.5)).changed() {
+            changed = true;
+        }
+        ui.label("Saturation Boost:");
+        if ui.add(egui::Slider::new(&mut self.settings.saturation_boost, 0.0..=100.0).text("%").step_by(1.0)).changed() {
+            changed = true;
+        }
+        if ui.checkbox(&mut self.settings.backlight, "Backlit Panes")
+            .on_hover_text("Brighten each pane towards its middle").changed() {
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            ui.label(format!("Seed: {}", self.settings.seed));
+            if ui.button("🎲 New Seed").clicked() {
+                self.settings.seed = next_seed(self.settings.seed);
+                changed = true;
+            }
+        });
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct InfraredFilter {
+    pub settings: InfraredSettings,
+}
+
+impl Filter for InfraredFilter {
+    fn id(&self) -> &'static str {
+        "infrared"
+    }
+
+    fn name(&self) -> &'static str {
+        "Infrared Film"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_infrared(image.clone(), &self.settings)
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = InfraredSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Channel Mix:");
+        let current_preset = self.settings.preset.clone();
+        egui::ComboBox::from_id_salt("infrared_preset").selected_text(current_preset.name()).show_ui(ui, |ui| {
+            ui.selectable_value(&mut self.settings.preset, InfraredPreset::FalseColor, InfraredPreset::FalseColor.name());
+            ui.selectable_value(&mut self.settings.preset, InfraredPreset::BlackAndWhite, InfraredPreset::BlackAndWhite.name());
+            ui.selectable_value(&mut self.settings.preset, InfraredPreset::Custom, InfraredPreset::Custom.name());
+        });
+        if current_preset != self.settings.preset {
+            // Start the custom mixer from whichever preset was showing
+            if let (InfraredPreset::Custom, Some(matrix)) = (&self.settings.preset, current_preset.matrix()) {
+                self.settings.custom_mixer = matrix;
+            }
+            changed = true;
+        }
+        if self.settings.preset == InfraredPreset::Custom {
+            egui::Grid::new("infrared_mixer").num_columns(4).show(ui, |ui| {
+                ui.label("");
+                ui.label("R in");
+                ui.label("G in");
+                ui.label("B in");
+                ui.end_row();
+                for (row, label) in self.settings.custom_mixer.iter_mut().zip(["R out", "G out", "B out"]) {
+                    ui.label(label);
+                    for value in row.iter_mut() {
+                        changed |= ui.add(egui::DragValue::new(value).speed(0.01).range(-2.0..=2.0)).changed();
+                    }
+                    ui.end_row();
+                }
+            });
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Highlight Glow:");
+        if ui.add(egui::Slider::new(&mut self.settings.glow, 0.0..=100.0).text("%").step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.label("Grain:");
+        if ui.add(egui::Slider::new(&mut self.settings.grain, 0.0..=100.0).text("%").step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            ui.label(format!("Seed: {}", self.settings.seed));
+            if ui.button("🎲 New Seed").clicked() {
+                self.settings.seed = next_seed(self.settings.seed);
+                changed = true;
+            }
+        });
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct LomoFilter {
+    pub settings: LomoSettings,
+}
+
+impl Filter for LomoFilter {
+    fn id(&self) -> &'static str {
+        "lomo"
+    }
+
+    fn name(&self) -> &'static str {
+        "Lomo Camera"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_lomo(image.clone(), &self.settings)
+    }
+
+    fn background_job(&self) -> Option<FilterJob> {
+        let settings = self.settings.clone();
+        Some(Box::new(move |image, _| apply_lomo(image.clone(), &settings)))
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = LomoSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Preset:");
+        let current_preset = self.settings.preset.clone();
+        egui::ComboBox::from_id_salt("lomo_preset").selected_text(current_preset.name()).show_ui(ui, |ui| {
+            ui.selectable_value(&mut self.settings.preset, LomoPreset::Classic, LomoPreset::Classic.name());
+            ui.selectable_value(&mut self.settings.preset, LomoPreset::CrossProcessed, LomoPreset::CrossProcessed.name());
+            ui.selectable_value(&mut self.settings.preset, LomoPreset::Dreamy, LomoPreset::Dreamy.name());
+            ui.selectable_value(&mut self.settings.preset, LomoPreset::Custom, LomoPreset::Custom.name());
+        });
+        if current_preset != self.settings.preset {
+            self.settings.apply_preset();
+            changed = true;
+        }
+        ui.add_space(10.0);
+        // Touching any individual slider turns the preset into a custom mix
+        let mut tweaked = false;
+        ui.label("Vignette:");
+        tweaked |= ui.add(egui::Slider::new(&mut self.settings.vignette, 0.0..=100.0).text("%").step_by(1.0)).changed();
+        ui.label("Cross Process:");
+        tweaked |= ui.add(egui::Slider::new(&mut self.settings.cross_process, 0.0..=100.0).text("%").step_by(1.0)).changed();
+        ui.label("Saturation:");
+        tweaked |= ui.add(egui::Slider::new(&mut self.settings.saturation, 0.0..=100.0).text("%").step_by(1.0)).changed();
+        ui.label("Soft Focus:");
+        tweaked |= ui.add(egui::Slider::new(&mut self.settings.soft_focus, 0.0..=100.0).text("%").step_by(1.0))
+            .on_hover_text("Blur towards the frame edges").changed();
+        ui.label("Light Leak:");
+        tweaked |= ui.add(egui::Slider::new(&mut self.settings.light_leak, 0.0..=100.0).text("%").step_by(1.0)).changed();
+        if tweaked {
+            self.settings.preset = LomoPreset::Custom;
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            ui.label(format!("Seed: {}", self.settings.seed));
+            if ui.button("🎲 New Seed").on_hover_text("Move the light leaks").clicked() {
+                self.settings.seed = next_seed(self.settings.seed);
+                changed = true;
+            }
+        });
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct OldPhotoFilter {
+    pub settings: OldPhotoSettings,
+}
+
+impl Filter for OldPhotoFilter {
+    fn id(&self) -> &'static str {
+        "old_photo"
+    }
+
+    fn name(&self) -> &'static str {
+        "Old Photo"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_old_photo(image.clone(), &self.settings)
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = OldPhotoSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Age:");
+        if ui.add(egui::Slider::new(&mut self.settings.age, 0.0..=100.0).text("%").step_by(1.0))
+            .on_hover_text("Sepia, fading, vignetting and amount of damage").changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        ui.separator();
+        ui.label("Damage:");
+        if ui.checkbox(&mut self.settings.dust, "Dust Specks").changed() {
+            changed = true;
+        }
+        if ui.checkbox(&mut self.settings.scratches, "Hair Scratches").changed() {
+            changed = true;
+        }
+        if ui.checkbox(&mut self.settings.border_damage, "Worn Borders").changed() {
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            ui.label(format!("Seed: {}", self.settings.seed));
+            if ui.button("🎲 New Seed").clicked() {
+                self.settings.seed = next_seed(self.settings.seed);
+                changed = true;
+            }
+        });
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct OrtonFilter {
+    pub settings: OrtonSettings,
+}
+
+impl Filter for OrtonFilter {
+    fn id(&self) -> &'static str {
+        "orton"
+    }
+
+    fn name(&self) -> &'static str {
+        "Orton Glow"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_orton(image.clone(), &self.settings)
+    }
+
+    fn background_job(&self) -> Option<FilterJob> {
+        let settings = self.settings.clone();
+        Some(Box::new(move |image, _| apply_orton(image.clone(), &settings)))
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = OrtonSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Blur Radius:");
+        if ui.add(egui::Slider::new(&mut self.settings.blur_radius, 0.0..=50.0).text("px").step_by(0.5)).changed() {
+            changed = true;
+        }
+        ui.label("Glow Strength:");
+        if ui.add(egui::Slider::new(&mut self.settings.glow_strength, 0.0..=100.0).text("%").step_by(1.0)).changed() {
+            changed = true;
+        }
+        ui.label("Highlight Protection:");
+        if ui.add(egui::Slider::new(&mut self.settings.highlight_protection, 0.0..=100.0).text("%").step_by(1.0))
+            .on_hover_text("Reduce the glow in bright areas like skies").changed() {
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[derive(Default)]
+pub struct RetroFilter {
+    pub settings: RetroSettings,
+}
+
+impl Filter for RetroFilter {
+    fn id(&self) -> &'static str {
+        "retro"
+    }
+
+    fn name(&self) -> &'static str {
+        "Retro Console"
+    }
+
+    fn apply(&self, image: &DynamicImage) -> RgbaImage {
+        apply_retro(image.clone(), &self.settings)
+    }
+
+    fn texture_options(&self) -> egui::TextureOptions {
+        egui::TextureOptions::NEAREST
+    }
+
+    fn preview_scale(&self) -> PreviewScale {
+        PreviewScale::Zoom
+    }
+
+    fn reset_settings(&mut self) {
+        self.settings = RetroSettings::default();
+    }
+
+    fn settings_ui(&mut self, ui: &mut egui::Ui, _image: Option<&DynamicImage>) -> bool {
+        let mut changed = false;
+        ui.label("Console:");
+        let current_console = self.settings.console;
+        egui::ComboBox::from_id_salt("retro_console").selected_text(current_console.name()).show_ui(ui, |ui| {
+            for console in RetroConsole::ALL {
+                ui.selectable_value(&mut self.settings.console, console, console.name());
+            }
+        });
+        if current_console != self.settings.console {
+            self.settings = self.settings.console.settings();
+            changed = true;
+        }
+        if ui.checkbox(&mut self.settings.dither, "Ordered Dithering").changed() {
+            changed = true;
+        }
+        ui.add_space(10.0);
+        egui::CollapsingHeader::new("Advanced").default_open(false).show(ui, |ui| {
+            ui.label("Resolution:");
+            if ui.add(egui::Slider::new(&mut self.settings.width, 32..=640).text("width")).changed() {
+                changed = true;
+            }
+            if ui.add(egui::Slider::new(&mut self.settings.height, 32..=480).text("height")).changed() {
+                changed = true;
+            }
+            ui.label("Palette:");
+            let current_palette = self.settings.palette;
+            egui::ComboBox::from_id_salt("retro_palette").selected_text(current_palette.name()).show_ui(ui, |ui| {
+                for console in RetroConsole::ALL {
+                    ui.selectable_value(&mut self.settings.palette, console, console.name());
+                }
+            });
+            if current_palette != self.settings.palette {
+                changed = true;
+            }
+            if ui.add(egui::Slider::new(&mut self.settings.max_colors, 2..=256).text("colors on screen")).changed() {
+                changed = true;
+            }
+            if self.settings.dither
+                && ui.add(egui::Slider::new(&mut self.settings.dither_strength, 0.0..=100.0).text("dither %").step_by(1.0)).changed() {
+                changed = true;
+            }
+            ui.label("Upscale:");
+            let scale_text = if self.settings.scale == 0 { "× (auto)" } else { "×" };
+            if ui.add(egui::Slider::new(&mut self.settings.scale, 0..=8).text(scale_text))
+                .on_hover_text("0 = scale back to roughly the source size").changed() {
+                changed = true;
+            }
+        });
+        changed
+    }
+}
+
+// Square tone-curve editor; returns true when the control points changed
+fn curve_editor(ui: &mut egui::Ui, points: &mut Vec<[f32; 2]>, drag_index: &mut Option<usize>) -> bool {
+    let size = ui.available_width().min(260.0);
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::click_and_drag());
+    let painter = ui.painter_at(rect);
+
+    let to_screen = |p: [f32; 2]| egui::pos2(rect.min.x + p[0] * rect.width(), rect.max.y - p[1] * rect.height());
+    let from_screen = |pos: egui::Pos2| [
+        ((pos.x - rect.min.x) / rect.width()).clamp(0.0, 1.0),
+        ((rect.max.y - pos.y) / rect.height()).clamp(0.0, 1.0),
+    ];
+    let nearest_point = |points: &[[f32; 2]], pos: egui::Pos2| {
+        points.iter()
+            .enumerate()
+            .map(|(i, p)| (i, to_screen(*p).distance(pos)))
+            .filter(|(_, d)| *d < 10.0)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    };
+
+    let mut changed = false;
+
+    if response.drag_started() {
+        if let Some(pos) = response.interact_pointer_pos() {
+            *drag_index = nearest_point(points, pos);
+        }
+    }
+    if response.dragged() {
+        if let (Some(index), Some(pos)) = (*drag_index, response.interact_pointer_pos()) {
+            let [mut x, y] = from_screen(pos);
+            // Endpoints stay pinned to the edges, interior points stay between their neighbours
+            if index == 0 {
+                x = 0.0;
+            } else if index == points.len() - 1 {
+                x = 1.0;
+            } else {
+                x = x.clamp(points[index - 1][0] + 0.01, points[index + 1][0] - 0.01);
+            }
+            if points[index] != [x, y] {
+                points[index] = [x, y];
+                changed = true;
+            }
+        }
+    }
+    if response.drag_stopped() {
+        *drag_index = None;
+    }
+    if response.clicked() {
+        if let Some(pos) = response.interact_pointer_pos() {
+            if nearest_point(points, pos).is_none() {
+                let new_point = from_screen(pos);
+                let insert_at = points.iter().position(|p| p[0] > new_point[0]).unwrap_or(points.len());
+                if insert_at > 0 && insert_at < points.len() {
+                    points.insert(insert_at, new_point);
+                    changed = true;
+                }
+            }
+        }
+    }
+    if response.secondary_clicked() {
+        if let Some(pos) = response.interact_pointer_pos() {
+            if let Some(index) = nearest_point(points, pos) {
+                if index != 0 && index != points.len() - 1 {
+                    points.remove(index);
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    painter.rect_filled(rect, 2.0, egui::Color32::from_gray(24));
+    let grid_stroke = egui::Stroke::new(1.0, egui::Color32::from_gray(50));
+    for i in 1..4 {
+        let t = i as f32 / 4.0;
+        painter.line_segment([to_screen([t, 0.0]), to_screen([t, 1.0])], grid_stroke);
+        painter.line_segment([to_screen([0.0, t]), to_screen([1.0, t])], grid_stroke);
+    }
+    painter.line_segment([to_screen([0.0, 0.0]), to_screen([1.0, 1.0])], egui::Stroke::new(1.0, egui::Color32::from_gray(70)));
+
+    let curve: Vec<egui::Pos2> = (0..=128)
+        .map(|i| {
+            let x = i as f32 / 128.0;
+            to_screen([x, evaluate_curve(points, x).clamp(0.0, 1.0)])
+        })
+        .collect();
+    painter.add(egui::Shape::line(curve, egui::Stroke::new(2.0, egui::Color32::WHITE)));
+
+    for (i, p) in points.iter().enumerate() {
+        let color = if Some(i) == *drag_index { egui::Color32::YELLOW } else { egui::Color32::WHITE };
+        painter.circle_stroke(to_screen(*p), 4.0, egui::Stroke::new(2.0, color));
+    }
+
+    changed
+}
+
+// Average a 5x5 patch around the picked pixel so sensor noise doesn't skew the neutral point
+fn sample_neutral_patch(image: &DynamicImage, x: u32, y: u32) -> [u8; 3] {
+    let (width, height) = image.dimensions();
+    let mut sum = [0u32; 3];
+    let mut count = 0;
+    for sy in y.saturating_sub(2)..=(y + 2).min(height - 1) {
+        for sx in x.saturating_sub(2)..=(x + 2).min(width - 1) {
+            let p = image.get_pixel(sx, sy);
+            sum[0] += p[0] as u32;
+            sum[1] += p[1] as u32;
+            sum[2] += p[2] as u32;
+            count += 1;
+        }
+    }
+    [(sum[0] / count) as u8, (sum[1] / count) as u8, (sum[2] / count) as u8]
+}
@@ -1,10 +1,19 @@
-use image::{DynamicImage, RgbaImage, Rgba};
+use image::{DynamicImage, Rgba, RgbaImage};
 
+use crate::imageutils::sample_bilinear;
+use crate::progress::{self, Progress};
+use crate::tiling::render_bands;
+
+/// Settings for [`apply_fisheye`].
 #[derive(Clone, PartialEq)]
 pub struct FisheyeSettings {
+    /// Positive values bulge the centre outwards, negative values pinch it.
     pub strength: f32,
+    /// Scale applied to the sampling radius; below 1.0 zooms in.
     pub zoom: f32,
+    /// Lens centre as a fraction of the width.
     pub center_x: f32,
+    /// Lens centre as a fraction of the height.
     pub center_y: f32,
 }
 
@@ -19,85 +28,97 @@ impl Default for FisheyeSettings {
     }
 }
 
-pub fn apply_fisheye(image: DynamicImage, settings: &FisheyeSettings) -> RgbaImage {
+/// Applies a radial fisheye distortion around the configured centre, reporting each
+/// output row to `progress`.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, Rgba, RgbaImage};
+/// use pixforge::fisheyeconverter::{apply_fisheye, FisheyeSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let distorted = apply_fisheye(image, &FisheyeSettings::default(), None);
+/// assert_eq!(distorted.dimensions(), (32, 32));
+/// ```
+pub fn apply_fisheye(image: DynamicImage, settings: &FisheyeSettings, progress: Option<&Progress>) -> RgbaImage {
+    let progress = progress::or_none(progress);
     let rgba_img = image.to_rgba8();
     let (width, height) = rgba_img.dimensions();
     let mut output = RgbaImage::new(width, height);
-    
-    let w = width as f32;
-    let h = height as f32;
-    
-    let cx = w * settings.center_x;
-    let cy = h * settings.center_y;
-    
-    let max_radius = ((w * w + h * h) / 4.0).sqrt();
-    let strength_factor = if settings.strength >= 0.0 {
-        1.0 + settings.strength * 2.0
-    } else {
-        1.0 / (1.0 - settings.strength * 2.0)
-    };
-    
-    for y in 0..height {
+    let lens = Lens::new(settings, width, height);
+
+    for y in progress.rows(height) {
         for x in 0..width {
-            let px = x as f32;
-            let py = y as f32;
-            
-            let dx = px - cx;
-            let dy = py - cy;
-            let distance = (dx * dx + dy * dy).sqrt();
-            
-            if distance < 0.1 {
-                output.put_pixel(x, y, *rgba_img.get_pixel(x, y));
-                continue;
-            }
-            
-            let normalized_distance = distance / max_radius;
-            let distorted_distance = normalized_distance.powf(strength_factor);
-            let scale = distorted_distance * max_radius / distance * settings.zoom;
-            
-            let src_x = cx + dx * scale;
-            let src_y = cy + dy * scale;
-            
-            let pixel = sample_bilinear(&rgba_img, src_x, src_y, width, height);
-            output.put_pixel(x, y, pixel);
+            output.put_pixel(x, y, lens.pixel(&rgba_img, x, y));
         }
     }
-    
+
+    output
+}
+
+/// Same as [`apply_fisheye`], but writes the output `band_height` rows at a time.
+pub fn apply_fisheye_tiled(image: DynamicImage, settings: &FisheyeSettings, band_height: u32, progress: Option<&Progress>) -> RgbaImage {
+    let progress = progress::or_none(progress);
+    let rgba_img = image.into_rgba8();
+    let (width, height) = rgba_img.dimensions();
+    let mut output = RgbaImage::new(width, height);
+    let lens = Lens::new(settings, width, height);
+
+    // Any output row can sample any input row, so bands read the whole input
+    render_bands(&mut output, band_height, progress, |start, band| {
+        for (i, pixel) in band.chunks_exact_mut(4).enumerate() {
+            let x = (i % width as usize) as u32;
+            let y = start + (i / width as usize) as u32;
+            pixel.copy_from_slice(&lens.pixel(&rgba_img, x, y).0);
+        }
+    });
+
     output
 }
 
-#[inline]
-fn sample_bilinear(img: &RgbaImage, x: f32, y: f32, width: u32, height: u32) -> Rgba<u8> {
-    if x < 0.0 || y < 0.0 || x >= (width - 1) as f32 || y >= (height - 1) as f32 {
-        return Rgba([0, 0, 0, 0]);
+// Lens geometry for one image size, shared by the full and tiled paths
+struct Lens {
+    cx: f32,
+    cy: f32,
+    max_radius: f32,
+    strength_factor: f32,
+    zoom: f32,
+}
+
+impl Lens {
+    fn new(settings: &FisheyeSettings, width: u32, height: u32) -> Self {
+        let w = width as f32;
+        let h = height as f32;
+        Self {
+            cx: w * settings.center_x,
+            cy: h * settings.center_y,
+            max_radius: ((w * w + h * h) / 4.0).sqrt(),
+            strength_factor: if settings.strength >= 0.0 {
+                1.0 + settings.strength * 2.0
+            } else {
+                1.0 / (1.0 - settings.strength * 2.0)
+            },
+            zoom: settings.zoom,
+        }
     }
-    
-    let x0 = x.floor() as u32;
-    let y0 = y.floor() as u32;
-    let x1 = (x0 + 1).min(width - 1);
-    let y1 = (y0 + 1).min(height - 1);
-    
-    let fx = x - x0 as f32;
-    let fy = y - y0 as f32;
-    
-    let p00 = img.get_pixel(x0, y0);
-    let p10 = img.get_pixel(x1, y0);
-    let p01 = img.get_pixel(x0, y1);
-    let p11 = img.get_pixel(x1, y1);
-    
-    let mut result = [0u8; 4];
-    for i in 0..4 {
-        let v00 = p00[i] as f32;
-        let v10 = p10[i] as f32;
-        let v01 = p01[i] as f32;
-        let v11 = p11[i] as f32;
-        
-        let v0 = v00 * (1.0 - fx) + v10 * fx;
-        let v1 = v01 * (1.0 - fx) + v11 * fx;
-        let v = v0 * (1.0 - fy) + v1 * fy;
-        
-        result[i] = v.clamp(0.0, 255.0) as u8;
+
+    fn pixel(&self, source: &RgbaImage, x: u32, y: u32) -> Rgba<u8> {
+        let dx = x as f32 - self.cx;
+        let dy = y as f32 - self.cy;
+        let distance = (dx * dx + dy * dy).sqrt();
+
+        if distance < 0.1 {
+            return *source.get_pixel(x, y);
+        }
+
+        let normalized_distance = distance / self.max_radius;
+        let distorted_distance = normalized_distance.powf(self.strength_factor);
+        let scale = distorted_distance * self.max_radius / distance * self.zoom;
+
+        let src_x = self.cx + dx * scale;
+        let src_y = self.cy + dy * scale;
+
+        sample_bilinear(source, src_x, src_y, source.width(), source.height())
     }
-    
-    Rgba(result)
-}
\ No newline at end of file
+}
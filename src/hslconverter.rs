@@ -0,0 +1,158 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+
+use crate::colorspace::{hsl_to_rgb, rgb_to_hsl};
+
+#[derive(Clone, PartialEq)]
+pub struct HslSettings {
+    pub hue: f32,
+    pub saturation: f32,
+    pub lightness: f32,
+    pub use_bands: bool,
+    pub bands: [HueBandAdjustment; 6],
+}
+
+#[derive(Clone, Copy, PartialEq, Default)]
+pub struct HueBandAdjustment {
+    pub hue: f32,
+    pub saturation: f32,
+    pub lightness: f32,
+}
+
+impl HueBandAdjustment {
+    fn is_neutral(&self) -> bool {
+        self.hue == 0.0 && self.saturation == 0.0 && self.lightness == 0.0
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HueBand {
+    Reds,
+    Yellows,
+    Greens,
+    Cyans,
+    Blues,
+    Magentas,
+}
+
+impl HueBand {
+    pub const ALL: [HueBand; 6] = [
+        HueBand::Reds,
+        HueBand::Yellows,
+        HueBand::Greens,
+        HueBand::Cyans,
+        HueBand::Blues,
+        HueBand::Magentas,
+    ];
+
+    pub fn name(&self) -> &str {
+        match self {
+            HueBand::Reds => "Reds",
+            HueBand::Yellows => "Yellows",
+            HueBand::Greens => "Greens",
+            HueBand::Cyans => "Cyans",
+            HueBand::Blues => "Blues",
+            HueBand::Magentas => "Magentas",
+        }
+    }
+
+    fn center(&self) -> f32 {
+        match self {
+            HueBand::Reds => 0.0,
+            HueBand::Yellows => 60.0,
+            HueBand::Greens => 120.0,
+            HueBand::Cyans => 180.0,
+            HueBand::Blues => 240.0,
+            HueBand::Magentas => 300.0,
+        }
+    }
+}
+
+impl Default for HslSettings {
+    fn default() -> Self {
+        Self {
+            hue: 0.0,
+            saturation: 0.0,
+            lightness: 0.0,
+            use_bands: false,
+            bands: [HueBandAdjustment::default(); 6],
+        }
+    }
+}
+
+impl HslSettings {
+    fn is_neutral(&self) -> bool {
+        self.hue == 0.0
+            && self.saturation == 0.0
+            && self.lightness == 0.0
+            && (!self.use_bands || self.bands.iter().all(|b| b.is_neutral()))
+    }
+}
+
+/// Shifts hue, saturation and lightness globally and per hue band.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::hslconverter::{apply_hsl, HslSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_hsl(image, &HslSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_hsl(image: DynamicImage, settings: &HslSettings) -> RgbaImage {
+    let rgba_img = image.to_rgba8();
+    if settings.is_neutral() {
+        return rgba_img;
+    }
+
+    let (width, height) = rgba_img.dimensions();
+    let mut output = RgbaImage::new(width, height);
+
+    for y in 0..height {
+        for x in 0..width {
+            let pixel = rgba_img.get_pixel(x, y);
+            let (h, s, l) = rgb_to_hsl(
+                pixel[0] as f32 / 255.0,
+                pixel[1] as f32 / 255.0,
+                pixel[2] as f32 / 255.0,
+            );
+
+            let mut hue_shift = settings.hue;
+            let mut sat_adjust = settings.saturation;
+            let mut light_adjust = settings.lightness;
+
+            // Neighbouring bands overlap linearly so the weights always sum to one;
+            // scaling by saturation keeps neutral grays out of every band
+            if settings.use_bands {
+                for (band, adjustment) in HueBand::ALL.iter().zip(settings.bands.iter()) {
+                    let distance = (h - band.center()).rem_euclid(360.0);
+                    let distance = distance.min(360.0 - distance);
+                    let weight = (1.0 - distance / 60.0).max(0.0) * s;
+                    hue_shift += adjustment.hue * weight;
+                    sat_adjust += adjustment.saturation * weight;
+                    light_adjust += adjustment.lightness * weight;
+                }
+            }
+
+            let new_h = h + hue_shift;
+            let new_s = (s * (1.0 + sat_adjust / 100.0)).clamp(0.0, 1.0);
+            let new_l = if light_adjust < 0.0 {
+                l * (1.0 + light_adjust / 100.0)
+            } else {
+                l + (1.0 - l) * light_adjust / 100.0
+            }
+            .clamp(0.0, 1.0);
+
+            let (r, g, b) = hsl_to_rgb(new_h, new_s, new_l);
+            output.put_pixel(x, y, Rgba([
+                (r * 255.0).round().clamp(0.0, 255.0) as u8,
+                (g * 255.0).round().clamp(0.0, 255.0) as u8,
+                (b * 255.0).round().clamp(0.0, 255.0) as u8,
+                pixel[3],
+            ]));
+        }
+    }
+
+    output
+}
@@ -0,0 +1,275 @@
+use image::{GrayImage, RgbImage, RgbaImage, Rgba};
+
+// Sobel gradient magnitude, normalized so the strongest possible edge is 255
+pub fn sobel_magnitude(gray: &GrayImage) -> Vec<f32> {
+    let (width, height) = gray.dimensions();
+    let mut magnitude = vec![0.0f32; (width * height) as usize];
+
+    for y in 0..height {
+        for x in 0..width {
+            let (gx, gy) = sobel_at(gray, x, y);
+            magnitude[(y * width + x) as usize] = (gx * gx + gy * gy).sqrt() / 4.0;
+        }
+    }
+
+    magnitude
+}
+
+// Raw Sobel (gx, gy) per pixel, for callers that need edge direction
+pub fn sobel_gradients(gray: &GrayImage) -> Vec<(f32, f32)> {
+    let (width, height) = gray.dimensions();
+    let mut gradients = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            gradients.push(sobel_at(gray, x, y));
+        }
+    }
+    gradients
+}
+
+#[inline]
+fn sobel_at(gray: &GrayImage, x: u32, y: u32) -> (f32, f32) {
+    let (width, height) = gray.dimensions();
+    let xl = x.saturating_sub(1);
+    let xr = (x + 1).min(width - 1);
+    let yu = y.saturating_sub(1);
+    let yd = (y + 1).min(height - 1);
+
+    let p = |px: u32, py: u32| gray.get_pixel(px, py)[0] as f32;
+
+    let gx = (p(xr, yu) + 2.0 * p(xr, y) + p(xr, yd)) - (p(xl, yu) + 2.0 * p(xl, y) + p(xl, yd));
+    let gy = (p(xl, yd) + 2.0 * p(x, yd) + p(xr, yd)) - (p(xl, yu) + 2.0 * p(x, yu) + p(xr, yu));
+    (gx, gy)
+}
+
+// Unsharp mask against a 3x3 box blur; edge pixels reuse their nearest neighbours
+pub fn sharpen_rgb(img: &RgbImage, amount: f32) -> RgbImage {
+    let (width, height) = img.dimensions();
+    RgbImage::from_fn(width, height, |x, y| {
+        let mut sum = [0.0f32; 3];
+        for ny in [y.saturating_sub(1), y, (y + 1).min(height - 1)] {
+            for nx in [x.saturating_sub(1), x, (x + 1).min(width - 1)] {
+                let pixel = img.get_pixel(nx, ny);
+                for c in 0..3 {
+                    sum[c] += pixel[c] as f32;
+                }
+            }
+        }
+        let pixel = img.get_pixel(x, y);
+        image::Rgb([0, 1, 2].map(|c| {
+            let value = pixel[c] as f32;
+            (value + (value - sum[c] / 9.0) * amount).round().clamp(0.0, 255.0) as u8
+        }))
+    })
+}
+
+// Shrink to `width` x `height` by averaging every source pixel under each output pixel.
+// Output pixel x covers source columns x * w / width up to (x + 1) * w / width, so each
+// source pixel counts towards exactly one output pixel.
+pub fn box_average(img: &RgbaImage, width: u32, height: u32) -> RgbaImage {
+    let (src_width, src_height) = img.dimensions();
+    let edge = |i: u32, src: u32, dst: u32| (i as u64 * src as u64 / dst as u64) as u32;
+    let column_cell: Vec<usize> = (0..width).flat_map(|x| (edge(x, src_width, width)..edge(x + 1, src_width, width)).map(move |_| x as usize)).collect();
+    let mut out = RgbaImage::new(width, height);
+    let mut sums = vec![[0u64; 4]; width as usize];
+    let mut counts = vec![0u64; width as usize];
+    for y in 0..height {
+        sums.iter_mut().for_each(|sum| *sum = [0; 4]);
+        counts.iter_mut().for_each(|count| *count = 0);
+        for sy in edge(y, src_height, height)..edge(y + 1, src_height, height) {
+            for (sx, &cell) in column_cell.iter().enumerate() {
+                let pixel = img.get_pixel(sx as u32, sy);
+                for c in 0..4 {
+                    sums[cell][c] += pixel[c] as u64;
+                }
+                counts[cell] += 1;
+            }
+        }
+        for x in 0..width as usize {
+            let count = counts[x].max(1);
+            out.put_pixel(x as u32, y, Rgba(sums[x].map(|sum| ((sum + count / 2) / count) as u8)));
+        }
+    }
+    out
+}
+
+// Grow a boolean mask by `radius` pixels using a separable square max filter
+pub fn dilate_mask(mask: &[bool], width: u32, height: u32, radius: u32) -> Vec<bool> {
+    if radius == 0 {
+        return mask.to_vec();
+    }
+    let w = width as i32;
+    let h = height as i32;
+    let r = radius as i32;
+
+    let mut horizontal = vec![false; mask.len()];
+    for y in 0..h {
+        for x in 0..w {
+            let x0 = (x - r).max(0);
+            let x1 = (x + r).min(w - 1);
+            horizontal[(y * w + x) as usize] = (x0..=x1).any(|sx| mask[(y * w + sx) as usize]);
+        }
+    }
+
+    let mut result = vec![false; mask.len()];
+    for y in 0..h {
+        let y0 = (y - r).max(0);
+        let y1 = (y + r).min(h - 1);
+        for x in 0..w {
+            result[(y * w + x) as usize] = (y0..=y1).any(|sy| horizontal[(sy * w + x) as usize]);
+        }
+    }
+
+    result
+}
+
+// Median-cut palette extraction: repeatedly split the box with the widest channel range
+pub fn median_cut(mut pixels: Vec<[u8; 3]>, count: usize) -> Vec<[u8; 3]> {
+    if pixels.is_empty() || count == 0 {
+        return Vec::new();
+    }
+
+    let mut boxes: Vec<Vec<[u8; 3]>> = vec![std::mem::take(&mut pixels)];
+    while boxes.len() < count {
+        let mut best: Option<(usize, usize, u8)> = None;
+        for (i, b) in boxes.iter().enumerate() {
+            if b.len() < 2 {
+                continue;
+            }
+            let (channel, range) = widest_channel(b);
+            if best.is_none_or(|(_, _, r)| range > r) {
+                best = Some((i, channel, range));
+            }
+        }
+        let Some((index, channel, range)) = best else { break };
+        if range == 0 {
+            break;
+        }
+        let mut b = boxes.swap_remove(index);
+        b.sort_unstable_by_key(|p| p[channel]);
+        let upper = b.split_off(b.len() / 2);
+        boxes.push(b);
+        boxes.push(upper);
+    }
+
+    boxes.iter().map(|b| average_color(b)).collect()
+}
+
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    let mut min = [255u8; 3];
+    let mut max = [0u8; 3];
+    for p in pixels {
+        for c in 0..3 {
+            min[c] = min[c].min(p[c]);
+            max[c] = max[c].max(p[c]);
+        }
+    }
+    (0..3)
+        .map(|c| (c, max[c] - min[c]))
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or((0, 0))
+}
+
+fn average_color(pixels: &[[u8; 3]]) -> [u8; 3] {
+    let mut sum = [0u64; 3];
+    for p in pixels {
+        for c in 0..3 {
+            sum[c] += p[c] as u64;
+        }
+    }
+    let n = pixels.len().max(1) as u64;
+    [(sum[0] / n) as u8, (sum[1] / n) as u8, (sum[2] / n) as u8]
+}
+
+#[inline]
+pub fn nearest_color(palette: &[[u8; 3]], color: [u8; 3]) -> [u8; 3] {
+    let mut best = palette[0];
+    let mut best_dist = i32::MAX;
+    for entry in palette {
+        let dr = entry[0] as i32 - color[0] as i32;
+        let dg = entry[1] as i32 - color[1] as i32;
+        let db = entry[2] as i32 - color[2] as i32;
+        let dist = dr * dr + dg * dg + db * db;
+        if dist < best_dist {
+            best_dist = dist;
+            best = *entry;
+        }
+    }
+    best
+}
+
+// Deterministic per-lattice-point noise in 0..1. A pure function of its arguments, so
+// every pixel, row or item can draw its own value in any order on any thread; this is
+// the source of randomness for all seeded converters.
+#[inline]
+pub fn hash_noise(x: i32, y: i32, seed: u64) -> f32 {
+    // Folding keeps seeds below 2^32 producing the same noise they always have
+    let seed = (seed ^ (seed >> 32)) as u32;
+    let mut h = (x as u32).wrapping_mul(0x8da6_b343) ^ (y as u32).wrapping_mul(0xd816_3841) ^ seed.wrapping_mul(0xcb1a_b31f);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0x5bd1_e995);
+    h ^= h >> 15;
+    (h & 0x00ff_ffff) as f32 / 0x00ff_ffff as f32
+}
+
+// Smoothly interpolated value noise with lattice spacing `scale` pixels, in 0..1
+pub fn value_noise(x: f32, y: f32, scale: f32, seed: u64) -> f32 {
+    let fx = x / scale;
+    let fy = y / scale;
+    let x0 = fx.floor();
+    let y0 = fy.floor();
+    let tx = fx - x0;
+    let ty = fy - y0;
+    let sx = tx * tx * (3.0 - 2.0 * tx);
+    let sy = ty * ty * (3.0 - 2.0 * ty);
+    let (xi, yi) = (x0 as i32, y0 as i32);
+
+    let top = hash_noise(xi, yi, seed) + (hash_noise(xi + 1, yi, seed) - hash_noise(xi, yi, seed)) * sx;
+    let bottom = hash_noise(xi, yi + 1, seed) + (hash_noise(xi + 1, yi + 1, seed) - hash_noise(xi, yi + 1, seed)) * sx;
+    top + (bottom - top) * sy
+}
+
+// Mirror a coordinate back into 0..=max so wedges reaching past the border still find pixels
+#[inline]
+pub fn reflect_coord(v: f32, max: f32) -> f32 {
+    let period = 2.0 * max;
+    let v = v.rem_euclid(period);
+    if v > max { period - v } else { v }
+}
+
+// Bilinear lookup; coordinates outside the image come back transparent
+#[inline]
+pub fn sample_bilinear(img: &RgbaImage, x: f32, y: f32, width: u32, height: u32) -> Rgba<u8> {
+    if x < 0.0 || y < 0.0 || x >= width.saturating_sub(1) as f32 || y >= height.saturating_sub(1) as f32 {
+        return Rgba([0, 0, 0, 0]);
+    }
+    
+    let x0 = x.floor() as u32;
+    let y0 = y.floor() as u32;
+    let x1 = (x0 + 1).min(width - 1);
+    let y1 = (y0 + 1).min(height - 1);
+    
+    let fx = x - x0 as f32;
+    let fy = y - y0 as f32;
+    
+    let p00 = img.get_pixel(x0, y0);
+    let p10 = img.get_pixel(x1, y0);
+    let p01 = img.get_pixel(x0, y1);
+    let p11 = img.get_pixel(x1, y1);
+    
+    let mut result = [0u8; 4];
+    for i in 0..4 {
+        let v00 = p00[i] as f32;
+        let v10 = p10[i] as f32;
+        let v01 = p01[i] as f32;
+        let v11 = p11[i] as f32;
+        
+        let v0 = v00 * (1.0 - fx) + v10 * fx;
+        let v1 = v01 * (1.0 - fx) + v11 * fx;
+        let v = v0 * (1.0 - fy) + v1 * fy;
+        
+        result[i] = v.clamp(0.0, 255.0) as u8;
+    }
+    
+    Rgba(result)
+}
@@ -0,0 +1,140 @@
+use image::{DynamicImage, GrayImage, Luma, RgbaImage, Rgba};
+
+use crate::imageutils::hash_noise;
+
+pub type MixerMatrix = [[f32; 3]; 3];
+
+// Rows are output R, G, B; columns are input R, G, B. Each row sums to one so neutral
+// grays stay put. Red, the nearest visible stand-in for near-infrared, leads every row
+// and so drives the luminance, while the negative blue weight darkens skies.
+const FALSE_COLOR_MATRIX: MixerMatrix = [
+    [1.3, -0.1, -0.2],
+    [0.6, 0.5, -0.1],
+    [0.4, 0.1, 0.5],
+];
+const BLACK_AND_WHITE_MATRIX: MixerMatrix = [
+    [1.4, -0.1, -0.3],
+    [1.4, -0.1, -0.3],
+    [1.4, -0.1, -0.3],
+];
+const IDENTITY_MATRIX: MixerMatrix = [
+    [1.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0],
+    [0.0, 0.0, 1.0],
+];
+
+#[derive(Clone, PartialEq)]
+pub struct InfraredSettings {
+    pub preset: InfraredPreset,
+    pub custom_mixer: MixerMatrix,
+    pub glow: f32,
+    pub grain: f32,
+    pub seed: u64,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum InfraredPreset {
+    FalseColor,
+    BlackAndWhite,
+    Custom,
+}
+
+impl InfraredPreset {
+    pub fn name(&self) -> &str {
+        match self {
+            InfraredPreset::FalseColor => "False Color IR",
+            InfraredPreset::BlackAndWhite => "Black & White IR",
+            InfraredPreset::Custom => "Custom Mixer",
+        }
+    }
+
+    pub fn matrix(&self) -> Option<MixerMatrix> {
+        match self {
+            InfraredPreset::FalseColor => Some(FALSE_COLOR_MATRIX),
+            InfraredPreset::BlackAndWhite => Some(BLACK_AND_WHITE_MATRIX),
+            InfraredPreset::Custom => None,
+        }
+    }
+}
+
+impl Default for InfraredSettings {
+    fn default() -> Self {
+        Self {
+            preset: InfraredPreset::FalseColor,
+            custom_mixer: IDENTITY_MATRIX,
+            glow: 35.0,
+            grain: 20.0,
+            seed: 0x1a2b,
+        }
+    }
+}
+
+impl InfraredSettings {
+    pub fn mixer(&self) -> MixerMatrix {
+        self.preset.matrix().unwrap_or(self.custom_mixer)
+    }
+}
+
+#[inline]
+pub fn mix_pixel(matrix: &MixerMatrix, rgb: [u8; 3]) -> [u8; 3] {
+    let input = rgb.map(|c| c as f32);
+    matrix.map(|row| (row[0] * input[0] + row[1] * input[1] + row[2] * input[2]).round().clamp(0.0, 255.0) as u8)
+}
+
+/// Simulates infrared film through a channel mixer, with highlight glow and grain.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::infraredconverter::{apply_infrared, InfraredSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_infrared(image, &InfraredSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_infrared(image: DynamicImage, settings: &InfraredSettings) -> RgbaImage {
+    let mut rgba_img = image.to_rgba8();
+    let (width, height) = rgba_img.dimensions();
+    if width == 0 || height == 0 {
+        return rgba_img;
+    }
+    let matrix = settings.mixer();
+
+    for pixel in rgba_img.pixels_mut() {
+        let [r, g, b] = mix_pixel(&matrix, [pixel[0], pixel[1], pixel[2]]);
+        *pixel = Rgba([r, g, b, pixel[3]]);
+    }
+
+    // Highlight halation: blur the brightest tones and screen them back over the image
+    let glow = settings.glow / 100.0;
+    if glow > 0.0 {
+        let highlights = GrayImage::from_fn(width, height, |x, y| {
+            let p = rgba_img.get_pixel(x, y);
+            let luma = (0.299 * p[0] as f32 + 0.587 * p[1] as f32 + 0.114 * p[2] as f32) / 255.0;
+            Luma([(((luma - 0.6) / 0.4).max(0.0) * 255.0).round() as u8])
+        });
+        let sigma = (width.min(height) as f32 * 0.012).max(1.0);
+        let halo = image::imageops::blur(&highlights, sigma);
+        for (pixel, h) in rgba_img.pixels_mut().zip(halo.pixels()) {
+            let amount = h[0] as f32 / 255.0 * glow;
+            for c in 0..3 {
+                let v = pixel[c] as f32 / 255.0;
+                pixel[c] = ((1.0 - (1.0 - v) * (1.0 - amount)) * 255.0).round() as u8;
+            }
+        }
+    }
+
+    // Monochrome film grain, identical across channels
+    let grain = settings.grain / 100.0;
+    if grain > 0.0 {
+        for (x, y, pixel) in rgba_img.enumerate_pixels_mut() {
+            let noise = (hash_noise(x as i32, y as i32, settings.seed) - 0.5) * grain * 60.0;
+            for c in 0..3 {
+                pixel[c] = (pixel[c] as f32 + noise).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+
+    rgba_img
+}
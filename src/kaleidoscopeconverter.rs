@@ -0,0 +1,84 @@
+use std::f32::consts::TAU;
+
+use image::{DynamicImage, RgbaImage};
+
+use crate::imageutils::{reflect_coord, sample_bilinear};
+
+#[derive(Clone, PartialEq)]
+pub struct KaleidoscopeSettings {
+    pub segments: u32,
+    pub rotation: f32,
+    pub zoom: f32,
+    pub mirrored: bool,
+    pub center_x: f32,
+    pub center_y: f32,
+}
+
+impl Default for KaleidoscopeSettings {
+    fn default() -> Self {
+        Self {
+            segments: 6,
+            rotation: 0.0,
+            zoom: 1.0,
+            mirrored: true,
+            center_x: 0.5,
+            center_y: 0.5,
+        }
+    }
+}
+
+// Fold an angle (radians) into the source wedge, returning the offset from the wedge's start edge.
+// In mirrored mode each segment holds the wedge and its reflection, so every boundary is a mirror
+// line and the result is continuous for any segment count.
+pub fn fold_angle(angle: f32, segments: u32, mirrored: bool) -> f32 {
+    let segment = TAU / segments.max(1) as f32;
+    let local = angle.rem_euclid(segment);
+    if mirrored {
+        let half = segment / 2.0;
+        if local > half { segment - local } else { local }
+    } else {
+        local
+    }
+}
+
+/// Folds `image` into mirrored segments around the configured centre.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::kaleidoscopeconverter::{apply_kaleidoscope, KaleidoscopeSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_kaleidoscope(image, &KaleidoscopeSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_kaleidoscope(image: DynamicImage, settings: &KaleidoscopeSettings) -> RgbaImage {
+    let rgba_img = image.to_rgba8();
+    let (width, height) = rgba_img.dimensions();
+    if width < 2 || height < 2 {
+        return rgba_img;
+    }
+    let mut output = RgbaImage::new(width, height);
+
+    let cx = width as f32 * settings.center_x;
+    let cy = height as f32 * settings.center_y;
+    let rotation = settings.rotation.to_radians();
+    let max_x = (width - 1) as f32 - 0.001;
+    let max_y = (height - 1) as f32 - 0.001;
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 - cx;
+            let dy = y as f32 - cy;
+            let radius = (dx * dx + dy * dy).sqrt() / settings.zoom.max(0.01);
+            let angle = fold_angle(dy.atan2(dx) - rotation, settings.segments, settings.mirrored) + rotation;
+
+            let src_x = reflect_coord(cx + radius * angle.cos(), max_x);
+            let src_y = reflect_coord(cy + radius * angle.sin(), max_y);
+            output.put_pixel(x, y, sample_bilinear(&rgba_img, src_x, src_y, width, height));
+        }
+    }
+
+    output
+}
@@ -0,0 +1,160 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelsSettings {
+    pub channel_mode: LevelsChannelMode,
+    pub input_black: f32,
+    pub input_white: f32,
+    pub gamma: f32,
+    pub output_black: f32,
+    pub output_white: f32,
+    pub curve: Vec<[f32; 2]>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub enum LevelsChannelMode {
+    Luminance,
+    PerChannel,
+}
+
+impl LevelsChannelMode {
+    pub fn name(&self) -> &str {
+        match self {
+            LevelsChannelMode::Luminance => "Luminance",
+            LevelsChannelMode::PerChannel => "Per Channel (RGB)",
+        }
+    }
+}
+
+impl Default for LevelsSettings {
+    fn default() -> Self {
+        Self {
+            channel_mode: LevelsChannelMode::PerChannel,
+            input_black: 0.0,
+            input_white: 255.0,
+            gamma: 1.0,
+            output_black: 0.0,
+            output_white: 255.0,
+            curve: vec![[0.0, 0.0], [1.0, 1.0]],
+        }
+    }
+}
+
+// Monotone cubic (Fritsch-Carlson) interpolation through the curve's control points,
+// which must be sorted by x. Never overshoots between points, unlike a natural spline.
+pub fn evaluate_curve(points: &[[f32; 2]], x: f32) -> f32 {
+    match points.len() {
+        0 => return x,
+        1 => return points[0][1],
+        _ => {}
+    }
+
+    let n = points.len();
+    if x <= points[0][0] {
+        return points[0][1];
+    }
+    if x >= points[n - 1][0] {
+        return points[n - 1][1];
+    }
+
+    let secants: Vec<f32> = points
+        .windows(2)
+        .map(|w| {
+            let dx = (w[1][0] - w[0][0]).max(f32::EPSILON);
+            (w[1][1] - w[0][1]) / dx
+        })
+        .collect();
+
+    let mut tangents = vec![0.0f32; n];
+    tangents[0] = secants[0];
+    tangents[n - 1] = secants[n - 2];
+    for k in 1..n - 1 {
+        if secants[k - 1] * secants[k] > 0.0 {
+            tangents[k] = (secants[k - 1] + secants[k]) / 2.0;
+        }
+    }
+    for k in 0..n - 1 {
+        if secants[k] == 0.0 {
+            tangents[k] = 0.0;
+            tangents[k + 1] = 0.0;
+            continue;
+        }
+        let alpha = tangents[k] / secants[k];
+        let beta = tangents[k + 1] / secants[k];
+        let sum = alpha * alpha + beta * beta;
+        if sum > 9.0 {
+            let tau = 3.0 / sum.sqrt();
+            tangents[k] = tau * alpha * secants[k];
+            tangents[k + 1] = tau * beta * secants[k];
+        }
+    }
+
+    let k = points.windows(2).position(|w| x < w[1][0]).unwrap_or(n - 2);
+    let [x0, y0] = points[k];
+    let [x1, y1] = points[k + 1];
+    let h = (x1 - x0).max(f32::EPSILON);
+    let t = (x - x0) / h;
+    let t2 = t * t;
+    let t3 = t2 * t;
+
+    let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+    let h10 = t3 - 2.0 * t2 + t;
+    let h01 = -2.0 * t3 + 3.0 * t2;
+    let h11 = t3 - t2;
+
+    h00 * y0 + h10 * h * tangents[k] + h01 * y1 + h11 * h * tangents[k + 1]
+}
+
+// Levels followed by the tone curve, baked into a 256-entry table
+pub fn build_lut(settings: &LevelsSettings) -> [u8; 256] {
+    let in_range = (settings.input_white - settings.input_black).max(1.0);
+    let inv_gamma = 1.0 / settings.gamma.max(0.01);
+    let mut lut = [0u8; 256];
+
+    for (i, entry) in lut.iter_mut().enumerate() {
+        let normalized = ((i as f32 - settings.input_black) / in_range).clamp(0.0, 1.0);
+        let gamma_corrected = normalized.powf(inv_gamma);
+        let leveled = settings.output_black + gamma_corrected * (settings.output_white - settings.output_black);
+        let curved = evaluate_curve(&settings.curve, leveled / 255.0);
+        *entry = (curved * 255.0).round().clamp(0.0, 255.0) as u8;
+    }
+
+    lut
+}
+
+/// Applies input/output levels, gamma and the tone curve.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::levelsconverter::{apply_levels, LevelsSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_levels(image, &LevelsSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_levels(image: DynamicImage, settings: &LevelsSettings) -> RgbaImage {
+    let mut rgba_img = image.to_rgba8();
+    let lut = build_lut(settings);
+
+    match settings.channel_mode {
+        LevelsChannelMode::PerChannel => {
+            for pixel in rgba_img.pixels_mut() {
+                *pixel = Rgba([lut[pixel[0] as usize], lut[pixel[1] as usize], lut[pixel[2] as usize], pixel[3]]);
+            }
+        }
+        LevelsChannelMode::Luminance => {
+            // Shift all channels by the luminance change so hue is kept
+            for pixel in rgba_img.pixels_mut() {
+                let luma = (0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32).round() as usize;
+                let delta = lut[luma.min(255)] as f32 - luma as f32;
+                let shift = |v: u8| (v as f32 + delta).clamp(0.0, 255.0) as u8;
+                *pixel = Rgba([shift(pixel[0]), shift(pixel[1]), shift(pixel[2]), pixel[3]]);
+            }
+        }
+    }
+
+    rgba_img
+}
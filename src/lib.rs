@@ -0,0 +1,45 @@
+//! Image-processing filters behind PixForge, usable without the GUI.
+//!
+//! Every filter lives in its own `*converter` module with a `Default`-able settings
+//! struct and an `apply_*` function taking a [`image::DynamicImage`].
+//! Any input is accepted, including empty and one-pixel images, and out-of-range
+//! settings are clamped to the nearest usable value rather than panicking.
+//!
+//! Converters with a random element (grain, jitter, scattered sites, noise dithering)
+//! take a `seed: u64` in their settings. Their noise is a pure function of the seed and
+//! the pixel or item coordinates, never of iteration order, so the same input and
+//! settings give byte-identical output on every run and with any number of threads.
+
+pub mod error;
+pub mod progress;
+pub mod tiling;
+pub mod luminance;
+pub mod asciiconverter;
+pub mod ditherconverter;
+pub mod fisheyeconverter;
+pub mod crtconverter;
+pub mod popartconverter;
+pub mod lutconverter;
+pub mod hslconverter;
+pub mod levelsconverter;
+pub mod whitebalanceconverter;
+pub mod tiltshiftconverter;
+pub mod solarizeconverter;
+pub mod watercolorconverter;
+pub mod sketchconverter;
+pub mod mosaicconverter;
+pub mod neonconverter;
+pub mod kaleidoscopeconverter;
+pub mod drosteconverter;
+pub mod engravingconverter;
+pub mod dotmatrixconverter;
+pub mod stainedglassconverter;
+pub mod infraredconverter;
+pub mod lomoconverter;
+pub mod oldphotoconverter;
+pub mod ortonconverter;
+pub mod retroconverter;
+pub mod colorspace;
+mod imageutils;
+
+pub use error::ArtsifyError;
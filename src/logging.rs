@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+// Directives used when RUST_LOG is not set
+const DEFAULT_FILTER: &str = "warn";
+const VERBOSE_FILTER: &str = "warn,pixforge=debug";
+
+// Set to any value to also write a daily rolling log into the config directory
+const LOG_FILE_VAR: &str = "ARTSIFY_LOG_FILE";
+
+// Installs the global subscriber. Call once, before eframe starts, and keep the returned
+// guards alive until exit: dropping them flushes the background writers. Both outputs go
+// through a non-blocking writer so the GUI thread never waits on I/O.
+pub fn init(verbose: bool) -> Vec<WorkerGuard> {
+    let mut guards = Vec::new();
+
+    let (stderr, guard) = tracing_appender::non_blocking(std::io::stderr());
+    guards.push(guard);
+    let stderr_layer = fmt::layer().with_writer(stderr).with_filter(env_filter(verbose));
+
+    let mut file_error = None;
+    let file_layer = match std::env::var_os(LOG_FILE_VAR).and_then(|_| log_dir()) {
+        Some(dir) => match RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("artsify")
+            .filename_suffix("log")
+            .build(&dir)
+        {
+            Ok(appender) => {
+                let (file, guard) = tracing_appender::non_blocking(appender);
+                guards.push(guard);
+                Some(fmt::layer().with_writer(file).with_ansi(false).with_filter(env_filter(verbose)))
+            }
+            Err(e) => {
+                file_error = Some(format!("cannot write logs to {}: {}", dir.display(), e));
+                None
+            }
+        },
+        None => None,
+    };
+
+    tracing_subscriber::registry().with(stderr_layer).with(file_layer).init();
+    if let Some(e) = file_error {
+        tracing::warn!("{}", e);
+    }
+    guards
+}
+
+fn env_filter(verbose: bool) -> EnvFilter {
+    match std::env::var("RUST_LOG") {
+        // The project is still called Artsify, so accept `artsify=...` for the crate's targets
+        Ok(directives) => EnvFilter::new(directives.replace("artsify", "pixforge")),
+        Err(_) if verbose => EnvFilter::new(VERBOSE_FILTER),
+        Err(_) => EnvFilter::new(DEFAULT_FILTER),
+    }
+}
+
+fn log_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("artsify").join("logs"))
+}
@@ -0,0 +1,196 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+
+use crate::colorspace::{hsl_to_rgb, rgb_to_hsl};
+use crate::imageutils::hash_noise;
+
+const LEAK_COLORS: [[f32; 3]; 3] = [
+    [255.0, 120.0, 30.0],
+    [255.0, 60.0, 40.0],
+    [255.0, 190.0, 80.0],
+];
+
+#[derive(Clone, PartialEq)]
+pub struct LomoSettings {
+    pub preset: LomoPreset,
+    pub vignette: f32,
+    pub cross_process: f32,
+    pub saturation: f32,
+    pub soft_focus: f32,
+    pub light_leak: f32,
+    pub seed: u64,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum LomoPreset {
+    Classic,
+    CrossProcessed,
+    Dreamy,
+    Custom,
+}
+
+impl LomoPreset {
+    pub fn name(&self) -> &str {
+        match self {
+            LomoPreset::Classic => "Classic Lomo",
+            LomoPreset::CrossProcessed => "Cross Processed",
+            LomoPreset::Dreamy => "Dreamy Leak",
+            LomoPreset::Custom => "Custom",
+        }
+    }
+
+    // Strengths for vignette, cross-process, saturation, soft focus and light leak
+    pub fn strengths(&self) -> Option<[f32; 5]> {
+        match self {
+            LomoPreset::Classic => Some([70.0, 40.0, 35.0, 30.0, 0.0]),
+            LomoPreset::CrossProcessed => Some([50.0, 90.0, 50.0, 15.0, 20.0]),
+            LomoPreset::Dreamy => Some([40.0, 25.0, 15.0, 60.0, 70.0]),
+            LomoPreset::Custom => None,
+        }
+    }
+}
+
+impl Default for LomoSettings {
+    fn default() -> Self {
+        let mut settings = Self {
+            preset: LomoPreset::Classic,
+            vignette: 0.0,
+            cross_process: 0.0,
+            saturation: 0.0,
+            soft_focus: 0.0,
+            light_leak: 0.0,
+            seed: 1,
+        };
+        settings.apply_preset();
+        settings
+    }
+}
+
+impl LomoSettings {
+    pub fn apply_preset(&mut self) {
+        if let Some([vignette, cross_process, saturation, soft_focus, light_leak]) = self.preset.strengths() {
+            self.vignette = vignette;
+            self.cross_process = cross_process;
+            self.saturation = saturation;
+            self.soft_focus = soft_focus;
+            self.light_leak = light_leak;
+        }
+    }
+}
+
+// Cross-processing: contrasty reds and greens, blues squeezed so highlights go yellow
+// and shadows go blue-green
+fn cross_process_curves(strength: f32) -> [[u8; 256]; 3] {
+    let s_curve = |v: f32, k: f32| {
+        let s = v * v * (3.0 - 2.0 * v);
+        v + (s - v) * k
+    };
+    let targets: [&dyn Fn(f32) -> f32; 3] = [&|v| s_curve(v, 1.0), &|v| s_curve(v, 0.6), &|v| 0.15 + v * 0.7];
+
+    let mut curves = [[0u8; 256]; 3];
+    for (curve, target) in curves.iter_mut().zip(targets) {
+        for (i, entry) in curve.iter_mut().enumerate() {
+            let v = i as f32 / 255.0;
+            let mixed = v + (target(v) - v) * strength;
+            *entry = (mixed * 255.0).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+    curves
+}
+
+/// Toy camera look: vignette, cross-processed curves, soft focus and light leaks.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::lomoconverter::{apply_lomo, LomoSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_lomo(image, &LomoSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_lomo(image: DynamicImage, settings: &LomoSettings) -> RgbaImage {
+    let source = image.to_rgba8();
+    let (width, height) = source.dimensions();
+    if width == 0 || height == 0 {
+        return source;
+    }
+    let w = width as f32;
+    let h = height as f32;
+
+    let soft = settings.soft_focus / 100.0;
+    let blurred = if soft > 0.0 {
+        Some(image::imageops::blur(&source, (w.min(h) * 0.008).max(1.0)))
+    } else {
+        None
+    };
+    let curves = cross_process_curves(settings.cross_process / 100.0);
+    let saturation = 1.0 + settings.saturation / 100.0;
+    let vignette = settings.vignette / 100.0;
+    let leak = settings.light_leak / 100.0;
+
+    // Light leaks hug one seeded edge, as if the back door let light in
+    let edge = (hash_noise(0, 0, settings.seed) * 4.0) as u32 % 4;
+    let streaks: Vec<(f32, f32, [f32; 3])> = (0..3)
+        .map(|i| {
+            let position = hash_noise(i, 1, settings.seed);
+            let spread = 0.08 + hash_noise(i, 2, settings.seed) * 0.2;
+            let color = LEAK_COLORS[(hash_noise(i, 3, settings.seed) * 3.0) as usize % 3];
+            (position, spread, color)
+        })
+        .collect();
+
+    let mut output = RgbaImage::new(width, height);
+    for (x, y, pixel) in source.enumerate_pixels() {
+        let nx = (x as f32 + 0.5) / w;
+        let ny = (y as f32 + 0.5) / h;
+        // Normalized so the corners sit at radius 1
+        let radius = (((nx - 0.5) * 2.0).powi(2) + ((ny - 0.5) * 2.0).powi(2)).sqrt() / std::f32::consts::SQRT_2;
+
+        let mut rgb = [pixel[0] as f32, pixel[1] as f32, pixel[2] as f32];
+        if let Some(blurred) = &blurred {
+            let weight = soft * ((radius - 0.3) / 0.7).clamp(0.0, 1.0);
+            let b = blurred.get_pixel(x, y);
+            for c in 0..3 {
+                rgb[c] += (b[c] as f32 - rgb[c]) * weight;
+            }
+        }
+
+        for c in 0..3 {
+            rgb[c] = curves[c][rgb[c].round().clamp(0.0, 255.0) as usize] as f32;
+        }
+
+        if saturation != 1.0 {
+            let (hue, s, l) = rgb_to_hsl(rgb[0] / 255.0, rgb[1] / 255.0, rgb[2] / 255.0);
+            let (r, g, b) = hsl_to_rgb(hue, (s * saturation).clamp(0.0, 1.0), l);
+            rgb = [r * 255.0, g * 255.0, b * 255.0];
+        }
+
+        if vignette > 0.0 {
+            let t = ((radius - 0.35) / 0.75).clamp(0.0, 1.0);
+            let falloff = 1.0 - vignette * t * t * (3.0 - 2.0 * t);
+            rgb = rgb.map(|c| c * falloff);
+        }
+
+        if leak > 0.0 {
+            let (depth, along) = match edge {
+                0 => (nx, ny),
+                1 => (1.0 - nx, ny),
+                2 => (ny, nx),
+                _ => (1.0 - ny, nx),
+            };
+            for (position, spread, color) in &streaks {
+                let intensity = leak * (-(depth / 0.18).powi(2)).exp() * (-((along - position) / spread).powi(2)).exp();
+                for c in 0..3 {
+                    // Screen blend keeps the leak from clipping already-bright areas to flat white
+                    rgb[c] = 255.0 - (255.0 - rgb[c]) * (1.0 - color[c] / 255.0 * intensity);
+                }
+            }
+        }
+
+        let encode = |v: f32| v.round().clamp(0.0, 255.0) as u8;
+        output.put_pixel(x, y, Rgba([encode(rgb[0]), encode(rgb[1]), encode(rgb[2]), pixel[3]]));
+    }
+
+    output
+}
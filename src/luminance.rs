@@ -0,0 +1,61 @@
+//! Ways of reducing a colour to a single brightness, shared by the converters that
+//! work in gray so the same image maps the same way in each.
+
+use image::{DynamicImage, GrayImage, Luma};
+
+/// How a colour's brightness is measured.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LuminanceModel {
+    /// 0.299 R + 0.587 G + 0.114 B, the weights of SD video. Pure green is 0.587.
+    Rec601,
+    /// 0.2126 R + 0.7152 G + 0.0722 B, the weights of sRGB and HD video. Pure green is
+    /// 0.7152.
+    Rec709,
+    /// The mean of the three channels. Pure green is 1/3.
+    Average,
+    /// Halfway between the largest and smallest channel, the L of HSL. Pure green is 0.5.
+    Lightness,
+}
+
+impl LuminanceModel {
+    /// Human-readable label.
+    pub fn name(&self) -> &str {
+        match self {
+            LuminanceModel::Rec601 => "Rec. 601",
+            LuminanceModel::Rec709 => "Rec. 709",
+            LuminanceModel::Average => "Average",
+            LuminanceModel::Lightness => "Lightness",
+        }
+    }
+
+    /// Brightness of an sRGB colour with channels in 0..1, also in 0..1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pixforge::luminance::LuminanceModel;
+    ///
+    /// assert_eq!(LuminanceModel::Lightness.luminance(0.0, 1.0, 0.0), 0.5);
+    /// ```
+    pub fn luminance(&self, r: f32, g: f32, b: f32) -> f32 {
+        match self {
+            LuminanceModel::Rec601 => 0.299 * r + 0.587 * g + 0.114 * b,
+            LuminanceModel::Rec709 => 0.2126 * r + 0.7152 * g + 0.0722 * b,
+            LuminanceModel::Average => (r + g + b) / 3.0,
+            LuminanceModel::Lightness => (r.max(g).max(b) + r.min(g).min(b)) / 2.0,
+        }
+    }
+
+    /// `image` in 8-bit gray, ignoring alpha. [`LuminanceModel::Rec709`] gives exactly
+    /// [`DynamicImage::to_luma8`].
+    pub fn to_luma8(&self, image: &DynamicImage) -> GrayImage {
+        if *self == LuminanceModel::Rec709 {
+            return image.to_luma8();
+        }
+        let rgb = image.to_rgb8();
+        GrayImage::from_fn(rgb.width(), rgb.height(), |x, y| {
+            let [r, g, b] = rgb.get_pixel(x, y).0.map(|c| c as f32 / 255.0);
+            Luma([(self.luminance(r, g, b) * 255.0).round().clamp(0.0, 255.0) as u8])
+        })
+    }
+}
@@ -0,0 +1,307 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+
+use crate::error::ArtsifyError;
+
+const BUILTIN_LUT_SIZE: usize = 17;
+
+#[derive(Clone, PartialEq)]
+pub struct LutSettings {
+    pub look: LutLook,
+    pub strength: f32,
+    pub custom_lut: Option<Lut3D>,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum LutLook {
+    TealOrange,
+    BleachBypass,
+    FadedFilm,
+    Custom,
+}
+
+impl LutLook {
+    pub fn name(&self) -> &str {
+        match self {
+            LutLook::TealOrange => "Teal & Orange",
+            LutLook::BleachBypass => "Bleach Bypass",
+            LutLook::FadedFilm => "Faded Film",
+            LutLook::Custom => "Custom (.cube)",
+        }
+    }
+}
+
+impl Default for LutSettings {
+    fn default() -> Self {
+        Self {
+            look: LutLook::TealOrange,
+            strength: 1.0,
+            custom_lut: None,
+        }
+    }
+}
+
+// 3D lookup table with the red index varying fastest, as in the .cube format
+#[derive(Clone, PartialEq)]
+pub struct Lut3D {
+    pub title: String,
+    pub size: usize,
+    pub domain_min: [f32; 3],
+    pub domain_max: [f32; 3],
+    pub data: Vec<[f32; 3]>,
+}
+
+impl Lut3D {
+    pub fn from_fn(title: &str, size: usize, f: impl Fn([f32; 3]) -> [f32; 3]) -> Self {
+        let scale = (size - 1) as f32;
+        let mut data = Vec::with_capacity(size * size * size);
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    data.push(f([r as f32 / scale, g as f32 / scale, b as f32 / scale]));
+                }
+            }
+        }
+        Self {
+            title: title.to_string(),
+            size,
+            domain_min: [0.0; 3],
+            domain_max: [1.0; 3],
+            data,
+        }
+    }
+
+    #[inline]
+    fn entry(&self, r: usize, g: usize, b: usize) -> [f32; 3] {
+        self.data[(b * self.size + g) * self.size + r]
+    }
+
+    // Trilinear interpolation between the eight surrounding lattice points
+    pub fn sample(&self, color: [f32; 3]) -> [f32; 3] {
+        let scale = (self.size - 1) as f32;
+        let mut index = [0usize; 3];
+        let mut next = [0usize; 3];
+        let mut frac = [0.0f32; 3];
+        for c in 0..3 {
+            let range = (self.domain_max[c] - self.domain_min[c]).max(f32::EPSILON);
+            let v = ((color[c] - self.domain_min[c]) / range).clamp(0.0, 1.0) * scale;
+            let i = (v.floor() as usize).min(self.size - 1);
+            index[c] = i;
+            next[c] = (i + 1).min(self.size - 1);
+            frac[c] = v - i as f32;
+        }
+
+        let lerp = |a: [f32; 3], b: [f32; 3], t: f32| {
+            [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t]
+        };
+
+        let (r0, g0, b0) = (index[0], index[1], index[2]);
+        let (r1, g1, b1) = (next[0], next[1], next[2]);
+
+        let c00 = lerp(self.entry(r0, g0, b0), self.entry(r1, g0, b0), frac[0]);
+        let c10 = lerp(self.entry(r0, g1, b0), self.entry(r1, g1, b0), frac[0]);
+        let c01 = lerp(self.entry(r0, g0, b1), self.entry(r1, g0, b1), frac[0]);
+        let c11 = lerp(self.entry(r0, g1, b1), self.entry(r1, g1, b1), frac[0]);
+
+        let c0 = lerp(c00, c10, frac[1]);
+        let c1 = lerp(c01, c11, frac[1]);
+        lerp(c0, c1, frac[2])
+    }
+}
+
+pub fn parse_cube(text: &str) -> Result<Lut3D, ArtsifyError> {
+    let mut title = String::new();
+    let mut size: Option<usize> = None;
+    let mut domain_min = [0.0f32; 3];
+    let mut domain_max = [1.0f32; 3];
+    let mut data = Vec::new();
+
+    for (line_no, raw_line) in text.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line_no = line_no + 1;
+        let mut parts = line.split_whitespace();
+        let keyword = parts.next().unwrap_or_default();
+
+        match keyword {
+            "TITLE" => {
+                title = line["TITLE".len()..].trim().trim_matches('"').to_string();
+            }
+            "LUT_3D_SIZE" => {
+                let value = parts.next().and_then(|v| v.parse::<usize>().ok())
+                    .ok_or_else(|| ArtsifyError::Decode(format!("Line {}: invalid LUT_3D_SIZE", line_no)))?;
+                if !(2..=256).contains(&value) {
+                    return Err(ArtsifyError::Decode(format!("Line {}: LUT_3D_SIZE {} is out of range (2-256)", line_no, value)));
+                }
+                size = Some(value);
+            }
+            "LUT_1D_SIZE" => {
+                return Err(ArtsifyError::Decode("1D LUTs are not supported, expected a 3D .cube file".to_string()));
+            }
+            "DOMAIN_MIN" | "DOMAIN_MAX" => {
+                let values = parse_triplet(parts, line_no)?;
+                if keyword == "DOMAIN_MIN" {
+                    domain_min = values;
+                } else {
+                    domain_max = values;
+                }
+            }
+            "LUT_3D_INPUT_RANGE" => {
+                let range: Vec<f32> = parts.filter_map(|v| v.parse::<f32>().ok()).collect();
+                if range.len() != 2 {
+                    return Err(ArtsifyError::Decode(format!("Line {}: LUT_3D_INPUT_RANGE expects two numbers", line_no)));
+                }
+                domain_min = [range[0]; 3];
+                domain_max = [range[1]; 3];
+            }
+            _ if keyword.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') => {
+                if size.is_none() {
+                    return Err(ArtsifyError::Decode(format!("Line {}: table data found before LUT_3D_SIZE", line_no)));
+                }
+                data.push(parse_triplet(line.split_whitespace(), line_no)?);
+            }
+            _ => {
+                return Err(ArtsifyError::Decode(format!("Line {}: unknown keyword '{}'", line_no, keyword)));
+            }
+        }
+    }
+
+    let size = size.ok_or_else(|| ArtsifyError::Decode("Missing LUT_3D_SIZE, not a 3D .cube file".to_string()))?;
+    let expected = size * size * size;
+    if data.len() != expected {
+        return Err(ArtsifyError::Decode(format!("Expected {} table entries for a {}³ LUT, found {}", expected, size, data.len())));
+    }
+    for c in 0..3 {
+        if domain_max[c] <= domain_min[c] {
+            return Err(ArtsifyError::Decode("DOMAIN_MAX must be greater than DOMAIN_MIN".to_string()));
+        }
+    }
+
+    Ok(Lut3D {
+        title,
+        size,
+        domain_min,
+        domain_max,
+        data,
+    })
+}
+
+fn parse_triplet<'a>(mut parts: impl Iterator<Item = &'a str>, line_no: usize) -> Result<[f32; 3], ArtsifyError> {
+    let mut values = [0.0f32; 3];
+    for value in values.iter_mut() {
+        *value = parts.next()
+            .and_then(|v| v.parse::<f32>().ok())
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| ArtsifyError::Decode(format!("Line {}: expected three numbers", line_no)))?;
+    }
+    if parts.next().is_some() {
+        return Err(ArtsifyError::Decode(format!("Line {}: expected three numbers", line_no)));
+    }
+    Ok(values)
+}
+
+pub fn builtin_lut(look: &LutLook) -> Option<Lut3D> {
+    let lut = match look {
+        LutLook::TealOrange => Lut3D::from_fn(look.name(), BUILTIN_LUT_SIZE, teal_orange),
+        LutLook::BleachBypass => Lut3D::from_fn(look.name(), BUILTIN_LUT_SIZE, bleach_bypass),
+        LutLook::FadedFilm => Lut3D::from_fn(look.name(), BUILTIN_LUT_SIZE, faded_film),
+        LutLook::Custom => return None,
+    };
+    Some(lut)
+}
+
+#[inline]
+fn luma(c: [f32; 3]) -> f32 {
+    0.2126 * c[0] + 0.7152 * c[1] + 0.0722 * c[2]
+}
+
+fn teal_orange(c: [f32; 3]) -> [f32; 3] {
+    let l = luma(c);
+    // Shadows lean teal, highlights lean orange
+    let shadow = (1.0 - l * 2.0).max(0.0);
+    let highlight = (l * 2.0 - 1.0).max(0.0);
+    let shift = [
+        -0.10 * shadow + 0.12 * highlight,
+        0.04 * shadow + 0.03 * highlight,
+        0.10 * shadow - 0.10 * highlight,
+    ];
+    [
+        (c[0] + shift[0]).clamp(0.0, 1.0),
+        (c[1] + shift[1]).clamp(0.0, 1.0),
+        (c[2] + shift[2]).clamp(0.0, 1.0),
+    ]
+}
+
+fn bleach_bypass(c: [f32; 3]) -> [f32; 3] {
+    let l = luma(c);
+    let mut out = [0.0; 3];
+    for i in 0..3 {
+        // Mostly desaturated with a hard contrast curve
+        let desat = l + (c[i] - l) * 0.4;
+        let s = desat * desat * (3.0 - 2.0 * desat);
+        out[i] = (desat + (s - desat) * 0.8).clamp(0.0, 1.0);
+    }
+    out
+}
+
+fn faded_film(c: [f32; 3]) -> [f32; 3] {
+    let l = luma(c);
+    let warmth = [0.03, 0.01, -0.02];
+    let mut out = [0.0; 3];
+    for i in 0..3 {
+        let desat = l + (c[i] - l) * 0.8;
+        // Lifted blacks and compressed highlights
+        out[i] = (0.08 + desat * 0.84 + warmth[i]).clamp(0.0, 1.0);
+    }
+    out
+}
+
+/// Grades `image` with a built-in look or a loaded 3D LUT.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::lutconverter::{apply_lut, LutSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_lut(image, &LutSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_lut(image: DynamicImage, settings: &LutSettings) -> RgbaImage {
+    let rgba_img = image.to_rgba8();
+    let (width, height) = rgba_img.dimensions();
+
+    let lut = match settings.look {
+        LutLook::Custom => settings.custom_lut.clone(),
+        ref look => builtin_lut(look),
+    };
+    let Some(lut) = lut else {
+        return rgba_img;
+    };
+
+    let strength = settings.strength.clamp(0.0, 1.0);
+    let mut output = RgbaImage::new(width, height);
+
+    for y in 0..height {
+        for x in 0..width {
+            let pixel = rgba_img.get_pixel(x, y);
+            let original = [
+                pixel[0] as f32 / 255.0,
+                pixel[1] as f32 / 255.0,
+                pixel[2] as f32 / 255.0,
+            ];
+            let graded = lut.sample(original);
+
+            let blend = |i: usize| {
+                let v = original[i] + (graded[i] - original[i]) * strength;
+                (v * 255.0).round().clamp(0.0, 255.0) as u8
+            };
+
+            output.put_pixel(x, y, Rgba([blend(0), blend(1), blend(2), pixel[3]]));
+        }
+    }
+
+    output
+}
@@ -1,12 +1,15 @@
-mod asciiconverter;
-mod ditherconverter;
-mod fisheyeconverter;
-mod crtconverter;
+mod asciifilter;
+mod filters;
 mod gui;
+mod logging;
+mod timings;
 
 use gui::AsciiArtApp;
 
 fn main() -> eframe::Result<()> {
+    let verbose = std::env::args().skip(1).any(|arg| arg == "--verbose" || arg == "-v");
+    let _log_guards = logging::init(verbose);
+
     let options = eframe::NativeOptions {
         viewport: eframe::egui::ViewportBuilder::default()
             .with_inner_size([1400.0, 1000.0])
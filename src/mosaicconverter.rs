@@ -0,0 +1,130 @@
+use image::{DynamicImage, RgbaImage};
+use rayon::prelude::*;
+
+use crate::imageutils::hash_noise;
+
+// Subsamples per axis for each output pixel, so grout edges stay anti-aliased
+const SUPERSAMPLE: u32 = 3;
+const MAX_ROTATION_DEGREES: f32 = 5.0;
+
+#[derive(Clone, PartialEq)]
+pub struct MosaicSettings {
+    pub tile_size: u32,
+    pub grout_width: f32,
+    pub grout_color: [u8; 3],
+    pub variation: f32,
+    pub seed: u64,
+}
+
+impl Default for MosaicSettings {
+    fn default() -> Self {
+        Self {
+            tile_size: 16,
+            grout_width: 2.0,
+            grout_color: [200, 195, 185],
+            variation: 40.0,
+            seed: 1,
+        }
+    }
+}
+
+struct Tile {
+    color: [f32; 3],
+    sin: f32,
+    cos: f32,
+}
+
+/// Rebuilds `image` from square tiles separated by grout lines.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::mosaicconverter::{apply_mosaic, MosaicSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_mosaic(image, &MosaicSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_mosaic(image: DynamicImage, settings: &MosaicSettings) -> RgbaImage {
+    let rgba_img = image.to_rgba8();
+    let (width, height) = rgba_img.dimensions();
+    if width == 0 || height == 0 {
+        return rgba_img;
+    }
+    let tile_size = settings.tile_size.max(2);
+    let cols = width.div_ceil(tile_size);
+    let rows = height.div_ceil(tile_size);
+
+    // Average source color per tile
+    let mut sums = vec![[0.0f32; 4]; (cols * rows) as usize];
+    for (x, y, pixel) in rgba_img.enumerate_pixels() {
+        let sum = &mut sums[((y / tile_size) * cols + x / tile_size) as usize];
+        sum[0] += pixel[0] as f32;
+        sum[1] += pixel[1] as f32;
+        sum[2] += pixel[2] as f32;
+        sum[3] += 1.0;
+    }
+
+    let variation = settings.variation / 100.0;
+    let tiles: Vec<Tile> = sums
+        .iter()
+        .enumerate()
+        .map(|(i, sum)| {
+            let (cx, cy) = ((i as u32 % cols) as i32, (i as u32 / cols) as i32);
+            let noise = |k: u32| hash_noise(cx, cy, settings.seed.wrapping_add(k as u64)) - 0.5;
+            let brightness = noise(0) * 2.0 * variation * 40.0;
+            let color = [0, 1, 2].map(|c| (sum[c] / sum[3] + brightness + noise(1 + c as u32) * variation * 20.0).clamp(0.0, 255.0));
+            let angle = (noise(4) * 2.0 * MAX_ROTATION_DEGREES * variation).to_radians();
+            Tile { color, sin: angle.sin(), cos: angle.cos() }
+        })
+        .collect();
+
+    let ts = tile_size as f32;
+    let half = (ts - settings.grout_width) / 2.0;
+    let bevel = (ts * 0.15).max(1.0);
+    let grout = settings.grout_color.map(|c| c as f32);
+    let step = 1.0 / SUPERSAMPLE as f32;
+    let samples = (SUPERSAMPLE * SUPERSAMPLE) as f32;
+
+    let mut output = rgba_img.clone();
+    output.par_chunks_mut(width as usize * 4).enumerate().for_each(|(y, row)| {
+        for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+            let mut acc = [0.0f32; 3];
+            for sy in 0..SUPERSAMPLE {
+                for sx in 0..SUPERSAMPLE {
+                    let px = x as f32 + (sx as f32 + 0.5) * step;
+                    let py = y as f32 + (sy as f32 + 0.5) * step;
+                    let (cx, cy) = ((px / ts) as u32, (py / ts) as u32);
+                    let tile = &tiles[(cy.min(rows - 1) * cols + cx.min(cols - 1)) as usize];
+
+                    // Rotate into the tile's own frame around its center
+                    let dx = px - (cx as f32 + 0.5) * ts;
+                    let dy = py - (cy as f32 + 0.5) * ts;
+                    let lx = dx * tile.cos + dy * tile.sin;
+                    let ly = -dx * tile.sin + dy * tile.cos;
+
+                    let color = if half > 0.0 && lx.abs() < half && ly.abs() < half {
+                        // Lit from the top-left: raised highlight on two sides, shadow on the others
+                        let highlight = (1.0 - (lx + half).min(ly + half) / bevel).max(0.0);
+                        let shadow = (1.0 - (half - lx).min(half - ly) / bevel).max(0.0);
+                        tile.color.map(|c| {
+                            let lit = c + (255.0 - c) * highlight * 0.35;
+                            lit * (1.0 - shadow * 0.35)
+                        })
+                    } else {
+                        grout
+                    };
+                    acc[0] += color[0];
+                    acc[1] += color[1];
+                    acc[2] += color[2];
+                }
+            }
+            for c in 0..3 {
+                pixel[c] = (acc[c] / samples).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    });
+
+    output
+}
@@ -0,0 +1,117 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+
+use crate::colorspace::{hsl_to_rgb, rgb_to_hsl};
+use crate::imageutils::sobel_gradients;
+
+#[derive(Clone, PartialEq)]
+pub struct NeonSettings {
+    pub color_mode: NeonColorMode,
+    pub neon_color: [u8; 3],
+    pub edge_threshold: f32,
+    pub glow_radius: f32,
+    pub glow_intensity: f32,
+    pub background_dim: f32,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum NeonColorMode {
+    Single,
+    SourceHue,
+    RainbowDirection,
+}
+
+impl NeonColorMode {
+    pub fn name(&self) -> &str {
+        match self {
+            NeonColorMode::Single => "Single Color",
+            NeonColorMode::SourceHue => "Hue From Source",
+            NeonColorMode::RainbowDirection => "Rainbow By Direction",
+        }
+    }
+}
+
+impl Default for NeonSettings {
+    fn default() -> Self {
+        Self {
+            color_mode: NeonColorMode::SourceHue,
+            neon_color: [255, 40, 200],
+            edge_threshold: 40.0,
+            glow_radius: 6.0,
+            glow_intensity: 150.0,
+            background_dim: 85.0,
+        }
+    }
+}
+
+/// Turns edges into glowing neon tubes over a dimmed background.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::neonconverter::{apply_neon, NeonSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_neon(image, &NeonSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_neon(image: DynamicImage, settings: &NeonSettings) -> RgbaImage {
+    let rgba_img = image.to_rgba8();
+    let (width, height) = rgba_img.dimensions();
+    if width == 0 || height == 0 {
+        return rgba_img;
+    }
+    let gray = image::imageops::blur(&image.to_luma8(), 1.0);
+    let gradients = sobel_gradients(&gray);
+
+    // Colored tubes: edge strength times the chosen neon color
+    let mut lines = RgbaImage::new(width, height);
+    for (x, y, pixel) in rgba_img.enumerate_pixels() {
+        let (gx, gy) = gradients[(y * width + x) as usize];
+        let magnitude = (gx * gx + gy * gy).sqrt() / 4.0;
+        let strength = ((magnitude - settings.edge_threshold) / 16.0 + 0.5).clamp(0.0, 1.0);
+        if strength <= 0.0 {
+            continue;
+        }
+
+        let [r, g, b] = match settings.color_mode {
+            NeonColorMode::Single => settings.neon_color.map(|c| c as f32 / 255.0),
+            NeonColorMode::SourceHue => {
+                let (h, _, _) = rgb_to_hsl(pixel[0] as f32 / 255.0, pixel[1] as f32 / 255.0, pixel[2] as f32 / 255.0);
+                let (r, g, b) = hsl_to_rgb(h, 1.0, 0.55);
+                [r, g, b]
+            }
+            NeonColorMode::RainbowDirection => {
+                // Orientation only, so both sides of a line share a hue
+                let hue = gy.atan2(gx).to_degrees().rem_euclid(180.0) * 2.0;
+                let (r, g, b) = hsl_to_rgb(hue, 1.0, 0.55);
+                [r, g, b]
+            }
+        };
+        let encode = |v: f32| (v * strength * 255.0).round().clamp(0.0, 255.0) as u8;
+        lines.put_pixel(x, y, Rgba([encode(r), encode(g), encode(b), 255]));
+    }
+
+    let glow = if settings.glow_radius > 0.0 {
+        image::imageops::blur(&lines, settings.glow_radius)
+    } else {
+        lines.clone()
+    };
+    let intensity = settings.glow_intensity / 100.0;
+    let keep = 1.0 - settings.background_dim / 100.0;
+
+    let mut output = RgbaImage::new(width, height);
+    for (x, y, pixel) in rgba_img.enumerate_pixels() {
+        let line = lines.get_pixel(x, y);
+        let halo = glow.get_pixel(x, y);
+        // Additive composite; tube cores run hot towards white
+        let core = line[0].max(line[1]).max(line[2]) as f32 / 255.0 * 0.5;
+        let channel = |c: usize| {
+            let tube = line[c] as f32 + (255.0 - line[c] as f32) * core;
+            (pixel[c] as f32 * keep + tube + halo[c] as f32 * intensity).round().clamp(0.0, 255.0) as u8
+        };
+        output.put_pixel(x, y, Rgba([channel(0), channel(1), channel(2), pixel[3]]));
+    }
+
+    output
+}
@@ -0,0 +1,215 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+
+use crate::imageutils::{hash_noise, value_noise};
+
+// Damage is specified per megapixel / per 1000px of width so large scans don't look sparse
+const DUST_PER_MEGAPIXEL: f32 = 400.0;
+const SCRATCHES_PER_1000PX: f32 = 6.0;
+const SEPIA: [f32; 3] = [1.07, 0.94, 0.74];
+
+#[derive(Clone, PartialEq)]
+pub struct OldPhotoSettings {
+    pub age: f32,
+    pub dust: bool,
+    pub scratches: bool,
+    pub border_damage: bool,
+    pub seed: u64,
+}
+
+impl Default for OldPhotoSettings {
+    fn default() -> Self {
+        Self {
+            age: 60.0,
+            dust: true,
+            scratches: true,
+            border_damage: true,
+            seed: 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Speck {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    pub light: bool,
+}
+
+// A mark from (x0, y0) to (x1, y1); scratches wobble sideways by `wobble` pixels
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Streak {
+    pub x0: f32,
+    pub y0: f32,
+    pub x1: f32,
+    pub y1: f32,
+    pub width: f32,
+    pub wobble: f32,
+}
+
+fn feature_scale(width: u32, height: u32) -> f32 {
+    (width.min(height) as f32 / 1000.0).max(0.25)
+}
+
+pub fn dust_specks(width: u32, height: u32, amount: f32, seed: u64) -> Vec<Speck> {
+    let megapixels = width as f32 * height as f32 / 1_000_000.0;
+    let count = (megapixels * DUST_PER_MEGAPIXEL * amount).round() as i32;
+    let scale = feature_scale(width, height);
+    (0..count)
+        .map(|i| {
+            let noise = |k: i32| hash_noise(i, k, seed);
+            Speck {
+                x: noise(0) * width as f32,
+                y: noise(1) * height as f32,
+                // Mostly tiny specks with the occasional larger blob
+                radius: (0.6 + noise(2).powi(4) * 4.0) * scale,
+                light: noise(3) < 0.7,
+            }
+        })
+        .collect()
+}
+
+pub fn hair_scratches(width: u32, height: u32, amount: f32, seed: u64) -> Vec<Streak> {
+    let count = (width as f32 / 1000.0 * SCRATCHES_PER_1000PX * amount).round() as i32;
+    let scale = feature_scale(width, height);
+    (0..count)
+        .map(|i| {
+            let noise = |k: i32| hash_noise(i, k + 10, seed);
+            let x = noise(0) * width as f32;
+            let start = noise(1) * height as f32 * 0.5;
+            let length = (0.3 + noise(2) * 0.7) * height as f32;
+            Streak {
+                x0: x,
+                y0: start,
+                x1: x + (noise(3) - 0.5) * 0.1 * length,
+                y1: (start + length).min(height as f32),
+                width: (0.5 + noise(4)) * scale,
+                wobble: noise(5) * 6.0 * scale,
+            }
+        })
+        .collect()
+}
+
+pub fn creases(width: u32, height: u32, amount: f32, seed: u64) -> Vec<Streak> {
+    let count = (amount * 2.0).round() as i32;
+    let scale = feature_scale(width, height);
+    let (w, h) = (width as f32, height as f32);
+    (0..count)
+        .map(|i| {
+            let noise = |k: i32| hash_noise(i, k + 20, seed);
+            // Corner-to-edge diagonals, like a print that was folded
+            let (x0, y0) = if noise(0) < 0.5 { (noise(1) * w * 0.3, 0.0) } else { (0.0, noise(1) * h * 0.3) };
+            let (x1, y1) = if noise(2) < 0.5 { (w * (0.6 + noise(3) * 0.4), h) } else { (w, h * (0.6 + noise(3) * 0.4)) };
+            Streak { x0, y0, x1, y1, width: (1.5 + noise(4) * 2.0) * scale, wobble: 0.0 }
+        })
+        .collect()
+}
+
+// Stamp anti-aliased discs into a damage layer, keeping the strongest mark per pixel
+fn stamp_disc(layer: &mut [f32], width: u32, height: u32, cx: f32, cy: f32, radius: f32, value: f32) {
+    let reach = radius + 1.0;
+    let x_start = (cx - reach).floor().max(0.0) as u32;
+    let y_start = (cy - reach).floor().max(0.0) as u32;
+    let x_end = ((cx + reach).ceil().max(0.0) as u32).min(width);
+    let y_end = ((cy + reach).ceil().max(0.0) as u32).min(height);
+    for y in y_start..y_end {
+        for x in x_start..x_end {
+            let distance = ((x as f32 + 0.5 - cx).powi(2) + (y as f32 + 0.5 - cy).powi(2)).sqrt();
+            let coverage = (radius - distance + 0.5).clamp(0.0, 1.0) * value;
+            let cell = &mut layer[(y * width + x) as usize];
+            if coverage.abs() > cell.abs() {
+                *cell = coverage;
+            }
+        }
+    }
+}
+
+fn stamp_streak(layer: &mut [f32], width: u32, height: u32, streak: &Streak, value: f32) {
+    let length = ((streak.x1 - streak.x0).powi(2) + (streak.y1 - streak.y0).powi(2)).sqrt();
+    let steps = (length * 2.0).ceil().max(1.0) as u32;
+    for step in 0..=steps {
+        let t = step as f32 / steps as f32;
+        let sway = (t * 9.0).sin() * streak.wobble;
+        let x = streak.x0 + (streak.x1 - streak.x0) * t + sway;
+        let y = streak.y0 + (streak.y1 - streak.y0) * t;
+        // Scratches taper at both ends
+        let taper = (t * (1.0 - t) * 4.0).min(1.0).sqrt();
+        stamp_disc(layer, width, height, x, y, streak.width / 2.0 * taper, value);
+    }
+}
+
+/// Ages `image` with sepia toning, dust, scratches and a worn border.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::oldphotoconverter::{apply_old_photo, OldPhotoSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_old_photo(image, &OldPhotoSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_old_photo(image: DynamicImage, settings: &OldPhotoSettings) -> RgbaImage {
+    let rgba_img = image.to_rgba8();
+    let (width, height) = rgba_img.dimensions();
+    let age = settings.age / 100.0;
+
+    // Positive values lighten (emulsion lifted off), negative ones darken
+    let mut damage = vec![0.0f32; (width * height) as usize];
+    if settings.dust {
+        for speck in dust_specks(width, height, age, settings.seed) {
+            let value = if speck.light { 0.85 } else { -0.7 };
+            stamp_disc(&mut damage, width, height, speck.x, speck.y, speck.radius, value);
+        }
+    }
+    if settings.scratches {
+        for scratch in hair_scratches(width, height, age, settings.seed) {
+            stamp_streak(&mut damage, width, height, &scratch, 0.6);
+        }
+    }
+    for crease in creases(width, height, age, settings.seed) {
+        stamp_streak(&mut damage, width, height, &crease, 0.15);
+    }
+
+    let scale = feature_scale(width, height);
+    let border = width.min(height) as f32 * 0.035;
+    let (w, h) = (width as f32, height as f32);
+
+    let mut output = RgbaImage::new(width, height);
+    for (x, y, pixel) in rgba_img.enumerate_pixels() {
+        let luma = (0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32) / 255.0;
+        // Faded contrast: lifted blacks and dulled whites
+        let faded = 0.08 * age + luma * (1.0 - 0.25 * age);
+
+        let mut rgb = [0, 1, 2].map(|c| {
+            let toned = (faded * SEPIA[c]).min(1.0) * 255.0;
+            pixel[c] as f32 + (toned - pixel[c] as f32) * (0.3 + 0.7 * age)
+        });
+
+        let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+        let radius = (((px / w - 0.5) * 2.0).powi(2) + ((py / h - 0.5) * 2.0).powi(2)).sqrt() / std::f32::consts::SQRT_2;
+        let vignette = 1.0 - 0.5 * age * ((radius - 0.4) / 0.6).clamp(0.0, 1.0).powi(2);
+        rgb = rgb.map(|c| c * vignette);
+
+        let mark = damage[(y * width + x) as usize];
+        if mark > 0.0 {
+            rgb = rgb.map(|c| c + (235.0 - c) * mark);
+        } else if mark < 0.0 {
+            rgb = rgb.map(|c| c * (1.0 + mark));
+        }
+
+        if settings.border_damage {
+            // Ragged edge: the border band's inner boundary wanders with noise
+            let edge_distance = px.min(py).min(w - px).min(h - py);
+            let ragged = border * (0.5 + value_noise(px + py * 0.37, px * 0.29 + py, 14.0 * scale, settings.seed));
+            let wear = (1.0 - edge_distance / ragged.max(1.0)).clamp(0.0, 1.0);
+            rgb = rgb.map(|c| c * (1.0 - 0.75 * wear * wear));
+        }
+
+        let encode = |v: f32| v.round().clamp(0.0, 255.0) as u8;
+        output.put_pixel(x, y, Rgba([encode(rgb[0]), encode(rgb[1]), encode(rgb[2]), pixel[3]]));
+    }
+
+    output
+}
@@ -0,0 +1,75 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+
+#[derive(Clone, PartialEq)]
+pub struct OrtonSettings {
+    pub blur_radius: f32,
+    pub glow_strength: f32,
+    pub highlight_protection: f32,
+}
+
+impl Default for OrtonSettings {
+    fn default() -> Self {
+        Self {
+            blur_radius: 12.0,
+            glow_strength: 60.0,
+            highlight_protection: 50.0,
+        }
+    }
+}
+
+#[inline]
+fn screen(a: f32, b: f32) -> f32 {
+    1.0 - (1.0 - a) * (1.0 - b)
+}
+
+/// Blends a blurred, brightened copy over `image` for a dreamy glow.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::ortonconverter::{apply_orton, OrtonSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_orton(image, &OrtonSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_orton(image: DynamicImage, settings: &OrtonSettings) -> RgbaImage {
+    let rgba_img = image.to_rgba8();
+    let strength = settings.glow_strength / 100.0;
+    if strength <= 0.0 || rgba_img.width() == 0 || rgba_img.height() == 0 {
+        return rgba_img;
+    }
+
+    // Classic darkroom recipe: an overexposed sharp copy multiplied with an overexposed blurred copy
+    let mut bright = rgba_img.clone();
+    for pixel in bright.pixels_mut() {
+        for c in 0..3 {
+            let v = pixel[c] as f32 / 255.0;
+            pixel[c] = (screen(v, v) * 255.0).round() as u8;
+        }
+    }
+    let glow = if settings.blur_radius > 0.0 {
+        image::imageops::blur(&bright, settings.blur_radius)
+    } else {
+        bright.clone()
+    };
+
+    let protection = settings.highlight_protection / 100.0;
+    let mut output = RgbaImage::new(rgba_img.width(), rgba_img.height());
+    for (((out, pixel), sharp), soft) in output.pixels_mut().zip(rgba_img.pixels()).zip(bright.pixels()).zip(glow.pixels()) {
+        let luma = (0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32) / 255.0;
+        // Back the effect off in highlights so skies keep their detail instead of clipping
+        let t = ((luma - 0.7) / 0.3).clamp(0.0, 1.0);
+        let weight = strength * (1.0 - protection * t * t * (3.0 - 2.0 * t));
+
+        let channel = |c: usize| {
+            let original = pixel[c] as f32 / 255.0;
+            let orton = sharp[c] as f32 / 255.0 * (soft[c] as f32 / 255.0);
+            ((original + (orton - original) * weight) * 255.0).round().clamp(0.0, 255.0) as u8
+        };
+        *out = Rgba([channel(0), channel(1), channel(2), pixel[3]]);
+    }
+
+    output
+}
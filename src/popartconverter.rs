@@ -0,0 +1,191 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+
+use crate::imageutils::{dilate_mask, median_cut, nearest_color, sobel_magnitude};
+
+// Larger inputs are processed at this size and scaled back up afterwards
+const MAX_WORKING_SIZE: u32 = 1200;
+
+#[derive(Clone, PartialEq)]
+pub struct PopArtSettings {
+    pub palette: PopArtPalette,
+    pub dot_size: f32,
+    pub dot_angle: f32,
+    // Width of the outlines: 0 draws none, 1 the bare edges and each step one pixel more
+    // on either side
+    pub outline_thickness: u32,
+    pub edge_threshold: f32,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum PopArtPalette {
+    Primary,
+    Warhol,
+    Newsprint,
+    Adaptive,
+}
+
+impl PopArtPalette {
+    pub fn name(&self) -> &str {
+        match self {
+            PopArtPalette::Primary => "Primary",
+            PopArtPalette::Warhol => "Warhol",
+            PopArtPalette::Newsprint => "Newsprint",
+            PopArtPalette::Adaptive => "Adaptive",
+        }
+    }
+
+    fn colors(&self) -> Vec<[u8; 3]> {
+        match self {
+            PopArtPalette::Primary => vec![
+                [255, 250, 235],
+                [255, 222, 0],
+                [228, 30, 38],
+                [20, 90, 200],
+                [245, 190, 160],
+                [20, 20, 20],
+            ],
+            PopArtPalette::Warhol => vec![
+                [255, 240, 245],
+                [255, 105, 180],
+                [255, 215, 0],
+                [0, 200, 200],
+                [120, 60, 200],
+                [20, 20, 20],
+            ],
+            PopArtPalette::Newsprint => vec![
+                [245, 240, 225],
+                [240, 200, 60],
+                [200, 60, 50],
+                [60, 110, 160],
+                [30, 30, 30],
+            ],
+            PopArtPalette::Adaptive => Vec::new(),
+        }
+    }
+}
+
+impl Default for PopArtSettings {
+    fn default() -> Self {
+        Self {
+            palette: PopArtPalette::Primary,
+            dot_size: 6.0,
+            dot_angle: 45.0,
+            outline_thickness: 2,
+            edge_threshold: 60.0,
+        }
+    }
+}
+
+/// Posterizes `image` into flat pop-art colour panels.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::popartconverter::{apply_pop_art, PopArtSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_pop_art(image, &PopArtSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_pop_art(image: DynamicImage, settings: &PopArtSettings) -> RgbaImage {
+    let (orig_width, orig_height) = (image.width(), image.height());
+    if orig_width == 0 || orig_height == 0 {
+        return image.to_rgba8();
+    }
+
+    // Keep the edge pass and dot screen interactive on large photos
+    let working = if orig_width.max(orig_height) > MAX_WORKING_SIZE {
+        image.resize(MAX_WORKING_SIZE, MAX_WORKING_SIZE, image::imageops::FilterType::Triangle)
+    } else {
+        image
+    };
+
+    let rgb_img = working.to_rgb8();
+    let (width, height) = rgb_img.dimensions();
+
+    let palette = match settings.palette {
+        PopArtPalette::Adaptive => adaptive_palette(&rgb_img),
+        ref preset => preset.colors(),
+    };
+    let paper = *palette
+        .iter()
+        .max_by_key(|c| c[0] as u32 + c[1] as u32 + c[2] as u32)
+        .unwrap_or(&[255, 255, 255]);
+
+    // Outline mask from a lightly blurred luminance pass
+    let gray = image::imageops::blur(&working.to_luma8(), 1.0);
+    let magnitude = sobel_magnitude(&gray);
+    let edges: Vec<bool> = magnitude.iter().map(|&m| m > settings.edge_threshold).collect();
+    let outline = if settings.outline_thickness > 0 {
+        dilate_mask(&edges, width, height, settings.outline_thickness - 1)
+    } else {
+        vec![false; edges.len()]
+    };
+
+    let cell = settings.dot_size.max(2.0);
+    let (sin_a, cos_a) = settings.dot_angle.to_radians().sin_cos();
+
+    let mut output = RgbaImage::new(width, height);
+
+    for y in 0..height {
+        for x in 0..width {
+            if outline[(y * width + x) as usize] {
+                output.put_pixel(x, y, Rgba([15, 15, 15, 255]));
+                continue;
+            }
+
+            let pixel = rgb_img.get_pixel(x, y);
+            let color = nearest_color(&palette, [pixel[0], pixel[1], pixel[2]]);
+            let luma = (0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32) / 255.0;
+
+            // Midtones become colored dots on paper, shadows and highlights stay flat
+            let final_color = if luma > 0.25 && luma < 0.75 {
+                let coverage = (0.75 - luma) / 0.5;
+                let radius = cell * std::f32::consts::FRAC_1_SQRT_2 * coverage.sqrt();
+
+                let u = x as f32 * cos_a + y as f32 * sin_a;
+                let v = -(x as f32) * sin_a + y as f32 * cos_a;
+                let du = u - ((u / cell).floor() + 0.5) * cell;
+                let dv = v - ((v / cell).floor() + 0.5) * cell;
+
+                if (du * du + dv * dv).sqrt() <= radius { color } else { paper }
+            } else {
+                color
+            };
+
+            output.put_pixel(x, y, Rgba([final_color[0], final_color[1], final_color[2], 255]));
+        }
+    }
+
+    if (width, height) != (orig_width, orig_height) {
+        output = image::imageops::resize(&output, orig_width, orig_height, image::imageops::FilterType::Triangle);
+    }
+
+    output
+}
+
+fn adaptive_palette(img: &image::RgbImage) -> Vec<[u8; 3]> {
+    // Sample a coarse grid so palette extraction stays cheap
+    let step = ((img.width() * img.height()) as f32 / 4096.0).sqrt().max(1.0) as u32;
+    let mut samples = Vec::new();
+    for y in (0..img.height()).step_by(step as usize) {
+        for x in (0..img.width()).step_by(step as usize) {
+            let p = img.get_pixel(x, y);
+            samples.push([p[0], p[1], p[2]]);
+        }
+    }
+
+    // Push the extracted colors towards flat, saturated comic inks
+    let mut palette: Vec<[u8; 3]> = median_cut(samples, 5)
+        .into_iter()
+        .map(|c| {
+            let avg = (c[0] as f32 + c[1] as f32 + c[2] as f32) / 3.0;
+            let boost = |v: u8| (avg + (v as f32 - avg) * 1.6).clamp(0.0, 255.0) as u8;
+            [boost(c[0]), boost(c[1]), boost(c[2])]
+        })
+        .collect();
+    palette.push([255, 250, 235]);
+    palette.push([20, 20, 20]);
+    palette
+}
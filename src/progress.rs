@@ -0,0 +1,85 @@
+//! Progress reporting and cancellation for long-running converters.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+
+// Stands in when the caller passes no handle, so converters never branch on the Option
+static NO_PROGRESS: Progress = Progress { sender: None, cancelled: None };
+
+/// Handle a converter reports progress through and polls for cancellation.
+///
+/// Converters that can run for a while take an `Option<&Progress>`; pass `None` when
+/// nobody is watching. Updates arrive on the channel as `(done, total)` pairs in the
+/// converter's own units (usually rows). A cancelled converter stops early and returns
+/// a partially processed image, which the caller should discard.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::atomic::AtomicBool;
+/// use std::sync::{mpsc, Arc};
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::fisheyeconverter::{apply_fisheye, FisheyeSettings};
+/// use pixforge::progress::Progress;
+///
+/// let (sender, receiver) = mpsc::channel();
+/// let progress = Progress::new(sender, Arc::new(AtomicBool::new(false)));
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 16));
+/// apply_fisheye(image, &FisheyeSettings::default(), Some(&progress));
+/// assert_eq!(receiver.try_iter().last(), Some((16, 16)));
+/// ```
+pub struct Progress {
+    sender: Option<mpsc::Sender<(usize, usize)>>,
+    cancelled: Option<Arc<AtomicBool>>,
+}
+
+impl Progress {
+    /// Sends updates to `sender`; setting `cancelled` asks the converter to stop.
+    pub fn new(sender: mpsc::Sender<(usize, usize)>, cancelled: Arc<AtomicBool>) -> Self {
+        Self {
+            sender: Some(sender),
+            cancelled: Some(cancelled),
+        }
+    }
+
+    /// Records that `done` of `total` units are finished.
+    pub fn report(&self, done: usize, total: usize) {
+        if let Some(sender) = &self.sender {
+            // Nobody listening any more just means the result is no longer wanted
+            let _ = sender.send((done, total));
+        }
+    }
+
+    /// Whether the caller has asked the converter to stop.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+
+    // Row indices 0..total, reporting each as it starts and the total at the end,
+    // and stopping early once cancelled
+    pub(crate) fn rows(&self, total: u32) -> impl Iterator<Item = u32> + '_ {
+        (0..=total)
+            .take_while(move |_| !self.is_cancelled())
+            .inspect(move |&y| self.report(y as usize, total as usize))
+            .filter(move |&y| y < total)
+    }
+}
+
+// Row counter shared by the rayon workers, which finish rows in any order
+pub(crate) struct RowProgress<'a> {
+    pub(crate) progress: &'a Progress,
+    pub(crate) done: AtomicUsize,
+    pub(crate) total: usize,
+}
+
+impl RowProgress<'_> {
+    pub(crate) fn tick(&self) {
+        let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+        self.progress.report(done, self.total);
+    }
+}
+
+// The handle converters use when the caller passed `None`
+pub(crate) fn or_none(progress: Option<&Progress>) -> &Progress {
+    progress.unwrap_or(&NO_PROGRESS)
+}
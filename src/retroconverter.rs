@@ -0,0 +1,184 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+
+use crate::ditherconverter::BAYER_4X4;
+use crate::imageutils::{median_cut, nearest_color};
+
+// The commonly used 2C02 NES palette with the duplicate blacks removed
+const NES_PALETTE: [[u8; 3]; 52] = [
+    [124, 124, 124], [0, 0, 252], [0, 0, 188], [68, 40, 188], [148, 0, 132], [168, 0, 32], [168, 16, 0], [136, 20, 0],
+    [80, 48, 0], [0, 120, 0], [0, 104, 0], [0, 88, 0], [0, 64, 88], [0, 0, 0],
+    [188, 188, 188], [0, 120, 248], [0, 88, 248], [104, 68, 252], [216, 0, 204], [228, 0, 88], [248, 56, 0], [228, 92, 16],
+    [172, 124, 0], [0, 184, 0], [0, 168, 0], [0, 168, 68], [0, 136, 136],
+    [248, 248, 248], [60, 188, 252], [104, 136, 252], [152, 120, 248], [248, 120, 248], [248, 88, 152], [248, 120, 88], [252, 160, 68],
+    [248, 184, 0], [184, 248, 24], [88, 216, 84], [88, 248, 152], [0, 232, 216], [120, 120, 120],
+    [252, 252, 252], [164, 228, 252], [184, 184, 248], [216, 184, 248], [248, 184, 248], [248, 164, 192], [240, 208, 176], [252, 224, 168],
+    [248, 216, 120], [216, 248, 120], [184, 248, 184],
+];
+
+pub(crate) const GAME_BOY_PALETTE: [[u8; 3]; 4] = [[15, 56, 15], [48, 98, 48], [139, 172, 15], [155, 188, 15]];
+
+#[derive(Clone, PartialEq)]
+pub struct RetroSettings {
+    pub console: RetroConsole,
+    pub palette: RetroConsole,
+    pub width: u32,
+    pub height: u32,
+    pub max_colors: u32,
+    pub dither: bool,
+    pub dither_strength: f32,
+    pub scale: u32,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RetroConsole {
+    Nes,
+    Snes,
+    GameBoy,
+    MasterSystem,
+}
+
+impl RetroConsole {
+    pub const ALL: [RetroConsole; 4] = [RetroConsole::Nes, RetroConsole::Snes, RetroConsole::GameBoy, RetroConsole::MasterSystem];
+
+    pub fn name(&self) -> &str {
+        match self {
+            RetroConsole::Nes => "NES",
+            RetroConsole::Snes => "SNES",
+            RetroConsole::GameBoy => "Game Boy",
+            RetroConsole::MasterSystem => "Master System",
+        }
+    }
+
+    // Native resolution and how many colors can be on screen at once
+    fn limits(&self) -> (u32, u32, u32) {
+        match self {
+            RetroConsole::Nes => (256, 240, 25),
+            RetroConsole::Snes => (256, 224, 256),
+            RetroConsole::GameBoy => (160, 144, 4),
+            RetroConsole::MasterSystem => (256, 192, 32),
+        }
+    }
+
+    pub fn settings(&self) -> RetroSettings {
+        let (width, height, max_colors) = self.limits();
+        RetroSettings {
+            console: *self,
+            palette: *self,
+            width,
+            height,
+            max_colors,
+            dither: *self != RetroConsole::Snes,
+            dither_strength: 50.0,
+            scale: 0,
+        }
+    }
+}
+
+impl Default for RetroSettings {
+    fn default() -> Self {
+        RetroConsole::Nes.settings()
+    }
+}
+
+// Snap a color to the nearest value the SNES's 15-bit RGB can show
+#[inline]
+fn snap_15bit(c: u8) -> u8 {
+    let v = c & 0xF8;
+    v | (v >> 5)
+}
+
+// Every color the console hardware can output, or None when the gamut is too large to list
+pub fn hardware_palette(console: RetroConsole) -> Option<Vec<[u8; 3]>> {
+    match console {
+        RetroConsole::Nes => Some(NES_PALETTE.to_vec()),
+        RetroConsole::GameBoy => Some(GAME_BOY_PALETTE.to_vec()),
+        RetroConsole::MasterSystem => {
+            // 2 bits per channel
+            let level = |bits: usize| (bits & 3) as u8 * 85;
+            Some((0..64).map(|i| [level(i >> 4), level(i >> 2), level(i)]).collect())
+        }
+        RetroConsole::Snes => None,
+    }
+}
+
+// The colors one frame may use: the most-used hardware colors up to the on-screen limit.
+// The SNES picks them freely from its 15-bit gamut, so those come from median cut instead.
+pub fn frame_palette(image: &RgbaImage, console: RetroConsole, max_colors: u32) -> Vec<[u8; 3]> {
+    let pixels: Vec<[u8; 3]> = image.pixels().map(|p| [p[0], p[1], p[2]]).collect();
+    let max_colors = max_colors.max(2) as usize;
+
+    let Some(hardware) = hardware_palette(console) else {
+        let mut palette: Vec<[u8; 3]> = median_cut(pixels, max_colors).into_iter().map(|c| c.map(snap_15bit)).collect();
+        palette.sort_unstable();
+        palette.dedup();
+        if palette.is_empty() {
+            palette.push([0, 0, 0]);
+        }
+        return palette;
+    };
+    if hardware.len() <= max_colors {
+        return hardware;
+    }
+
+    let mut usage = vec![0usize; hardware.len()];
+    for pixel in &pixels {
+        let nearest = nearest_color(&hardware, *pixel);
+        if let Some(index) = hardware.iter().position(|c| *c == nearest) {
+            usage[index] += 1;
+        }
+    }
+    let mut ranked: Vec<usize> = (0..hardware.len()).collect();
+    ranked.sort_by_key(|&i| std::cmp::Reverse(usage[i]));
+    ranked.into_iter().take(max_colors).map(|i| hardware[i]).collect()
+}
+
+/// Downscales `image` to a console resolution and quantizes it to that console's palette.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::retroconverter::{apply_retro, RetroSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_retro(image, &RetroSettings::default());
+/// // Fitted to the NES screen height, then kept at 1x since the source is smaller
+/// assert_eq!(output.dimensions(), (240, 240));
+/// ```
+pub fn apply_retro(image: DynamicImage, settings: &RetroSettings) -> RgbaImage {
+    let (orig_width, orig_height) = (image.width(), image.height());
+    if orig_width == 0 || orig_height == 0 {
+        return image.to_rgba8();
+    }
+    // Fit inside the native screen, keeping the aspect ratio
+    let small = image.resize(settings.width.max(1), settings.height.max(1), image::imageops::FilterType::Triangle).to_rgba8();
+    let (width, height) = small.dimensions();
+
+    let palette = frame_palette(&small, settings.palette, settings.max_colors);
+    // Ordered dither offsets scaled to the typical gap between palette entries
+    let spread = 255.0 / (palette.len() as f32).cbrt().max(1.0) * settings.dither_strength / 100.0;
+
+    let mut quantized = RgbaImage::new(width, height);
+    for (x, y, pixel) in small.enumerate_pixels() {
+        let offset = if settings.dither {
+            (BAYER_4X4[(y % 4) as usize][(x % 4) as usize] as f32 / 16.0 - 0.5) * spread
+        } else {
+            0.0
+        };
+        let color = [0, 1, 2].map(|c| (pixel[c] as f32 + offset).round().clamp(0.0, 255.0) as u8);
+        let [r, g, b] = nearest_color(&palette, color);
+        // Consoles have no partial transparency
+        let alpha = if pixel[3] >= 128 { 255 } else { 0 };
+        quantized.put_pixel(x, y, Rgba([r, g, b, alpha]));
+    }
+
+    let scale = if settings.scale == 0 {
+        (orig_width / width).min(orig_height / height).max(1)
+    } else {
+        settings.scale
+    };
+    if scale == 1 {
+        return quantized;
+    }
+    image::imageops::resize(&quantized, width * scale, height * scale, image::imageops::FilterType::Nearest)
+}
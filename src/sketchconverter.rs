@@ -0,0 +1,117 @@
+use image::{DynamicImage, GrayImage, Luma, RgbaImage, Rgba};
+
+use crate::imageutils::sobel_magnitude;
+
+#[derive(Clone, PartialEq)]
+pub struct SketchSettings {
+    pub mode: SketchMode,
+    pub blur_radius: f32,
+    pub darkness: f32,
+    pub edge_threshold: f32,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum SketchMode {
+    Pencil,
+    ColoredPencil,
+    LineArt,
+}
+
+impl SketchMode {
+    pub fn name(&self) -> &str {
+        match self {
+            SketchMode::Pencil => "Pencil",
+            SketchMode::ColoredPencil => "Colored Pencil",
+            SketchMode::LineArt => "Line Art",
+        }
+    }
+}
+
+impl Default for SketchSettings {
+    fn default() -> Self {
+        Self {
+            mode: SketchMode::Pencil,
+            blur_radius: 8.0,
+            darkness: 30.0,
+            edge_threshold: 40.0,
+        }
+    }
+}
+
+/// Draws `image` as a pencil, coloured pencil or line-art sketch.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::sketchconverter::{apply_sketch, SketchSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_sketch(image, &SketchSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_sketch(image: DynamicImage, settings: &SketchSettings) -> RgbaImage {
+    let rgba_img = image.to_rgba8();
+    let gray = image.to_luma8();
+    let (width, height) = gray.dimensions();
+    if width == 0 || height == 0 {
+        return rgba_img;
+    }
+
+    let tone = match settings.mode {
+        SketchMode::Pencil | SketchMode::ColoredPencil => color_dodge_sketch(&gray, settings),
+        SketchMode::LineArt => line_art(&gray, settings.edge_threshold),
+    };
+
+    let mut output = RgbaImage::new(width, height);
+    for (x, y, pixel) in rgba_img.enumerate_pixels() {
+        let value = tone.get_pixel(x, y)[0];
+        let out = if settings.mode == SketchMode::ColoredPencil {
+            // Muted, lightened source color multiplied under the graphite
+            let luma = gray.get_pixel(x, y)[0] as f32;
+            let tint = |c: u8| {
+                let muted = luma + (c as f32 - luma) * 0.5;
+                let pastel = muted + (255.0 - muted) * 0.4;
+                (pastel * value as f32 / 255.0).round() as u8
+            };
+            Rgba([tint(pixel[0]), tint(pixel[1]), tint(pixel[2]), pixel[3]])
+        } else {
+            Rgba([value, value, value, pixel[3]])
+        };
+        output.put_pixel(x, y, out);
+    }
+
+    output
+}
+
+// Classic dodge sketch: the gray image divided by its blurred negative, so flat areas go white
+fn color_dodge_sketch(gray: &GrayImage, settings: &SketchSettings) -> GrayImage {
+    let (width, height) = gray.dimensions();
+    let inverted = GrayImage::from_fn(width, height, |x, y| Luma([255 - gray.get_pixel(x, y)[0]]));
+    let blurred = if settings.blur_radius > 0.0 {
+        image::imageops::blur(&inverted, settings.blur_radius)
+    } else {
+        inverted
+    };
+
+    let exponent = 1.0 + settings.darkness / 25.0;
+    GrayImage::from_fn(width, height, |x, y| {
+        let base = gray.get_pixel(x, y)[0] as f32;
+        let blend = blurred.get_pixel(x, y)[0] as f32;
+        let dodged = if blend >= 255.0 { 255.0 } else { (base * 255.0 / (255.0 - blend)).min(255.0) };
+        Luma([(255.0 * (dodged / 255.0).powf(exponent)).round() as u8])
+    })
+}
+
+fn line_art(gray: &GrayImage, threshold: f32) -> GrayImage {
+    let (width, height) = gray.dimensions();
+    // A light pre-blur keeps sensor noise from turning into speckles
+    let smoothed = image::imageops::blur(gray, 1.0);
+    let magnitude = sobel_magnitude(&smoothed);
+    GrayImage::from_fn(width, height, |x, y| {
+        let edge = magnitude[(y * width + x) as usize];
+        // Soften the cut over a few levels so lines are anti-aliased
+        let ink = ((edge - threshold) / 16.0 + 0.5).clamp(0.0, 1.0);
+        Luma([(255.0 * (1.0 - ink)).round() as u8])
+    })
+}
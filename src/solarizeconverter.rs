@@ -0,0 +1,132 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+
+#[derive(Clone, PartialEq)]
+pub struct SolarizeSettings {
+    pub mode: SolarizeMode,
+    pub direction: SolarizeDirection,
+    pub threshold: f32,
+    pub rolloff: f32,
+    pub double: bool,
+    pub secondary_threshold: f32,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum SolarizeMode {
+    PerChannel,
+    Luminance,
+}
+
+impl SolarizeMode {
+    pub fn name(&self) -> &str {
+        match self {
+            SolarizeMode::PerChannel => "Per Channel (RGB)",
+            SolarizeMode::Luminance => "Luminance",
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum SolarizeDirection {
+    Highlights,
+    Shadows,
+}
+
+impl SolarizeDirection {
+    pub fn name(&self) -> &str {
+        match self {
+            SolarizeDirection::Highlights => "Invert Highlights",
+            SolarizeDirection::Shadows => "Invert Shadows",
+        }
+    }
+}
+
+impl Default for SolarizeSettings {
+    fn default() -> Self {
+        Self {
+            mode: SolarizeMode::PerChannel,
+            direction: SolarizeDirection::Highlights,
+            threshold: 128.0,
+            rolloff: 24.0,
+            double: false,
+            secondary_threshold: 64.0,
+        }
+    }
+}
+
+// How much of the negative to mix in for a tone `value` (0..255).
+// The smoothstep ramp is `rolloff` levels wide and slides with the threshold so that
+// threshold 255 never inverts and threshold 0 always does (for highlights).
+pub fn inversion_weight(value: f32, threshold: f32, rolloff: f32, direction: &SolarizeDirection) -> f32 {
+    let width = rolloff.max(1.0) / 255.0;
+    let start = (threshold / 255.0) * (1.0 + width) - width;
+    let t = ((value / 255.0 - start) / width).clamp(0.0, 1.0);
+    let weight = t * t * (3.0 - 2.0 * t);
+    match direction {
+        SolarizeDirection::Highlights => weight,
+        SolarizeDirection::Shadows => 1.0 - weight,
+    }
+}
+
+fn thresholds(settings: &SolarizeSettings) -> Vec<f32> {
+    let mut passes = vec![settings.threshold];
+    if settings.double {
+        passes.push(settings.secondary_threshold);
+    }
+    passes
+}
+
+pub fn build_lut(settings: &SolarizeSettings) -> [u8; 256] {
+    let mut lut = [0u8; 256];
+    for (i, entry) in lut.iter_mut().enumerate() {
+        let mut value = i as f32;
+        for threshold in thresholds(settings) {
+            let weight = inversion_weight(value, threshold, settings.rolloff, &settings.direction);
+            value += weight * (255.0 - 2.0 * value);
+        }
+        *entry = value.round().clamp(0.0, 255.0) as u8;
+    }
+    lut
+}
+
+/// Inverts tones past the threshold, like a Sabattier darkroom exposure.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::solarizeconverter::{apply_solarize, SolarizeSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_solarize(image, &SolarizeSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_solarize(image: DynamicImage, settings: &SolarizeSettings) -> RgbaImage {
+    let mut rgba_img = image.to_rgba8();
+
+    match settings.mode {
+        SolarizeMode::PerChannel => {
+            let lut = build_lut(settings);
+            for pixel in rgba_img.pixels_mut() {
+                *pixel = Rgba([lut[pixel[0] as usize], lut[pixel[1] as usize], lut[pixel[2] as usize], pixel[3]]);
+            }
+        }
+        SolarizeMode::Luminance => {
+            // The pixel's luminance decides how far the whole color is pushed towards its negative
+            let passes = thresholds(settings);
+            for pixel in rgba_img.pixels_mut() {
+                let mut rgb = [pixel[0] as f32, pixel[1] as f32, pixel[2] as f32];
+                for &threshold in &passes {
+                    let luma = 0.299 * rgb[0] + 0.587 * rgb[1] + 0.114 * rgb[2];
+                    let weight = inversion_weight(luma, threshold, settings.rolloff, &settings.direction);
+                    for channel in rgb.iter_mut() {
+                        *channel += weight * (255.0 - 2.0 * *channel);
+                    }
+                }
+                let encode = |v: f32| v.round().clamp(0.0, 255.0) as u8;
+                *pixel = Rgba([encode(rgb[0]), encode(rgb[1]), encode(rgb[2]), pixel[3]]);
+            }
+        }
+    }
+
+    rgba_img
+}
@@ -0,0 +1,202 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+use rayon::prelude::*;
+
+use crate::colorspace::{hsl_to_rgb, rgb_to_hsl};
+use crate::imageutils::{hash_noise, sobel_magnitude};
+
+const LEAD: [f32; 3] = [28.0, 24.0, 22.0];
+// Edge detection for site placement runs on a copy no larger than this
+const BIAS_MAP_SIZE: u32 = 512;
+
+#[derive(Clone, PartialEq)]
+pub struct StainedGlassSettings {
+    pub cell_count: u32,
+    pub border_width: f32,
+    pub saturation_boost: f32,
+    pub backlight: bool,
+    pub seed: u64,
+}
+
+impl Default for StainedGlassSettings {
+    fn default() -> Self {
+        Self {
+            cell_count: 600,
+            border_width: 3.0,
+            saturation_boost: 40.0,
+            backlight: true,
+            seed: 1,
+        }
+    }
+}
+
+// Nearest site for a pixel and its distance to the closest edge of that site's cell
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CellSample {
+    pub site: usize,
+    pub site_distance: f32,
+    pub border_distance: f32,
+}
+
+// Half the sites are uniform, the rest prefer strong edges so cells follow the subject's outlines
+pub fn generate_sites(image: &DynamicImage, count: u32, seed: u64) -> Vec<(f32, f32)> {
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    let small = image.resize(BIAS_MAP_SIZE, BIAS_MAP_SIZE, image::imageops::FilterType::Triangle).to_luma8();
+    let edges = sobel_magnitude(&small);
+    let (sw, sh) = small.dimensions();
+
+    let count = count.max(1) as usize;
+    let mut sites = Vec::with_capacity(count);
+    let mut attempt = 0i32;
+    while sites.len() < count {
+        let x = hash_noise(attempt, 0, seed) * width;
+        let y = hash_noise(attempt, 1, seed) * height;
+        let accept = if sites.len() % 2 == 0 {
+            true
+        } else {
+            let ex = ((x / width * sw as f32) as u32).min(sw - 1);
+            let ey = ((y / height * sh as f32) as u32).min(sh - 1);
+            let strength = (0.05 + edges[(ey * sw + ex) as usize] / 64.0).min(1.0);
+            hash_noise(attempt, 2, seed) < strength
+        };
+        if accept {
+            sites.push((x, y));
+        }
+        attempt += 1;
+    }
+    sites
+}
+
+// Uniform bucket grid over the sites for ring-by-ring neighbour searches
+struct SiteGrid {
+    bucket: f32,
+    cols: i32,
+    rows: i32,
+    cells: Vec<Vec<usize>>,
+}
+
+impl SiteGrid {
+    fn new(sites: &[(f32, f32)], width: u32, height: u32) -> Self {
+        let bucket = ((width as f32 * height as f32) / sites.len().max(1) as f32).sqrt().max(1.0);
+        let cols = (width as f32 / bucket).ceil().max(1.0) as i32;
+        let rows = (height as f32 / bucket).ceil().max(1.0) as i32;
+        let mut cells = vec![Vec::new(); (cols * rows) as usize];
+        for (i, &(x, y)) in sites.iter().enumerate() {
+            let bx = ((x / bucket) as i32).clamp(0, cols - 1);
+            let by = ((y / bucket) as i32).clamp(0, rows - 1);
+            cells[(by * cols + bx) as usize].push(i);
+        }
+        Self { bucket, cols, rows, cells }
+    }
+
+    fn ring(&self, bx: i32, by: i32, r: i32) -> impl Iterator<Item = &usize> {
+        (by - r..=by + r)
+            .flat_map(move |y| (bx - r..=bx + r).map(move |x| (x, y)))
+            .filter(move |&(x, y)| (x - bx).abs() == r || (y - by).abs() == r)
+            .filter(move |&(x, y)| x >= 0 && y >= 0 && x < self.cols && y < self.rows)
+            .flat_map(move |(x, y)| self.cells[(y * self.cols + x) as usize].iter())
+    }
+}
+
+// Finds the nearest site, then the distance to the nearest bisector with any other site.
+// Only sites within `reach` of the nearest distance can own a closer bisector, since the
+// bisector distance is at least half the difference in site distances.
+fn sample_cell(grid: &SiteGrid, sites: &[(f32, f32)], px: f32, py: f32, reach: f32) -> CellSample {
+    let bx = ((px / grid.bucket) as i32).clamp(0, grid.cols - 1);
+    let by = ((py / grid.bucket) as i32).clamp(0, grid.rows - 1);
+    let max_ring = grid.cols.max(grid.rows);
+
+    let mut candidates: Vec<(usize, f32)> = Vec::new();
+    let mut best = f32::INFINITY;
+    for r in 0..=max_ring {
+        if (r - 1) as f32 * grid.bucket > best + reach {
+            break;
+        }
+        for &i in grid.ring(bx, by, r) {
+            let (sx, sy) = sites[i];
+            let d = ((px - sx).powi(2) + (py - sy).powi(2)).sqrt();
+            best = best.min(d);
+            candidates.push((i, d));
+        }
+    }
+
+    let &(site, d1) = candidates.iter().min_by(|a, b| a.1.total_cmp(&b.1)).unwrap_or(&(0, 0.0));
+    let (s1x, s1y) = sites[site];
+    let border_distance = candidates
+        .iter()
+        .filter(|&&(i, d)| i != site && d <= d1 + reach)
+        .map(|&(i, d2)| {
+            let (s2x, s2y) = sites[i];
+            let separation = ((s2x - s1x).powi(2) + (s2y - s1y).powi(2)).sqrt().max(1e-6);
+            (d2 * d2 - d1 * d1) / (2.0 * separation)
+        })
+        .fold(f32::INFINITY, f32::min);
+
+    CellSample { site, site_distance: d1, border_distance }
+}
+
+pub fn voronoi_partition(width: u32, height: u32, sites: &[(f32, f32)], reach: f32) -> Vec<CellSample> {
+    let grid = SiteGrid::new(sites, width, height);
+    (0..height)
+        .into_par_iter()
+        .flat_map_iter(|y| {
+            let grid = &grid;
+            (0..width).map(move |x| sample_cell(grid, sites, x as f32 + 0.5, y as f32 + 0.5, reach))
+        })
+        .collect()
+}
+
+/// Splits `image` into Voronoi glass panes with lead borders.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::stainedglassconverter::{apply_stained_glass, StainedGlassSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_stained_glass(image, &StainedGlassSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_stained_glass(image: DynamicImage, settings: &StainedGlassSettings) -> RgbaImage {
+    let rgba_img = image.to_rgba8();
+    let (width, height) = rgba_img.dimensions();
+    let sites = generate_sites(&image, settings.cell_count, settings.seed);
+    let half_border = settings.border_width / 2.0;
+    let cells = voronoi_partition(width, height, &sites, 2.0 * (half_border + 1.0));
+
+    // Average color per cell, then pushed towards saturated glass
+    let mut sums = vec![[0.0f32; 4]; sites.len()];
+    for (cell, pixel) in cells.iter().zip(rgba_img.pixels()) {
+        let sum = &mut sums[cell.site];
+        sum[0] += pixel[0] as f32;
+        sum[1] += pixel[1] as f32;
+        sum[2] += pixel[2] as f32;
+        sum[3] += 1.0;
+    }
+    let boost = 1.0 + settings.saturation_boost / 100.0;
+    let glass: Vec<[f32; 3]> = sums
+        .iter()
+        .map(|sum| {
+            let n = sum[3].max(1.0);
+            let (h, s, l) = rgb_to_hsl(sum[0] / n / 255.0, sum[1] / n / 255.0, sum[2] / n / 255.0);
+            let (r, g, b) = hsl_to_rgb(h, (s * boost).clamp(0.0, 1.0), l);
+            [r * 255.0, g * 255.0, b * 255.0]
+        })
+        .collect();
+
+    let mut output = RgbaImage::new(width, height);
+    for ((cell, pixel), out) in cells.iter().zip(rgba_img.pixels()).zip(output.pixels_mut()) {
+        let mut color = glass[cell.site];
+        if settings.backlight {
+            // Brighter towards the middle of each pane, as if lit from behind
+            let center = cell.border_distance / (cell.border_distance + cell.site_distance).max(1e-6);
+            let light = 0.8 + 0.35 * center;
+            color = color.map(|c| c * light);
+        }
+        let lead = (half_border - cell.border_distance + 0.5).clamp(0.0, 1.0);
+        let channel = |c: usize| (color[c] + (LEAD[c] - color[c]) * lead).round().clamp(0.0, 255.0) as u8;
+        *out = Rgba([channel(0), channel(1), channel(2), pixel[3]]);
+    }
+
+    output
+}
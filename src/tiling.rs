@@ -0,0 +1,32 @@
+//! Band-by-band execution for very large images.
+//!
+//! The `apply_*_tiled` functions write their output in horizontal bands, reading only
+//! the input rows each band needs, so peak memory stays close to input + output + one
+//! band. Their results are identical to the untiled functions.
+
+use image::RgbaImage;
+
+use crate::progress::Progress;
+
+/// Rows per band for callers without a better figure.
+pub const DEFAULT_BAND_HEIGHT: u32 = 256;
+
+// Calls `render(start, band)` for consecutive bands of `output`, where `band` holds the
+// raw RGBA bytes of rows `start..` and is written in place. Reports progress in rows.
+pub(crate) fn render_bands(output: &mut RgbaImage, band_height: u32, progress: &Progress, mut render: impl FnMut(u32, &mut [u8])) {
+    let (width, height) = output.dimensions();
+    let row_len = width as usize * 4;
+    if row_len == 0 {
+        return;
+    }
+    let band_height = band_height.clamp(1, height.max(1));
+    for (index, band) in output.chunks_mut(row_len * band_height as usize).enumerate() {
+        if progress.is_cancelled() {
+            return;
+        }
+        let start = index as u32 * band_height;
+        progress.report(start as usize, height as usize);
+        render(start, band);
+    }
+    progress.report(height as usize, height as usize);
+}
@@ -0,0 +1,128 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+
+use crate::colorspace::{hsl_to_rgb, rgb_to_hsl};
+
+// Number of pre-blurred copies the graduated blur interpolates between
+const BLUR_LEVELS: usize = 6;
+
+#[derive(Clone, PartialEq)]
+pub struct TiltShiftSettings {
+    pub focus_position: f32,
+    pub band_height: f32,
+    pub transition: f32,
+    pub max_blur: f32,
+    pub saturation: f32,
+}
+
+impl Default for TiltShiftSettings {
+    fn default() -> Self {
+        Self {
+            focus_position: 0.55,
+            band_height: 0.15,
+            transition: 0.25,
+            max_blur: 8.0,
+            saturation: 30.0,
+        }
+    }
+}
+
+impl TiltShiftSettings {
+    // Top and bottom of the sharp band as fractions of the image height
+    pub fn band_edges(&self) -> (f32, f32) {
+        let half = self.band_height / 2.0;
+        (
+            (self.focus_position - half).clamp(0.0, 1.0),
+            (self.focus_position + half).clamp(0.0, 1.0),
+        )
+    }
+}
+
+/// Blurs everything outside a horizontal focus band for a miniature look.
+///
+/// Rows inside the band come back unchanged. Outside it the blur and the saturation
+/// boost both ramp up together over `transition`, so colours pop most where the
+/// miniature is most out of focus.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::tiltshiftconverter::{apply_tilt_shift, TiltShiftSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_tilt_shift(image, &TiltShiftSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_tilt_shift(image: DynamicImage, settings: &TiltShiftSettings) -> RgbaImage {
+    let rgba_img = image.to_rgba8();
+    let (width, height) = rgba_img.dimensions();
+    // image's blur panics on an empty buffer
+    if width == 0 || height == 0 {
+        return rgba_img;
+    }
+
+    let mut levels = Vec::with_capacity(BLUR_LEVELS + 1);
+    levels.push(rgba_img.clone());
+    if settings.max_blur > 0.0 {
+        for level in 1..=BLUR_LEVELS {
+            let sigma = settings.max_blur * level as f32 / BLUR_LEVELS as f32;
+            levels.push(image::imageops::blur(&rgba_img, sigma));
+        }
+    }
+
+    let (band_top, band_bottom) = settings.band_edges();
+    let band_top = band_top * height as f32;
+    let band_bottom = band_bottom * height as f32;
+    let transition = (settings.transition * height as f32).max(1.0);
+    let saturation = settings.saturation / 100.0;
+
+    let mut output = RgbaImage::new(width, height);
+
+    for y in 0..height {
+        let py = y as f32 + 0.5;
+        let distance = if py < band_top {
+            band_top - py
+        } else if py > band_bottom {
+            py - band_bottom
+        } else {
+            0.0
+        };
+
+        // Blur and boost ramp from zero at the band edge to the maximum over `transition`
+        let strength = (distance / transition).min(1.0);
+        let position = strength * (levels.len() - 1) as f32;
+        let lower = position.floor() as usize;
+        let upper = (lower + 1).min(levels.len() - 1);
+        let t = position - lower as f32;
+
+        for x in 0..width {
+            let a = levels[lower].get_pixel(x, y);
+            let pixel = if t > 0.0 {
+                let b = levels[upper].get_pixel(x, y);
+                let mix = |i: usize| (a[i] as f32 + (b[i] as f32 - a[i] as f32) * t).round() as u8;
+                Rgba([mix(0), mix(1), mix(2), mix(3)])
+            } else {
+                *a
+            };
+
+            output.put_pixel(x, y, boost_saturation(pixel, 1.0 + saturation * strength));
+        }
+    }
+
+    output
+}
+
+#[inline]
+fn boost_saturation(pixel: Rgba<u8>, factor: f32) -> Rgba<u8> {
+    if factor == 1.0 {
+        return pixel;
+    }
+    let (h, s, l) = rgb_to_hsl(pixel[0] as f32 / 255.0, pixel[1] as f32 / 255.0, pixel[2] as f32 / 255.0);
+    let (r, g, b) = hsl_to_rgb(h, (s * factor).clamp(0.0, 1.0), l);
+    Rgba([
+        (r * 255.0).round().clamp(0.0, 255.0) as u8,
+        (g * 255.0).round().clamp(0.0, 255.0) as u8,
+        (b * 255.0).round().clamp(0.0, 255.0) as u8,
+        pixel[3],
+    ])
+}
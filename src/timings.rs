@@ -0,0 +1,36 @@
+use std::time::{Duration, Instant};
+
+// Wall-clock time of each stage of one operation (load, filter run, preview upload).
+// Cheap enough to record unconditionally; read by the performance overlay and each
+// stage is also logged at debug level.
+#[derive(Clone, Default)]
+pub struct Timings {
+    pub operation: String,
+    pub stages: Vec<(&'static str, Duration)>,
+}
+
+impl Timings {
+    pub fn new(operation: &str) -> Self {
+        Self {
+            operation: operation.to_string(),
+            stages: Vec::new(),
+        }
+    }
+
+    pub fn time<T>(&mut self, stage: &'static str, f: impl FnOnce() -> T) -> T {
+        let _span = tracing::debug_span!("stage", operation = %self.operation, stage).entered();
+        let start = Instant::now();
+        let result = f();
+        self.record(stage, start.elapsed());
+        result
+    }
+
+    pub fn record(&mut self, stage: &'static str, duration: Duration) {
+        tracing::debug!(operation = %self.operation, stage, ms = duration.as_secs_f64() * 1000.0, "stage finished");
+        self.stages.push((stage, duration));
+    }
+
+    pub fn total(&self) -> Duration {
+        self.stages.iter().map(|(_, duration)| *duration).sum()
+    }
+}
@@ -0,0 +1,179 @@
+use std::sync::atomic::AtomicUsize;
+
+use image::{DynamicImage, GrayImage, Luma, RgbaImage, Rgba};
+use rayon::prelude::*;
+
+use crate::imageutils::{sobel_magnitude, value_noise};
+use crate::progress::{self, Progress, RowProgress};
+
+// Larger inputs are smoothed at this size and scaled back up before the paper texture
+const MAX_WORKING_SIZE: u32 = 1600;
+const BILATERAL_RADIUS: i32 = 3;
+const BILATERAL_SPATIAL_SIGMA: f32 = 2.0;
+const BILATERAL_RANGE_SIGMA: f32 = 28.0;
+
+#[derive(Clone, PartialEq)]
+pub struct WatercolorSettings {
+    pub smoothing: u32,
+    pub bleed: f32,
+    pub texture: f32,
+    pub edge_darkening: f32,
+    pub seed: u64,
+}
+
+impl Default for WatercolorSettings {
+    fn default() -> Self {
+        Self {
+            smoothing: 3,
+            bleed: 40.0,
+            texture: 50.0,
+            edge_darkening: 50.0,
+            seed: 17,
+        }
+    }
+}
+
+/// Paints `image` as a watercolour wash, reporting every smoothed and textured row to
+/// `progress`.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::watercolorconverter::{apply_watercolor, WatercolorSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_watercolor(image, &WatercolorSettings::default(), None);
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_watercolor(image: DynamicImage, settings: &WatercolorSettings, progress: Option<&Progress>) -> RgbaImage {
+    let progress = progress::or_none(progress);
+    let (orig_width, orig_height) = (image.width(), image.height());
+    if orig_width == 0 || orig_height == 0 {
+        return image.to_rgba8();
+    }
+    let working = if orig_width.max(orig_height) > MAX_WORKING_SIZE {
+        image.resize(MAX_WORKING_SIZE, MAX_WORKING_SIZE, image::imageops::FilterType::Triangle)
+    } else {
+        image
+    };
+
+    let rgba_img = working.to_rgba8();
+    let (width, height) = rgba_img.dimensions();
+    let rows = RowProgress {
+        progress,
+        done: AtomicUsize::new(0),
+        total: settings.smoothing as usize * height as usize + orig_height as usize,
+    };
+
+    let mut colors: Vec<[f32; 3]> = rgba_img.pixels().map(|p| [p[0] as f32, p[1] as f32, p[2] as f32]).collect();
+    for _ in 0..settings.smoothing {
+        if progress.is_cancelled() {
+            break;
+        }
+        colors = bilateral_pass(&colors, width, height, &rows);
+    }
+
+    let mut smoothed = RgbaImage::from_fn(width, height, |x, y| {
+        let c = colors[(y * width + x) as usize];
+        Rgba([c[0].round() as u8, c[1].round() as u8, c[2].round() as u8, rgba_img.get_pixel(x, y)[3]])
+    });
+
+    // Pigment bleeds across edges by mixing in a blurred copy
+    let bleed = settings.bleed / 100.0;
+    if bleed > 0.0 {
+        let blurred = image::imageops::blur(&smoothed, 1.0 + bleed * 5.0);
+        let mix = bleed * 0.6;
+        for (pixel, soft) in smoothed.pixels_mut().zip(blurred.pixels()) {
+            for c in 0..3 {
+                pixel[c] = (pixel[c] as f32 + (soft[c] as f32 - pixel[c] as f32) * mix).round() as u8;
+            }
+        }
+    }
+
+    // Pigment pools along edges as the wash dries
+    let darkening = settings.edge_darkening / 100.0;
+    if darkening > 0.0 {
+        let gray = GrayImage::from_fn(width, height, |x, y| {
+            let c = colors[(y * width + x) as usize];
+            Luma([(0.299 * c[0] + 0.587 * c[1] + 0.114 * c[2]).round() as u8])
+        });
+        let edges = sobel_magnitude(&gray);
+        for (pixel, edge) in smoothed.pixels_mut().zip(edges.iter()) {
+            let factor = 1.0 - darkening * 0.6 * (edge / 96.0).min(1.0);
+            for c in 0..3 {
+                pixel[c] = (pixel[c] as f32 * factor).round() as u8;
+            }
+        }
+    }
+
+    let mut output = if (width, height) != (orig_width, orig_height) {
+        image::imageops::resize(&smoothed, orig_width, orig_height, image::imageops::FilterType::Triangle)
+    } else {
+        smoothed
+    };
+
+    // Paper texture is added at full resolution so the grain stays crisp
+    let texture = settings.texture / 100.0;
+    let fine_seed = settings.seed.wrapping_add(74);
+    let row_len = orig_width as usize * 4;
+    output.par_chunks_mut(row_len).enumerate().for_each(|(y, row)| {
+        if texture > 0.0 && !progress.is_cancelled() {
+            for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                let (fx, fy) = (x as f32, y as f32);
+                let grain = 0.6 * value_noise(fx, fy, 6.0, settings.seed) + 0.4 * value_noise(fx, fy, 1.5, fine_seed);
+                let factor = 1.0 + texture * 0.3 * (grain - 0.5);
+                for value in pixel.iter_mut().take(3) {
+                    *value = (*value as f32 * factor).round().clamp(0.0, 255.0) as u8;
+                }
+            }
+        }
+        rows.tick();
+    });
+
+    output
+}
+
+// One iteration of an edge-preserving bilateral filter, rows processed in parallel
+fn bilateral_pass(colors: &[[f32; 3]], width: u32, height: u32, rows: &RowProgress) -> Vec<[f32; 3]> {
+    let (w, h) = (width as i32, height as i32);
+    let r = BILATERAL_RADIUS;
+    let spatial: Vec<f32> = (-r..=r)
+        .flat_map(|dy| (-r..=r).map(move |dx| (dx, dy)))
+        .map(|(dx, dy)| (-((dx * dx + dy * dy) as f32) / (2.0 * BILATERAL_SPATIAL_SIGMA * BILATERAL_SPATIAL_SIGMA)).exp())
+        .collect();
+    let range_denominator = 2.0 * BILATERAL_RANGE_SIGMA * BILATERAL_RANGE_SIGMA;
+
+    let mut output = vec![[0.0f32; 3]; colors.len()];
+    output.par_chunks_mut(width as usize).enumerate().for_each(|(y, row)| {
+        if rows.progress.is_cancelled() {
+            return;
+        }
+        let y = y as i32;
+        for (x, out) in row.iter_mut().enumerate() {
+            let x = x as i32;
+            let center = colors[(y * w + x) as usize];
+            let mut sum = [0.0f32; 3];
+            let mut total = 0.0f32;
+            let mut k = 0;
+            for dy in -r..=r {
+                let sy = (y + dy).clamp(0, h - 1);
+                for dx in -r..=r {
+                    let sx = (x + dx).clamp(0, w - 1);
+                    let sample = colors[(sy * w + sx) as usize];
+                    let d2 = (sample[0] - center[0]).powi(2) + (sample[1] - center[1]).powi(2) + (sample[2] - center[2]).powi(2);
+                    let weight = spatial[k] * (-d2 / range_denominator).exp();
+                    sum[0] += sample[0] * weight;
+                    sum[1] += sample[1] * weight;
+                    sum[2] += sample[2] * weight;
+                    total += weight;
+                    k += 1;
+                }
+            }
+            *out = [sum[0] / total, sum[1] / total, sum[2] / total];
+        }
+        rows.tick();
+    });
+
+    output
+}
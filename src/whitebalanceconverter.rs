@@ -0,0 +1,139 @@
+use image::{DynamicImage, RgbaImage, Rgba};
+
+use crate::colorspace::{linear_to_srgb, srgb_to_linear};
+
+pub type Matrix3 = [[f32; 3]; 3];
+
+// Linear sRGB (D65) <-> CIE XYZ
+const RGB_TO_XYZ: Matrix3 = [
+    [0.4124564, 0.3575761, 0.1804375],
+    [0.2126729, 0.7151522, 0.0721750],
+    [0.0193339, 0.119192, 0.9503041],
+];
+const XYZ_TO_RGB: Matrix3 = [
+    [3.2404542, -1.5371385, -0.4985314],
+    [-0.969266, 1.8760108, 0.0415560],
+    [0.0556434, -0.2040259, 1.0572252],
+];
+
+// Bradford cone response matrix and its inverse
+const BRADFORD: Matrix3 = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+const BRADFORD_INV: Matrix3 = [
+    [0.9869929, -0.1470543, 0.1599627],
+    [0.4323053, 0.5183603, 0.0492912],
+    [-0.0085287, 0.0400428, 0.9684867],
+];
+
+// How far the temperature/tint sliders push the assumed illuminant at +-100
+const SHIFT_RANGE: f32 = 0.35;
+
+#[derive(Clone, PartialEq)]
+pub struct WhiteBalanceSettings {
+    pub temperature: f32,
+    pub tint: f32,
+    pub picked_white: Option<[u8; 3]>,
+}
+
+impl Default for WhiteBalanceSettings {
+    fn default() -> Self {
+        Self {
+            temperature: 0.0,
+            tint: 0.0,
+            picked_white: None,
+        }
+    }
+}
+
+#[inline]
+fn mul_vec(m: &Matrix3, v: [f32; 3]) -> [f32; 3] {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+        m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+        m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+    ]
+}
+
+fn mul_mat(a: &Matrix3, b: &Matrix3) -> Matrix3 {
+    let mut out = [[0.0; 3]; 3];
+    for (i, row) in out.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    out
+}
+
+// The illuminant (in linear RGB) that the correction should map to neutral gray
+pub fn source_white(settings: &WhiteBalanceSettings) -> [f32; 3] {
+    let base = match settings.picked_white {
+        Some([r, g, b]) => [
+            srgb_to_linear(r as f32 / 255.0).max(1e-4),
+            srgb_to_linear(g as f32 / 255.0).max(1e-4),
+            srgb_to_linear(b as f32 / 255.0).max(1e-4),
+        ],
+        None => [1.0, 1.0, 1.0],
+    };
+
+    // A positive temperature assumes a bluer light, so correcting it warms the image;
+    // a positive tint assumes a greener light, so correcting it pushes towards magenta
+    let t = settings.temperature / 100.0 * SHIFT_RANGE;
+    let g = settings.tint / 100.0 * SHIFT_RANGE;
+    [base[0] * (1.0 - t), base[1] * (1.0 + g), base[2] * (1.0 + t)]
+}
+
+// Bradford chromatic adaptation from `white` to D65, expressed in linear RGB.
+// The cone responses are scaled so `white` lands on a gray of the same luminance.
+pub fn adaptation_matrix(white: [f32; 3]) -> Matrix3 {
+    let src_xyz = mul_vec(&RGB_TO_XYZ, white);
+    let dst_xyz = mul_vec(&RGB_TO_XYZ, [src_xyz[1]; 3]);
+
+    let src_lms = mul_vec(&BRADFORD, src_xyz);
+    let dst_lms = mul_vec(&BRADFORD, dst_xyz);
+
+    let mut scale = [[0.0; 3]; 3];
+    for i in 0..3 {
+        scale[i][i] = dst_lms[i] / src_lms[i].max(1e-6);
+    }
+
+    let xyz_adapt = mul_mat(&BRADFORD_INV, &mul_mat(&scale, &BRADFORD));
+    mul_mat(&XYZ_TO_RGB, &mul_mat(&xyz_adapt, &RGB_TO_XYZ))
+}
+
+/// Corrects white balance with a Bradford chromatic adaptation.
+///
+/// # Example
+///
+/// ```
+/// use image::{DynamicImage, RgbaImage};
+/// use pixforge::whitebalanceconverter::{apply_white_balance, WhiteBalanceSettings};
+///
+/// let image = DynamicImage::ImageRgba8(RgbaImage::new(32, 32));
+/// let output = apply_white_balance(image, &WhiteBalanceSettings::default());
+/// assert_eq!(output.dimensions(), (32, 32));
+/// ```
+pub fn apply_white_balance(image: DynamicImage, settings: &WhiteBalanceSettings) -> RgbaImage {
+    let mut rgba_img = image.to_rgba8();
+    if *settings == WhiteBalanceSettings::default() {
+        return rgba_img;
+    }
+
+    let matrix = adaptation_matrix(source_white(settings));
+    let to_linear: Vec<f32> = (0..256).map(|v| srgb_to_linear(v as f32 / 255.0)).collect();
+
+    for pixel in rgba_img.pixels_mut() {
+        let linear = [
+            to_linear[pixel[0] as usize],
+            to_linear[pixel[1] as usize],
+            to_linear[pixel[2] as usize],
+        ];
+        let corrected = mul_vec(&matrix, linear);
+        let encode = |v: f32| (linear_to_srgb(v.clamp(0.0, 1.0)) * 255.0).round() as u8;
+        *pixel = Rgba([encode(corrected[0]), encode(corrected[1]), encode(corrected[2]), pixel[3]]);
+    }
+
+    rgba_img
+}
@@ -0,0 +1,586 @@
+use image::{DynamicImage, GenericImageView, Luma};
+
+use pixforge::asciiconverter::{convert_image_to_ascii, to_ansi, AsciiCell, AsciiSettings, ColorMode, DetailLevel, DEFAULT_CHARSET};
+
+fn cell(ch: char, fg: [u8; 3]) -> AsciiCell {
+    AsciiCell { ch, fg, bg: None, transparent: false }
+}
+
+#[test]
+fn ansi_coalesces_runs_and_resets_each_line() {
+    let grid = vec![
+        vec![cell('#', [10, 20, 30]), cell('#', [10, 20, 30]), cell('*', [10, 20, 30]), cell('.', [200, 0, 0])],
+        vec![cell('.', [200, 0, 0]), cell('.', [200, 0, 0])],
+    ];
+    assert_eq!(
+        to_ansi(&grid, ColorMode::TrueColor),
+        "\x1b[38;2;10;20;30m##*\x1b[38;2;200;0;0m.\x1b[0m\n\x1b[38;2;200;0;0m..\x1b[0m\n"
+    );
+}
+
+#[test]
+fn ansi_emits_and_clears_backgrounds() {
+    let block = AsciiCell { ch: '▀', fg: [1, 2, 3], bg: Some([4, 5, 6]), transparent: false };
+    let grid = vec![vec![block, block, cell('x', [1, 2, 3])]];
+    assert_eq!(to_ansi(&grid, ColorMode::TrueColor), "\x1b[38;2;1;2;3;48;2;4;5;6m▀▀\x1b[38;2;1;2;3;49mx\x1b[0m\n");
+}
+
+#[test]
+fn ansi_of_empty_grid_is_empty() {
+    assert_eq!(to_ansi(&[], ColorMode::TrueColor), "");
+    assert_eq!(to_ansi(&[Vec::new()], ColorMode::TrueColor), "\x1b[0m\n");
+}
+
+#[test]
+fn ansi_uses_palette_codes() {
+    let grid = vec![vec![cell('#', [255, 0, 0]), AsciiCell { ch: '▀', fg: [0, 255, 0], bg: Some([0, 0, 255]), transparent: false }]];
+    assert_eq!(to_ansi(&grid, ColorMode::Xterm256), "\x1b[38;5;196m#\x1b[38;5;46;48;5;21m▀\x1b[0m\n");
+    assert_eq!(to_ansi(&grid, ColorMode::Ansi16), "\x1b[91m#\x1b[92;44m▀\x1b[0m\n");
+}
+
+#[test]
+fn primaries_map_to_their_palette_entries() {
+    let primaries = [[255, 0, 0], [0, 255, 0], [0, 0, 255]];
+    let indices = |mode: ColorMode| primaries.map(|color| mode.palette_index(color));
+    assert_eq!(indices(ColorMode::Xterm256), [Some(196), Some(46), Some(21)]);
+    assert_eq!(indices(ColorMode::Ansi16), [Some(9), Some(10), Some(4)]);
+}
+
+#[test]
+fn palette_modes_only_produce_palette_colors() {
+    for mode in [ColorMode::Xterm256, ColorMode::Ansi16] {
+        let image = DynamicImage::ImageRgb8(image::ImageBuffer::from_fn(64, 64, |x, y| image::Rgb([(x * 4) as u8, (y * 4) as u8, 128])));
+        let result = convert_image_to_ascii(image, &AsciiSettings { color_mode: mode, ..Default::default() }, (64, 64), None);
+        for cell in result.colored_ascii.iter().flatten() {
+            assert_eq!(mode.quantize(cell.fg), cell.fg);
+        }
+    }
+}
+
+// Two decimals, as the SVG writes its coordinates
+#[cfg(feature = "render")]
+fn svg_number(value: f32) -> f32 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn svg_places_runs_on_their_cells() {
+    use pixforge::asciiconverter::{render_cell_size, to_svg};
+
+    let grid = vec![
+        vec![cell('<', [255, 0, 0]), cell('&', [255, 0, 0]), cell(' ', [0, 0, 0]), cell('#', [0, 255, 0])],
+        vec![AsciiCell { ch: '▀', fg: [1, 2, 3], bg: Some([4, 5, 6]), transparent: false }; 4],
+    ];
+    let settings = AsciiSettings { font_size: 10.0, char_aspect: 0.5, ..Default::default() };
+    let (width, height) = render_cell_size(None, &settings).unwrap();
+    let svg = to_svg(&grid, &settings, None).unwrap();
+    let row = |y: f32, cells: f32, rect_height: f32, fill: &str| {
+        format!("<rect x=\"0\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>", svg_number(y), svg_number(cells * width), svg_number(rect_height), fill)
+    };
+    assert!(svg.contains(&format!("<text x=\"0 {}\" ", svg_number(width))));
+    assert!(svg.contains("fill=\"#ff0000\">&lt;&amp;</text>"));
+    assert!(svg.contains(&format!("<text x=\"{}\" ", svg_number(3.0 * width))));
+    assert!(svg.contains(&row(height, 4.0, height, "#040506")));
+    assert!(svg.contains(&row(height, 4.0, height / 2.0, "#010203")));
+    assert!(svg.trim_end().ends_with("</svg>"));
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn svg_has_the_size_of_the_rendered_image() {
+    use pixforge::asciiconverter::{render_ascii_to_image, to_svg};
+
+    let grid = vec![vec![cell('#', [200, 100, 50]); 7]; 3];
+    for settings in [
+        AsciiSettings { font_size: 12.0, ..Default::default() },
+        AsciiSettings { font_size: 9.0, char_spacing: 1.4, line_spacing: 0.8, char_aspect: 0.6, ..Default::default() },
+    ] {
+        let image = render_ascii_to_image(&grid, &settings, None, None).unwrap();
+        let svg = to_svg(&grid, &settings, None).unwrap();
+        let size = format!("width=\"{}\" height=\"{}\"", image.width(), image.height());
+        assert!(svg.starts_with(&format!("<svg xmlns=\"http://www.w3.org/2000/svg\" {}", size)), "{}", svg.lines().next().unwrap_or_default());
+    }
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn svg_grays_colors_when_colors_are_off() {
+    use pixforge::asciiconverter::to_svg;
+
+    let settings = AsciiSettings { use_colors: false, ..Default::default() };
+    let svg = to_svg(&[vec![cell('#', [30, 60, 90])]], &settings, None).unwrap();
+    assert!(svg.contains("fill=\"#3c3c3c\""));
+    assert!(!svg.contains("#1e3c5a"));
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn svg_background_matches_the_render() {
+    use pixforge::asciiconverter::to_svg;
+
+    let grid = vec![vec![cell('#', [230, 230, 230]); 3]];
+    let light = AsciiSettings { use_colors: false, background_color: [255, 255, 255], ..Default::default() };
+    let svg = to_svg(&grid, &light, None).unwrap();
+    assert!(svg.contains("<rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>"));
+    // Gray glyphs go dark on a light canvas, as they do in the PNG
+    assert!(svg.contains("fill=\"#191919\">###</text>"));
+
+    let duotone = AsciiSettings { color_mode: ColorMode::Duotone { dark: [20, 0, 40], light: [250, 200, 0] }, ..Default::default() };
+    assert!(to_svg(&grid, &duotone, None).unwrap().contains("<rect width=\"100%\" height=\"100%\" fill=\"#140028\"/>"));
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn gray_text_turns_dark_on_light_background() {
+    use pixforge::asciiconverter::render_ascii_to_image;
+
+    let grid = vec![vec![cell('#', [230, 230, 230]); 3]];
+    let image = render_ascii_to_image(&grid, &AsciiSettings { use_colors: false, background_color: [255, 255, 255], ..Default::default() }, None, None).unwrap();
+    assert_eq!(image.get_pixel(0, 0).0, [255, 255, 255, 255]);
+    let darkest = image.pixels().map(|p| p[0]).min().unwrap();
+    assert!(darkest < 64, "darkest glyph pixel was {}", darkest);
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn light_page_theme_keeps_dark_areas_dark() {
+    use pixforge::asciiconverter::{render_ascii_to_image, AsciiTheme};
+
+    let mut settings = AsciiSettings::default();
+    settings.apply_theme(AsciiTheme::LightPage);
+    let grid = vec![vec![cell('#', [20, 20, 20]); 3]];
+    let image = render_ascii_to_image(&grid, &AsciiSettings { use_colors: false, ..settings }, None, None).unwrap();
+    assert_eq!(image.get_pixel(0, 0).0, [255, 255, 255, 255]);
+    let darkest = image.pixels().map(|p| p[0]).min().unwrap();
+    assert!(darkest < 64, "darkest glyph pixel was {}", darkest);
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn filled_cells_cover_the_whole_image() {
+    use pixforge::asciiconverter::render_ascii_to_image;
+
+    // Fractional cell sizes, so rounding errors would leave black seams
+    let settings = AsciiSettings { font_size: 11.0, char_aspect: 0.55, fill_cells: true, ..Default::default() };
+    let grid = vec![vec![cell(' ', [200, 120, 40]); 7]; 5];
+    let image = render_ascii_to_image(&grid, &settings, None, None).unwrap();
+    assert!(image.pixels().all(|p| p.0 == [50, 30, 10, 255]));
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn overlay_draws_over_a_dimmed_source() {
+    use pixforge::asciiconverter::render_ascii_to_image;
+
+    let source = DynamicImage::ImageLuma8(image::GrayImage::from_pixel(8, 8, Luma([200])));
+    let settings = AsciiSettings { overlay_source: true, overlay_brightness: 0.5, ..Default::default() };
+    let grid = vec![vec![cell(' ', [255, 255, 255]); 3]; 2];
+    let image = render_ascii_to_image(&grid, &settings, None, Some(&source)).unwrap();
+    assert!(image.pixels().all(|p| p.0 == [100, 100, 100, 255]));
+
+    let plain = render_ascii_to_image(&grid, &AsciiSettings { overlay_source: false, ..settings }, None, Some(&source)).unwrap();
+    assert!(plain.pixels().all(|p| p.0 == [0, 0, 0, 255]));
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn ramp_glyphs_stay_inside_their_cells() {
+    use pixforge::asciiconverter::{render_ascii_to_image, render_cell_size};
+
+    for font_size in [7.0, 12.0, 30.0] {
+        let settings = AsciiSettings { font_size, ..Default::default() };
+        let (cell_width, cell_height) = render_cell_size(None, &settings).unwrap();
+        for ch in DEFAULT_CHARSET.chars() {
+            let mut grid = vec![vec![cell(' ', [255, 255, 255]); 3]; 3];
+            grid[1][1].ch = ch;
+            let image = render_ascii_to_image(&grid, &settings, None, None).unwrap();
+            // Pixels straddling a cell edge count as inside; only colour is compared, as
+            // the text drawing touches the alpha of pixels it leaves black
+            let (left, right) = (cell_width as u32, (2.0 * cell_width).ceil() as u32);
+            let (top, bottom) = (cell_height as u32, (2.0 * cell_height).ceil() as u32);
+            let outside = image.enumerate_pixels()
+                .filter(|&(x, y, _)| x < left || x >= right || y < top || y >= bottom)
+                .find(|(_, _, p)| p.0[..3] != [0, 0, 0]);
+            assert!(outside.is_none(), "{:?} at {}pt spills out of its cell at {:?}", ch, font_size, outside);
+        }
+    }
+}
+
+#[test]
+fn shade_blocks_use_five_levels() {
+    use pixforge::asciiconverter::{AsciiMode, SHADE_BLOCKS};
+
+    let gradient = DynamicImage::ImageLuma8(image::ImageBuffer::from_fn(256, 16, |x, _| Luma([x as u8])));
+    let settings = AsciiSettings { mode: AsciiMode::ShadeBlocks, brightness: 1.0, contrast: 1.0, gamma: 1.0, ..Default::default() };
+    let result = convert_image_to_ascii(gradient, &settings, (256, 16), None);
+    let used: std::collections::BTreeSet<char> = result.ascii_art.chars().filter(|&ch| ch != '\n').collect();
+    assert_eq!(used, SHADE_BLOCKS.chars().collect());
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn shade_blocks_render_as_cell_coverage() {
+    use pixforge::asciiconverter::render_ascii_to_image;
+
+    let settings = AsciiSettings { font_size: 11.0, ..Default::default() };
+    for (ch, level) in [('█', 200), ('▓', 150), ('▒', 100), ('░', 50)] {
+        let grid = vec![vec![cell(ch, [200, 200, 200]); 3]; 2];
+        let image = render_ascii_to_image(&grid, &settings, None, None).unwrap();
+        assert!(image.pixels().all(|p| p.0 == [level, level, level, 255]), "{:?}", ch);
+    }
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn transparent_areas_stay_empty_and_clear() {
+    use pixforge::asciiconverter::render_ascii_to_image;
+
+    // A white square on a fully transparent white background
+    let logo = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(64, 64, |x, _| image::Rgba([255, 255, 255, if x < 32 { 0 } else { 255 }])));
+    let settings = AsciiSettings { detail_level: pixforge::asciiconverter::DetailLevel::Custom(16), ..Default::default() };
+    let result = convert_image_to_ascii(logo.clone(), &settings, (64, 64), None);
+    let row = &result.colored_ascii[0];
+    assert!(row[..4].iter().all(|cell| cell.transparent && cell.ch == ' '));
+    assert!(row[12..].iter().all(|cell| !cell.transparent && cell.ch != ' '));
+
+    let image = render_ascii_to_image(&result.colored_ascii, &settings, None, None).unwrap();
+    assert_eq!(image.get_pixel(0, 0).0[3], 0);
+    assert_eq!(image.get_pixel(image.width() - 1, 0).0[3], 255);
+
+    let ignored = convert_image_to_ascii(logo, &AsciiSettings { alpha_threshold: 0, ..settings }, (64, 64), None);
+    assert!(ignored.colored_ascii[0].iter().all(|cell| !cell.transparent));
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn caption_gets_its_own_row() {
+    use pixforge::asciiconverter::{render_ascii_to_image, render_cell_size};
+
+    let grid = vec![vec![cell(' ', [255, 255, 255]); 20]; 3];
+    let settings = AsciiSettings { font_size: 12.0, ..Default::default() };
+    let plain = render_ascii_to_image(&grid, &settings, None, None).unwrap();
+    let amber = AsciiSettings { caption: "hello".to_string(), use_colors: false, tint: Some([255, 176, 0]), ..settings.clone() };
+    let captioned = render_ascii_to_image(&grid, &amber, None, None).unwrap();
+
+    let (_, cell_height) = render_cell_size(None, &settings).unwrap();
+    assert_eq!(captioned.width(), plain.width());
+    assert_eq!(captioned.height(), plain.height() + cell_height.ceil() as u32);
+    let ink: Vec<_> = captioned.enumerate_pixels().filter(|(_, _, p)| p.0[..3] != [0, 0, 0]).collect();
+    assert!(!ink.is_empty());
+    assert!(ink.iter().all(|&(_, y, _)| y >= plain.height()));
+    // Centred: the ink sits around the middle column
+    let (left, right) = (ink.iter().map(|p| p.0).min().unwrap(), ink.iter().map(|p| p.0).max().unwrap());
+    assert!((left + right).abs_diff(captioned.width()) <= 4);
+    // In the tint, anti-aliased against black
+    assert!(ink.iter().all(|(_, _, p)| p[2] == 0 && p[1] <= p[0]));
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn exact_size_exports_letterbox_at_the_largest_font() {
+    use pixforge::asciiconverter::{fit_font_size, render_ascii_to_image, render_ascii_to_size};
+
+    // A wide grid in a 16:9 frame: the width decides, the height is padded
+    let grid = vec![vec![cell('#', [255, 0, 0]); 120]; 20];
+    let settings = AsciiSettings { background_color: [0, 0, 64], caption: "wallpaper".to_string(), ..Default::default() };
+    let image = render_ascii_to_size(&grid, &settings, None, None, (1920, 1080)).unwrap();
+    assert_eq!(image.dimensions(), (1920, 1080));
+    assert_eq!(image.get_pixel(960, 0).0, [0, 0, 64, 255]);
+    assert_eq!(image.get_pixel(960, 1079).0, [0, 0, 64, 255]);
+
+    let font_size = fit_font_size(120, 20, (1920, 1080), None, &settings).unwrap();
+    let art = render_ascii_to_image(&grid, &AsciiSettings { font_size, ..settings.clone() }, None, None).unwrap();
+    assert!(art.width() <= 1920 && art.width() >= 1900);
+    let larger = render_ascii_to_image(&grid, &AsciiSettings { font_size: font_size * 1.01, ..settings.clone() }, None, None).unwrap();
+    assert!(larger.width() > 1920);
+
+    assert!(render_ascii_to_size(&grid, &settings, None, None, (40, 40)).is_err());
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn spacing_that_collapses_the_image_is_an_error() {
+    use pixforge::asciiconverter::render_ascii_to_image;
+
+    let grid = vec![vec![cell('#', [255, 255, 255]); 4]; 2];
+    let spaced = AsciiSettings { line_spacing: 2.0, char_spacing: 2.0, ..Default::default() };
+    let normal = render_ascii_to_image(&grid, &AsciiSettings::default(), None, None).unwrap();
+    let wide = render_ascii_to_image(&grid, &spaced, None, None).unwrap();
+    assert!(wide.width() >= normal.width() * 2 - 1 && wide.height() >= normal.height() * 2 - 1);
+
+    let collapsed = AsciiSettings { font_size: 0.0, line_spacing: -1.0, char_spacing: 0.0, ..Default::default() };
+    assert!(render_ascii_to_image(&grid, &collapsed, None, None).is_err());
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn fonts_that_do_not_parse_are_rejected() {
+    use pixforge::asciiconverter::{check_font, render_ascii_to_image};
+
+    assert!(check_font(b"not a font").is_err());
+    assert!(render_ascii_to_image(&[vec![cell('#', [255, 255, 255])]], &AsciiSettings::default(), Some(b"not a font"), None).is_err());
+}
+
+#[test]
+fn results_report_their_grid() {
+    let one_pixel = DynamicImage::ImageLuma8(image::ImageBuffer::from_pixel(1, 1, Luma([128])));
+    let result = convert_image_to_ascii(one_pixel, &AsciiSettings::default(), (1, 1), None);
+    assert!(!result.is_empty());
+    assert_eq!(result.char_height as usize, result.colored_ascii.len());
+    assert!(result.colored_ascii.iter().all(|row| row.len() == result.char_width as usize));
+
+    let empty = convert_image_to_ascii(DynamicImage::new_luma8(0, 0), &AsciiSettings::default(), (0, 0), None);
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn zero_saturation_matches_grayscale() {
+    let image = DynamicImage::ImageRgb8(image::ImageBuffer::from_fn(64, 64, |x, y| image::Rgb([(x * 4) as u8, (y * 4) as u8, 200])));
+    let convert = |settings: AsciiSettings| convert_image_to_ascii(image.clone(), &settings, (64, 64), None).colored_ascii;
+    let desaturated = convert(AsciiSettings { saturation: 0.0, ..Default::default() });
+    let grayscale = convert(AsciiSettings { use_colors: false, ..Default::default() });
+    assert_eq!(desaturated, grayscale);
+}
+
+#[test]
+fn recoloring_matches_a_fresh_conversion() {
+    use pixforge::asciiconverter::{recolor_ascii, AsciiMode};
+
+    let image = DynamicImage::ImageRgb8(image::ImageBuffer::from_fn(64, 64, |x, y| image::Rgb([(x * 4) as u8, (y * 4) as u8, 200])));
+    let convert = |settings: &AsciiSettings| convert_image_to_ascii(image.clone(), settings, (64, 64), None);
+    for mode in [AsciiMode::Ramp, AsciiMode::HalfBlock] {
+        let original = convert(&AsciiSettings { mode, ..Default::default() });
+        for recolored in [
+            AsciiSettings { mode, use_colors: false, ..Default::default() },
+            AsciiSettings { mode, use_colors: false, tint: Some([255, 176, 0]), ..Default::default() },
+            AsciiSettings { mode, saturation: 0.5, vibrance: 1.0, color_mode: ColorMode::Ansi16, ..Default::default() },
+            AsciiSettings { mode, posterize_levels: Some(3), ..Default::default() },
+        ] {
+            assert!(original.settings.differs_only_in_color(&recolored));
+            let mut grid = original.colored_ascii.clone();
+            recolor_ascii(&mut grid, &original.samples, &recolored);
+            assert_eq!(grid, convert(&recolored).colored_ascii);
+        }
+    }
+    assert!(!AsciiSettings::default().differs_only_in_color(&AsciiSettings { contrast: 2.0, ..Default::default() }));
+}
+
+#[test]
+fn colour_only_updates_skip_resampling() {
+    use std::cell::Cell;
+    use std::sync::Arc;
+
+    use pixforge::asciiconverter::update_conversion;
+
+    let image = DynamicImage::ImageRgb8(image::ImageBuffer::from_fn(64, 64, |x, y| image::Rgb([(x * 4) as u8, (y * 4) as u8, 200])));
+    let resamples = Cell::new(0);
+    let update = |previous: Option<Arc<_>>, settings: &AsciiSettings| {
+        update_conversion(previous, settings, || {
+            resamples.set(resamples.get() + 1);
+            convert_image_to_ascii(image.clone(), settings, (64, 64), None)
+        })
+    };
+
+    let original = update(None, &AsciiSettings::default());
+    assert_eq!(resamples.get(), 1);
+
+    let redrawn = update(Some(original.clone()), &AsciiSettings { font_size: 24.0, line_spacing: 1.5, ..Default::default() });
+    assert!(Arc::ptr_eq(&redrawn, &original));
+
+    let tinted = AsciiSettings { use_colors: false, tint: Some([255, 176, 0]), ..Default::default() };
+    let recolored = update(Some(original.clone()), &tinted);
+    assert_eq!(resamples.get(), 1);
+    assert!(recolored.settings == tinted);
+    assert_eq!(recolored.colored_ascii, convert_image_to_ascii(image.clone(), &tinted, (64, 64), None).colored_ascii);
+
+    update(Some(recolored), &AsciiSettings { contrast: 2.0, ..tinted });
+    assert_eq!(resamples.get(), 2);
+}
+
+#[test]
+fn posterize_snaps_each_channel_to_its_levels() {
+    let image = DynamicImage::ImageRgb8(image::ImageBuffer::from_fn(64, 64, |x, y| image::Rgb([(x * 4) as u8, (y * 4) as u8, 128])));
+    let convert = |posterize_levels| convert_image_to_ascii(image.clone(), &AsciiSettings { posterize_levels, ..Default::default() }, (64, 64), None);
+    let full = convert(None);
+    let posterized = convert(Some(3));
+    assert_eq!(full.ascii_art, posterized.ascii_art);
+    let colors: std::collections::HashSet<_> = posterized.colored_ascii.iter().flatten().map(|cell| cell.fg).collect();
+    assert!(colors.len() > 3);
+    assert!(colors.iter().flatten().all(|c| [0, 128, 255].contains(c)));
+}
+
+#[test]
+fn auto_brightness_contrast_stretches_the_tones() {
+    let ramp = |low: u32, high: u32| DynamicImage::ImageLuma8(image::ImageBuffer::from_fn(256, 64, |x, _| Luma([(low + x * (high - low) / 255) as u8])));
+    let auto = |image: &DynamicImage| {
+        let mut settings = AsciiSettings::default();
+        settings.auto_brightness_contrast(image);
+        (settings.brightness, settings.contrast)
+    };
+    // Already full range: close to neutral
+    let (brightness, contrast) = auto(&ramp(0, 255));
+    assert!((brightness - 1.0).abs() < 0.05 && (contrast - 1.0).abs() < 0.05);
+    // A dark image is brightened and the dull one spread out, within the slider ranges
+    let (brightness, _) = auto(&ramp(0, 100));
+    assert!(brightness > 1.5);
+    let (_, contrast) = auto(&ramp(100, 160));
+    assert_eq!(contrast, 2.0);
+    for flat in [0, 128, 255] {
+        let (brightness, contrast) = auto(&ramp(flat, flat));
+        assert!((0.1..=2.0).contains(&brightness) && (0.1..=2.0).contains(&contrast));
+    }
+}
+
+#[test]
+fn fast_sampling_keeps_thin_lines_wherever_they_fall() {
+    // A four-pixel line in 20-pixel cells: averaged, its cell looks the same at any offset
+    let settings = AsciiSettings { detail_level: DetailLevel::Custom(20), use_colors: false, ..Default::default() };
+    let mut cells = std::collections::HashSet::new();
+    for line in [20, 23, 29, 36] {
+        let image = DynamicImage::ImageLuma8(image::ImageBuffer::from_fn(400, 200, |x, _| Luma(if (line..line + 4).contains(&x) { [255] } else { [0] })));
+        let result = convert_image_to_ascii(image, &settings, (400, 200), None);
+        assert!(result.colored_ascii.iter().all(|row| row[1] == result.colored_ascii[0][1]));
+        cells.insert((result.colored_ascii[0][1].fg, result.ascii_art));
+    }
+    assert_eq!(cells.len(), 1);
+    assert!(cells.iter().all(|(fg, _)| fg[0] > 0));
+}
+
+#[test]
+fn sharpening_steepens_edges_and_handles_tiny_images() {
+    let image = DynamicImage::ImageLuma8(image::ImageBuffer::from_fn(64, 64, |x, _| Luma(if x < 32 { [80] } else { [170] })));
+    let convert = |image: &DynamicImage, sharpen| convert_image_to_ascii(image.clone(), &AsciiSettings { sharpen, ..Default::default() }, image.dimensions(), None);
+    let spread = |result: pixforge::asciiconverter::ConversionResult| {
+        let grays: Vec<u8> = result.colored_ascii[0].iter().map(|cell| cell.fg[0]).collect();
+        grays.iter().max().unwrap() - grays.iter().min().unwrap()
+    };
+    assert!(spread(convert(&image, 1.5)) > spread(convert(&image, 0.0)));
+
+    // A single row of a couple of samples, smaller than the kernel
+    let settings = AsciiSettings { sharpen: 2.0, fit_height: true, max_rows: 1, ..Default::default() };
+    for (width, height) in [(1, 1), (2, 1), (1, 3)] {
+        let tiny = DynamicImage::ImageLuma8(image::ImageBuffer::from_pixel(width, height, Luma([200])));
+        assert!(!convert_image_to_ascii(tiny, &settings, (width, height), None).is_empty());
+    }
+}
+
+#[test]
+fn texture_weight_separates_busy_and_flat_areas() {
+    use pixforge::asciiconverter::DetailLevel;
+
+    let flat = DynamicImage::ImageLuma8(image::ImageBuffer::from_pixel(256, 256, Luma([128])));
+    let busy = DynamicImage::ImageLuma8(image::ImageBuffer::from_fn(256, 256, |x, y| Luma([if (x / 8 + y / 8) % 2 == 0 { 0 } else { 255 }])));
+    let mean_position = |image: &DynamicImage, texture_weight| {
+        let settings = AsciiSettings { detail_level: DetailLevel::Custom(16), texture_weight, ..Default::default() };
+        let result = convert_image_to_ascii(image.clone(), &settings, (256, 256), None);
+        let positions: Vec<usize> = result.ascii_art.chars().filter_map(|ch| DEFAULT_CHARSET.find(ch)).collect();
+        positions.iter().sum::<usize>() as f32 / positions.len() as f32
+    };
+    assert!((mean_position(&busy, 0.0) - mean_position(&flat, 0.0)).abs() < 2.0);
+    // Lower positions are denser characters
+    assert!(mean_position(&busy, 1.0) + 10.0 < mean_position(&flat, 1.0));
+}
+
+#[test]
+fn tint_scales_grays() {
+    let image = DynamicImage::ImageLuma8(image::ImageBuffer::from_fn(64, 64, |x, _| Luma([(x * 4) as u8])));
+    let convert = |tint| convert_image_to_ascii(image.clone(), &AsciiSettings { use_colors: false, tint, ..Default::default() }, (64, 64), None).colored_ascii;
+    let neutral = convert(None);
+    let amber = convert(Some([255, 128, 0]));
+    for (gray, tinted) in neutral.iter().flatten().zip(amber.iter().flatten()) {
+        assert_eq!(tinted.fg, [gray.fg[0], (gray.fg[0] as u32 * 128 / 255) as u8, 0]);
+    }
+}
+
+#[test]
+fn duotone_blends_between_its_colors() {
+    let image = DynamicImage::ImageLuma8(image::ImageBuffer::from_fn(64, 64, |x, _| Luma([(x * 4) as u8])));
+    let (dark, light) = ([10, 20, 80], [250, 200, 40]);
+    let settings = AsciiSettings { color_mode: ColorMode::Duotone { dark, light }, brightness: 1.0, contrast: 1.0, ..Default::default() };
+    let result = convert_image_to_ascii(image, &settings, (64, 64), None);
+    let row = &result.colored_ascii[0];
+    assert_eq!(row[0].fg, dark);
+    let last = row[row.len() - 1].fg;
+    assert!(last.iter().zip(light).all(|(&a, b)| a.abs_diff(b) <= 8), "{:?} is not near {:?}", last, light);
+    assert_eq!(settings.render_background(), dark);
+}
+
+// A left-to-right gradient at neutral brightness and contrast
+fn convert_gradient(settings: AsciiSettings) -> String {
+    let image = DynamicImage::ImageLuma8(image::ImageBuffer::from_fn(256, 16, |x, _| Luma([x as u8])));
+    let settings = AsciiSettings { brightness: 1.0, contrast: 1.0, ..settings };
+    convert_image_to_ascii(image, &settings, (256, 16), None).ascii_art
+}
+
+// Mean position in the default ramp of the characters for the gradient
+fn mean_ramp_position(gamma: f32) -> f32 {
+    let ascii_art = convert_gradient(AsciiSettings { gamma, ..Default::default() });
+    let positions: Vec<usize> = ascii_art.chars().filter_map(|c| DEFAULT_CHARSET.find(c)).collect();
+    positions.iter().sum::<usize>() as f32 / positions.len() as f32
+}
+
+#[test]
+fn linear_gamma_keeps_midtones_brighter() {
+    let linear = mean_ramp_position(1.0);
+    let curved = mean_ramp_position(1.5);
+    // Bright samples map to the dense start of the ramp
+    assert!(linear + 5.0 < curved, "linear {} vs curved {}", linear, curved);
+}
+
+// Columns of the gradient whose characters are not all the same
+fn mixed_columns(dither: bool) -> usize {
+    let ascii_art = convert_gradient(AsciiSettings { dither, ..Default::default() });
+    let rows: Vec<Vec<char>> = ascii_art.lines().map(|line| line.chars().collect()).collect();
+    (0..rows[0].len()).filter(|&x| rows.iter().any(|row| row[x] != rows[0][x])).count()
+}
+
+#[test]
+fn dithering_interleaves_gradient_bands() {
+    let banded = mixed_columns(false);
+    let dithered = mixed_columns(true);
+    assert!(dithered > banded + 20, "{} mixed columns with dithering, {} without", dithered, banded);
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn calibrated_ramp_darkens_steadily_along_a_gradient() {
+    use pixforge::asciiconverter::glyph_densities;
+
+    let ascii_art = convert_gradient(AsciiSettings { calibrated_ramp: true, gamma: 1.0, ..Default::default() });
+    let first_row = ascii_art.lines().next().unwrap();
+    let densities = glyph_densities(first_row, None).unwrap();
+    // Brighter pixels get denser characters, so ink only grows to the right
+    for (x, pair) in densities.windows(2).enumerate() {
+        assert!(pair[0] <= pair[1], "column {} is denser than column {} in {:?}", x, x + 1, first_row);
+    }
+}
+
+#[cfg(feature = "render")]
+#[test]
+fn gif_export_keeps_frames_and_delays() {
+    use std::io::Cursor;
+
+    use image::codecs::gif::{GifDecoder, GifEncoder};
+    use image::{AnimationDecoder, Delay, Frame, RgbaImage};
+    use pixforge::asciiconverter::render_ascii_gif;
+
+    let delays = [100, 250, 40];
+    let mut source = Vec::new();
+    {
+        let mut encoder = GifEncoder::new(&mut source);
+        for (i, &ms) in delays.iter().enumerate() {
+            let frame = RgbaImage::from_fn(48, 32, |x, y| image::Rgba([(x * 5) as u8, (y * 8) as u8, (i * 100) as u8, 255]));
+            encoder.encode_frame(Frame::from_parts(frame, 0, 0, Delay::from_numer_denom_ms(ms, 1))).unwrap();
+        }
+    }
+
+    let mut output = Vec::new();
+    render_ascii_gif(Cursor::new(source), &mut output, &AsciiSettings::default(), None, None).unwrap();
+    let frames = GifDecoder::new(Cursor::new(output)).unwrap().into_frames().collect_frames().unwrap();
+    assert_eq!(frames.len(), delays.len());
+    for (frame, &ms) in frames.iter().zip(&delays) {
+        assert_eq!(frame.delay().numer_denom_ms(), (ms, 1));
+        assert_eq!(frame.buffer().dimensions(), frames[0].buffer().dimensions());
+    }
+}
@@ -0,0 +1,27 @@
+use pixforge::colorspace::{hsl_to_rgb, rgb_to_hsl};
+
+fn to_u8(v: f32) -> u8 {
+    (v * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+#[test]
+fn hsl_round_trips_every_rgb_color() {
+    for r in 0..=255u8 {
+        for g in 0..=255u8 {
+            for b in 0..=255u8 {
+                let (h, s, l) = rgb_to_hsl(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+                let (r2, g2, b2) = hsl_to_rgb(h, s, l);
+                assert_eq!([to_u8(r2), to_u8(g2), to_u8(b2)], [r, g, b], "via hsl({}, {}, {})", h, s, l);
+            }
+        }
+    }
+}
+
+#[test]
+fn hsl_of_primaries() {
+    assert_eq!(rgb_to_hsl(1.0, 0.0, 0.0), (0.0, 1.0, 0.5));
+    assert_eq!(rgb_to_hsl(0.0, 1.0, 0.0), (120.0, 1.0, 0.5));
+    assert_eq!(rgb_to_hsl(0.0, 0.0, 1.0), (240.0, 1.0, 0.5));
+    assert_eq!(rgb_to_hsl(0.5, 0.5, 0.5), (0.0, 0.0, 0.5));
+    assert_eq!(hsl_to_rgb(360.0, 1.0, 0.5), (1.0, 0.0, 0.0));
+}
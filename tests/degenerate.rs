@@ -0,0 +1,321 @@
+// Every converter must cope with empty, single-pixel and very thin images: no panics,
+// and an output the size of the input.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use pixforge::asciiconverter::{convert_image_to_ascii, AsciiSettings};
+use pixforge::crtconverter::{apply_crt, apply_crt_tiled, CrtSettings};
+use pixforge::ditherconverter::{apply_dither, apply_dither_tiled, DitherAlgorithm, DitherSettings};
+use pixforge::dotmatrixconverter::{apply_dot_matrix, DotMatrixSettings};
+use pixforge::drosteconverter::{apply_droste, DrosteSettings};
+use pixforge::engravingconverter::{apply_engraving, EngravingSettings};
+use pixforge::fisheyeconverter::{apply_fisheye, apply_fisheye_tiled, FisheyeSettings};
+use pixforge::hslconverter::{apply_hsl, HslSettings};
+use pixforge::infraredconverter::{apply_infrared, InfraredSettings};
+use pixforge::kaleidoscopeconverter::{apply_kaleidoscope, KaleidoscopeSettings};
+use pixforge::levelsconverter::{apply_levels, LevelsSettings};
+use pixforge::lomoconverter::{apply_lomo, LomoSettings};
+use pixforge::lutconverter::{apply_lut, LutSettings};
+use pixforge::mosaicconverter::{apply_mosaic, MosaicSettings};
+use pixforge::neonconverter::{apply_neon, NeonSettings};
+use pixforge::oldphotoconverter::{apply_old_photo, OldPhotoSettings};
+use pixforge::ortonconverter::{apply_orton, OrtonSettings};
+use pixforge::popartconverter::{apply_pop_art, PopArtSettings};
+use pixforge::retroconverter::{apply_retro, RetroSettings};
+use pixforge::sketchconverter::{apply_sketch, SketchSettings};
+use pixforge::solarizeconverter::{apply_solarize, SolarizeSettings};
+use pixforge::stainedglassconverter::{apply_stained_glass, StainedGlassSettings};
+use pixforge::tiltshiftconverter::{apply_tilt_shift, TiltShiftSettings};
+use pixforge::watercolorconverter::{apply_watercolor, WatercolorSettings};
+use pixforge::whitebalanceconverter::{apply_white_balance, WhiteBalanceSettings};
+
+type Converter = fn(DynamicImage) -> RgbaImage;
+
+const DITHER_ALGORITHMS: [DitherAlgorithm; 17] = [
+    DitherAlgorithm::FloydSteinberg,
+    DitherAlgorithm::Atkinson,
+    DitherAlgorithm::Ordered,
+    DitherAlgorithm::Threshold,
+    DitherAlgorithm::Scanline,
+    DitherAlgorithm::Pattern,
+    DitherAlgorithm::Random,
+    DitherAlgorithm::Halftone,
+    DitherAlgorithm::HalftoneCmyk,
+    DitherAlgorithm::Jarvis,
+    DitherAlgorithm::Stucki,
+    DitherAlgorithm::Burkes,
+    DitherAlgorithm::Sierra,
+    DitherAlgorithm::SierraTwoRow,
+    DitherAlgorithm::SierraLite,
+    DitherAlgorithm::BlueNoise,
+    DitherAlgorithm::YliluomaOrdered,
+];
+
+const CONVERTERS: [(&str, Converter); 24] = [
+    ("dither", |image| apply_dither(image, &DitherSettings::default(), None)),
+    ("dither_tiled", |image| apply_dither_tiled(image, &DitherSettings::default(), 2, None)),
+    ("dot_matrix", |image| apply_dot_matrix(image, &DotMatrixSettings::default())),
+    ("droste", |image| apply_droste(image, &DrosteSettings::default())),
+    ("engraving", |image| apply_engraving(image, &EngravingSettings::default())),
+    ("fisheye", |image| apply_fisheye(image, &FisheyeSettings::default(), None)),
+    ("fisheye_tiled", |image| apply_fisheye_tiled(image, &FisheyeSettings::default(), 2, None)),
+    ("hsl", |image| apply_hsl(image, &HslSettings::default())),
+    ("infrared", |image| apply_infrared(image, &InfraredSettings::default())),
+    ("kaleidoscope", |image| apply_kaleidoscope(image, &KaleidoscopeSettings::default())),
+    ("levels", |image| apply_levels(image, &LevelsSettings::default())),
+    ("lomo", |image| apply_lomo(image, &LomoSettings::default())),
+    ("lut", |image| apply_lut(image, &LutSettings::default())),
+    ("mosaic", |image| apply_mosaic(image, &MosaicSettings::default())),
+    ("neon", |image| apply_neon(image, &NeonSettings::default())),
+    ("old_photo", |image| apply_old_photo(image, &OldPhotoSettings::default())),
+    ("orton", |image| apply_orton(image, &OrtonSettings::default())),
+    ("pop_art", |image| apply_pop_art(image, &PopArtSettings::default())),
+    ("sketch", |image| apply_sketch(image, &SketchSettings::default())),
+    ("solarize", |image| apply_solarize(image, &SolarizeSettings::default())),
+    ("stained_glass", |image| apply_stained_glass(image, &StainedGlassSettings::default())),
+    ("tilt_shift", |image| apply_tilt_shift(image, &TiltShiftSettings::default())),
+    ("watercolor", |image| apply_watercolor(image, &WatercolorSettings::default(), None)),
+    ("white_balance", |image| apply_white_balance(image, &WhiteBalanceSettings::default())),
+];
+
+// These change the size on purpose (a bezel, a console resolution), so they only have
+// to keep empty images empty and non-empty ones non-empty
+const RESIZING_CONVERTERS: [(&str, Converter); 3] = [
+    ("crt", |image| apply_crt(image, &CrtSettings::default(), None)),
+    ("crt_tiled", |image| apply_crt_tiled(image, &CrtSettings::default(), 2, None)),
+    ("retro", |image| apply_retro(image, &RetroSettings::default())),
+];
+
+// Settings at the ends of the GUI slider ranges and beyond, including zero for
+// everything used as a divisor or a step
+const EDGE_SETTINGS: [(&str, Converter); 36] = [
+    ("crt zeros", |image| {
+        let settings = CrtSettings { scanline_density: 0.0, brightness_gain: 0.0, curvature: 0.0, bezel_size: 0.0, vignette_strength: 0.0, ..Default::default() };
+        apply_crt(image, &settings, None)
+    }),
+    ("crt maxed", |image| {
+        let settings = CrtSettings { scanline_density: 100.0, brightness_gain: 10.0, curvature: 5.0, bezel_size: 1.0, vignette_strength: 5.0, ..Default::default() };
+        apply_crt(image, &settings, None)
+    }),
+    ("dither zeros", |image| {
+        let settings = DitherSettings { color_levels: 0, threshold: 0.0, contrast: 0.0, midtones: 0.0, highlights: 0.0, luminance_threshold: 0.0, ..Default::default() };
+        apply_dither(image, &settings, None)
+    }),
+    ("dither maxed", |image| {
+        let settings = DitherSettings { color_levels: 255, threshold: 255.0, contrast: 10.0, midtones: 10.0, highlights: 10.0, blur: 50.0, ..Default::default() };
+        apply_dither(image, &settings, None)
+    }),
+    ("dot_matrix zeros", |image| {
+        let settings = DotMatrixSettings { pin_size: 0.0, band_height: 0, ink_darkness: 0.0, misregistration: 0.0, ..Default::default() };
+        apply_dot_matrix(image, &settings)
+    }),
+    ("dot_matrix maxed", |image| {
+        let settings = DotMatrixSettings { pin_size: 64.0, band_height: 1000, ink_darkness: 100.0, misregistration: 50.0, ..Default::default() };
+        apply_dot_matrix(image, &settings)
+    }),
+    ("droste zeros", |image| {
+        let settings = DrosteSettings { inner_radius: 0.0, outer_radius: 0.0, turns: 0, strength: 0.0, center_x: 0.0, center_y: 0.0 };
+        apply_droste(image, &settings)
+    }),
+    ("droste inverted", |image| {
+        let settings = DrosteSettings { inner_radius: 1.5, outer_radius: 0.05, turns: -3, strength: 1.0, center_x: 1.0, center_y: 1.0 };
+        apply_droste(image, &settings)
+    }),
+    ("engraving zeros", |image| {
+        let settings = EngravingSettings { line_spacing: 0.0, angle: 0.0, cross_angle: 0.0, layers: 0, min_thickness: 0.0, max_thickness: 0.0, follow_flow: true };
+        apply_engraving(image, &settings)
+    }),
+    ("engraving maxed", |image| {
+        let settings = EngravingSettings { line_spacing: 500.0, angle: 180.0, cross_angle: 90.0, layers: 10, min_thickness: 1.0, max_thickness: 1.0, follow_flow: true };
+        apply_engraving(image, &settings)
+    }),
+    ("fisheye zeros", |image| {
+        let settings = FisheyeSettings { strength: 0.0, zoom: 0.0, center_x: 0.0, center_y: 0.0 };
+        apply_fisheye(image, &settings, None)
+    }),
+    ("fisheye maxed", |image| {
+        let settings = FisheyeSettings { strength: -1.0, zoom: 100.0, center_x: 1.0, center_y: 1.0 };
+        apply_fisheye(image, &settings, None)
+    }),
+    ("hsl extremes", |image| {
+        let settings = HslSettings { hue: 180.0, saturation: -100.0, lightness: 100.0, ..Default::default() };
+        apply_hsl(image, &settings)
+    }),
+    ("infrared maxed", |image| {
+        let settings = InfraredSettings { glow: 100.0, grain: 100.0, custom_mixer: [[100.0; 3]; 3], ..Default::default() };
+        apply_infrared(image, &settings)
+    }),
+    ("kaleidoscope zeros", |image| {
+        let settings = KaleidoscopeSettings { segments: 0, rotation: 0.0, zoom: 0.0, mirrored: true, center_x: 0.0, center_y: 0.0 };
+        apply_kaleidoscope(image, &settings)
+    }),
+    ("kaleidoscope maxed", |image| {
+        let settings = KaleidoscopeSettings { segments: 1000, rotation: 360.0, zoom: 100.0, mirrored: false, center_x: 1.0, center_y: 1.0 };
+        apply_kaleidoscope(image, &settings)
+    }),
+    ("levels zeros", |image| {
+        let settings = LevelsSettings { input_black: 0.0, input_white: 0.0, gamma: 0.0, output_black: 0.0, output_white: 0.0, curve: Vec::new(), ..Default::default() };
+        apply_levels(image, &settings)
+    }),
+    ("levels crossed", |image| {
+        let settings = LevelsSettings { input_black: 255.0, input_white: 0.0, gamma: 100.0, output_black: 255.0, output_white: 0.0, curve: vec![[0.5, 0.5]], ..Default::default() };
+        apply_levels(image, &settings)
+    }),
+    ("lomo maxed", |image| {
+        let settings = LomoSettings { vignette: 100.0, cross_process: 100.0, saturation: 100.0, soft_focus: 100.0, light_leak: 100.0, ..Default::default() };
+        apply_lomo(image, &settings)
+    }),
+    ("lut zeros", |image| apply_lut(image, &LutSettings { strength: 0.0, ..Default::default() })),
+    ("mosaic zeros", |image| {
+        let settings = MosaicSettings { tile_size: 0, grout_width: 0.0, variation: 0.0, ..Default::default() };
+        apply_mosaic(image, &settings)
+    }),
+    ("mosaic maxed", |image| {
+        let settings = MosaicSettings { tile_size: 1000, grout_width: 100.0, variation: 100.0, ..Default::default() };
+        apply_mosaic(image, &settings)
+    }),
+    ("neon zeros", |image| {
+        let settings = NeonSettings { edge_threshold: 0.0, glow_radius: 0.0, glow_intensity: 0.0, background_dim: 0.0, ..Default::default() };
+        apply_neon(image, &settings)
+    }),
+    ("neon maxed", |image| {
+        let settings = NeonSettings { edge_threshold: 1000.0, glow_radius: 30.0, glow_intensity: 400.0, background_dim: 100.0, ..Default::default() };
+        apply_neon(image, &settings)
+    }),
+    ("old_photo maxed", |image| apply_old_photo(image, &OldPhotoSettings { age: 100.0, ..Default::default() })),
+    ("orton maxed", |image| {
+        let settings = OrtonSettings { blur_radius: 50.0, glow_strength: 100.0, highlight_protection: 100.0 };
+        apply_orton(image, &settings)
+    }),
+    ("pop_art zeros", |image| {
+        let settings = PopArtSettings { dot_size: 0.0, dot_angle: 0.0, outline_thickness: 0, edge_threshold: 0.0, ..Default::default() };
+        apply_pop_art(image, &settings)
+    }),
+    ("pop_art maxed", |image| {
+        let settings = PopArtSettings { dot_size: 500.0, dot_angle: 90.0, outline_thickness: 50, edge_threshold: 1000.0, ..Default::default() };
+        apply_pop_art(image, &settings)
+    }),
+    ("retro zeros", |image| {
+        let settings = RetroSettings { width: 0, height: 0, max_colors: 0, dither_strength: 0.0, scale: 0, ..Default::default() };
+        apply_retro(image, &settings)
+    }),
+    ("sketch zeros", |image| {
+        let settings = SketchSettings { blur_radius: 0.0, darkness: 0.0, edge_threshold: 0.0, ..Default::default() };
+        apply_sketch(image, &settings)
+    }),
+    ("solarize zeros", |image| {
+        let settings = SolarizeSettings { threshold: 0.0, rolloff: 0.0, double: true, secondary_threshold: 0.0, ..Default::default() };
+        apply_solarize(image, &settings)
+    }),
+    ("stained_glass zeros", |image| {
+        let settings = StainedGlassSettings { cell_count: 0, border_width: 0.0, saturation_boost: 0.0, ..Default::default() };
+        apply_stained_glass(image, &settings)
+    }),
+    ("stained_glass maxed", |image| {
+        let settings = StainedGlassSettings { cell_count: 4000, border_width: 16.0, saturation_boost: 100.0, ..Default::default() };
+        apply_stained_glass(image, &settings)
+    }),
+    ("tilt_shift zeros", |image| {
+        let settings = TiltShiftSettings { focus_position: 0.0, band_height: 0.0, transition: 0.0, max_blur: 0.0, saturation: 0.0 };
+        apply_tilt_shift(image, &settings)
+    }),
+    ("watercolor zeros", |image| {
+        let settings = WatercolorSettings { smoothing: 0, bleed: 0.0, texture: 0.0, edge_darkening: 0.0, ..Default::default() };
+        apply_watercolor(image, &settings, None)
+    }),
+    ("white_balance extremes", |image| {
+        let settings = WhiteBalanceSettings { temperature: -100.0, tint: 100.0, picked_white: Some([0, 0, 0]) };
+        apply_white_balance(image, &settings)
+    }),
+];
+
+fn test_image(width: u32, height: u32) -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+        Rgba([(x * 60) as u8, (y * 60) as u8, ((x + y) * 30) as u8, 255])
+    }))
+}
+
+fn sizes() -> impl Iterator<Item = (u32, u32)> {
+    let small = (0..=4).flat_map(|width| (0..=4).map(move |height| (width, height)));
+    small.chain([(1, 300), (300, 1), (2, 500), (500, 2)])
+}
+
+// Runs `convert` on an image of each size and returns the cases that panicked or gave
+// an output `valid` rejects, so one run lists every failure
+fn failures(
+    name: &str,
+    convert: impl Fn(DynamicImage) -> RgbaImage,
+    valid: impl Fn((u32, u32), (u32, u32)) -> bool,
+) -> Vec<String> {
+    let mut failed = Vec::new();
+    for (width, height) in sizes() {
+        match catch_unwind(AssertUnwindSafe(|| convert(test_image(width, height)))) {
+            Ok(output) if valid((width, height), output.dimensions()) => {}
+            Ok(output) => failed.push(format!("{} at {}x{} gave {:?}", name, width, height, output.dimensions())),
+            Err(_) => failed.push(format!("{} at {}x{} panicked", name, width, height)),
+        }
+    }
+    failed
+}
+
+fn same_size(input: (u32, u32), output: (u32, u32)) -> bool {
+    input == output
+}
+
+#[test]
+fn converters_survive_tiny_images() {
+    let failed: Vec<_> = CONVERTERS.iter().flat_map(|&(name, convert)| failures(name, convert, same_size)).collect();
+    assert!(failed.is_empty(), "{}", failed.join("\n"));
+}
+
+#[test]
+fn resizing_converters_survive_tiny_images() {
+    let failed: Vec<_> = RESIZING_CONVERTERS
+        .iter()
+        .flat_map(|&(name, convert)| {
+            failures(name, convert, |(width, height), (out_width, out_height)| {
+                (width * height == 0) == (out_width * out_height == 0)
+            })
+        })
+        .collect();
+    assert!(failed.is_empty(), "{}", failed.join("\n"));
+}
+
+#[test]
+fn every_dither_algorithm_survives_tiny_images() {
+    let failed: Vec<_> = DITHER_ALGORITHMS
+        .into_iter()
+        .flat_map(|algorithm| {
+            let name = algorithm.name().to_string();
+            let settings = DitherSettings { algorithm, ..Default::default() };
+            failures(&name, |image| apply_dither(image, &settings, None), same_size)
+        })
+        .collect();
+    assert!(failed.is_empty(), "{}", failed.join("\n"));
+}
+
+#[test]
+fn edge_settings_do_not_panic() {
+    let failed: Vec<_> = EDGE_SETTINGS
+        .iter()
+        .flat_map(|&(name, convert)| {
+            failures(name, convert, |(width, height), (out_width, out_height)| {
+                (width * height == 0) == (out_width * out_height == 0)
+            })
+        })
+        .collect();
+    assert!(failed.is_empty(), "{}", failed.join("\n"));
+}
+
+#[test]
+fn ascii_survives_tiny_images() {
+    for (width, height) in sizes() {
+        let case = format!("ascii at {}x{}", width, height);
+        let result = convert_image_to_ascii(test_image(width, height), &AsciiSettings::default(), (width, height), None);
+        let rows = result.ascii_art.lines().count();
+        assert_eq!(rows, result.colored_ascii.len(), "{} text and grid disagree", case);
+        assert!(result.colored_ascii.iter().all(|row| row.len() == result.colored_ascii[0].len()), "{} is ragged", case);
+    }
+}
@@ -0,0 +1,65 @@
+// Every converter with a random element must give byte-identical output for the same
+// input and seed, however rayon schedules the work.
+
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use pixforge::ditherconverter::{apply_dither, DitherAlgorithm, DitherSettings};
+use pixforge::dotmatrixconverter::{apply_dot_matrix, DotMatrixSettings};
+use pixforge::infraredconverter::{apply_infrared, InfraredSettings};
+use pixforge::lomoconverter::{apply_lomo, LomoSettings};
+use pixforge::mosaicconverter::{apply_mosaic, MosaicSettings};
+use pixforge::oldphotoconverter::{apply_old_photo, OldPhotoSettings};
+use pixforge::stainedglassconverter::{apply_stained_glass, StainedGlassSettings};
+use pixforge::watercolorconverter::{apply_watercolor, WatercolorSettings};
+
+type Converter = Box<dyn Fn(DynamicImage, u64) -> RgbaImage + Sync>;
+
+fn seeded_converters() -> Vec<(&'static str, Converter)> {
+    vec![
+        ("dither", Box::new(|image, seed| {
+            apply_dither(image, &DitherSettings { algorithm: DitherAlgorithm::Random, seed, ..Default::default() }, None)
+        })),
+        ("dot_matrix", Box::new(|image, seed| apply_dot_matrix(image, &DotMatrixSettings { seed, ..Default::default() }))),
+        ("infrared", Box::new(|image, seed| apply_infrared(image, &InfraredSettings { grain: 60.0, seed, ..Default::default() }))),
+        ("lomo", Box::new(|image, seed| apply_lomo(image, &LomoSettings { light_leak: 70.0, seed, ..Default::default() }))),
+        ("mosaic", Box::new(|image, seed| apply_mosaic(image, &MosaicSettings { seed, ..Default::default() }))),
+        ("old_photo", Box::new(|image, seed| apply_old_photo(image, &OldPhotoSettings { seed, ..Default::default() }))),
+        ("stained_glass", Box::new(|image, seed| apply_stained_glass(image, &StainedGlassSettings { seed, ..Default::default() }))),
+        ("watercolor", Box::new(|image, seed| {
+            apply_watercolor(image, &WatercolorSettings { seed, ..Default::default() }, None)
+        })),
+    ]
+}
+
+fn test_image() -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(96, 64, |x, y| {
+        Rgba([(x * 255 / 95) as u8, (y * 255 / 63) as u8, ((x + y) * 3 % 256) as u8, 255])
+    }))
+}
+
+#[test]
+fn seeded_converters_are_deterministic() {
+    let image = test_image();
+    let single_thread = rayon::ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    let three_threads = rayon::ThreadPoolBuilder::new().num_threads(3).build().unwrap();
+
+    for (name, convert) in seeded_converters() {
+        for seed in [1, 0xdead_beef_0000_0042] {
+            let first = convert(image.clone(), seed);
+            let second = convert(image.clone(), seed);
+            let single = single_thread.install(|| convert(image.clone(), seed));
+            let several = three_threads.install(|| convert(image.clone(), seed));
+            assert!(first == second, "{} with seed {} differs between runs", name, seed);
+            assert!(first == single, "{} with seed {} differs on one thread", name, seed);
+            assert!(first == several, "{} with seed {} differs on three threads", name, seed);
+        }
+    }
+}
+
+#[test]
+fn seed_changes_output() {
+    let image = test_image();
+    for (name, convert) in seeded_converters() {
+        assert!(convert(image.clone(), 1) != convert(image.clone(), 2), "{} ignores its seed", name);
+    }
+}
@@ -0,0 +1,552 @@
+use image::{DynamicImage, GrayImage, Luma, Rgb, RgbImage};
+
+use pixforge::ditherconverter::{
+    apply_dither, apply_dither_tiled, check_dither_settings, check_palette, check_pattern, dither_prepared, encode_indexed_png, extract_palette, prepare_dither, parse_hex_palette, to_hex_palette, DitherAlgorithm,
+    DitherColorMode, DitherPalette, DitherPattern, DitherSettings, PaletteDistance, ScanlineMode,
+    ScanlineOrientation,
+};
+
+// Thresholding a 0..=255 ramp at every level recovers the gray each pixel was dithered
+// from, so the tone adjustments can be checked exactly
+fn adjusted_ramp(settings: &DitherSettings) -> Vec<u8> {
+    let ramp = DynamicImage::ImageLuma8(GrayImage::from_fn(256, 1, |x, _| Luma([x as u8])));
+    let mut levels = vec![0u8; 256];
+    for threshold in 0..255 {
+        let settings = DitherSettings { algorithm: DitherAlgorithm::Threshold, threshold: threshold as f32, ..settings.clone() };
+        let output = apply_dither(ramp.clone(), &settings, None);
+        for (x, _, _) in output.enumerate_pixels().filter(|(_, _, pixel)| pixel[0] == 255) {
+            levels[x as usize] = levels[x as usize].max(threshold + 1);
+        }
+    }
+    levels
+}
+
+#[test]
+fn neutral_tone_controls_leave_the_image_unchanged() {
+    let identity: Vec<u8> = (0..=255).collect();
+    assert_eq!(adjusted_ramp(&DitherSettings::default()), identity);
+
+    // Each control on its own moves the tones, and back at its neutral value does not
+    let moved = [
+        DitherSettings { contrast: 1.5, ..Default::default() },
+        DitherSettings { midtones: 0.3, ..Default::default() },
+        DitherSettings { highlights: 0.8, ..Default::default() },
+        DitherSettings { luminance_threshold: 100.0, ..Default::default() },
+        DitherSettings { black_point: 20.0, ..Default::default() },
+        DitherSettings { white_point: 230.0, ..Default::default() },
+    ];
+    for settings in &moved {
+        assert_ne!(adjusted_ramp(settings), identity);
+    }
+    let blurred = DitherSettings { blur: 2.0, ..Default::default() };
+    assert_ne!(adjusted_ramp(&blurred), identity);
+}
+
+#[test]
+fn black_and_white_points_stretch_the_levels_between_them() {
+    let stretched = adjusted_ramp(&DitherSettings { black_point: 64.0, white_point: 192.0, ..Default::default() });
+    assert!(stretched[..=64].iter().all(|&level| level == 0));
+    assert!(stretched[192..].iter().all(|&level| level == 255));
+    assert_eq!(stretched[128], 128);
+
+    // Equal points cut at that level rather than dividing by zero
+    let cut = adjusted_ramp(&DitherSettings { black_point: 128.0, white_point: 128.0, ..Default::default() });
+    assert!(cut[..=128].iter().all(|&level| level == 0));
+    assert!(cut[129..].iter().all(|&level| level == 255));
+}
+
+#[test]
+fn color_mode_dithers_each_channel() {
+    let image = DynamicImage::ImageRgb8(RgbImage::from_fn(64, 64, |x, y| Rgb([(x * 4) as u8, (y * 4) as u8, 90])));
+    for algorithm in [DitherAlgorithm::FloydSteinberg, DitherAlgorithm::Atkinson, DitherAlgorithm::Ordered, DitherAlgorithm::Threshold] {
+        let settings = DitherSettings { algorithm, color_mode: DitherColorMode::Rgb, color_levels: 3, ..Default::default() };
+        let output = apply_dither(image.clone(), &settings, None);
+        assert!(output.pixels().all(|p| p.0[..3].iter().all(|c| [0, 128, 255].contains(c)) && p[3] == 255));
+        assert!(output.pixels().any(|p| p[0] != p[1]));
+        // Blue is a flat 90 throughout, so only its average survives
+        let blue = output.pixels().map(|p| p[2] as f32).sum::<f32>() / (64.0 * 64.0);
+        if settings.algorithm.is_error_diffusion() {
+            assert!((blue - 90.0).abs() < 10.0, "{:?}", settings.algorithm.name());
+        }
+        assert_eq!(apply_dither_tiled(image.clone(), &settings, 7, None), output);
+    }
+}
+
+#[test]
+fn palettes_leave_only_their_colors() {
+    let image = DynamicImage::ImageRgb8(RgbImage::from_fn(48, 48, |x, y| Rgb([(x * 5) as u8, (y * 5) as u8, ((x + y) * 2) as u8])));
+    for preset in DitherPalette::ALL {
+        let palette = preset.colors();
+        for (algorithm, color_mode) in [
+            (DitherAlgorithm::FloydSteinberg, DitherColorMode::Rgb),
+            (DitherAlgorithm::Sierra, DitherColorMode::Grayscale),
+            (DitherAlgorithm::Ordered, DitherColorMode::Rgb),
+            (DitherAlgorithm::Random, DitherColorMode::Rgb),
+        ] {
+            let settings = DitherSettings { algorithm, color_mode, palette: Some(palette.clone()), ..Default::default() };
+            let output = apply_dither(image.clone(), &settings, None);
+            let used: std::collections::HashSet<[u8; 3]> = output.pixels().map(|p| [p[0], p[1], p[2]]).collect();
+            assert!(used.iter().all(|color| palette.contains(color)), "{} {}", preset.name(), settings.algorithm.name());
+            assert!(used.len() > 1);
+            assert_eq!(apply_dither_tiled(image.clone(), &settings, 5, None), output);
+        }
+    }
+}
+
+#[test]
+fn custom_palettes_come_from_images_and_hex_files() {
+    // Four flat quadrants give back exactly their four colours, darkest first
+    let quadrants = [[200, 30, 30], [20, 20, 20], [240, 240, 240], [30, 30, 200]];
+    let image = DynamicImage::ImageRgb8(RgbImage::from_fn(64, 64, |x, y| Rgb(quadrants[(x / 32 + y / 32 * 2) as usize])));
+    let palette = extract_palette(&image, 4);
+    assert_eq!(palette, vec![[20, 20, 20], [30, 30, 200], [200, 30, 30], [240, 240, 240]]);
+    assert!(check_palette(&palette).is_ok());
+    assert!(extract_palette(&image, 16).len() <= 4);
+
+    let pico8 = DitherPalette::Pico8.colors();
+    assert_eq!(parse_hex_palette(&to_hex_palette(&pico8)).unwrap(), pico8);
+    assert_eq!(parse_hex_palette("; exported palette\r\n\r\n#FF004D\r\n").unwrap(), vec![[255, 0, 77]]);
+    assert!(parse_hex_palette("ff004d\nnot a colour\n").is_err());
+    assert!(parse_hex_palette("ff00\n").is_err());
+}
+
+// The share of `value` pixels that touch another along a row or column, wrapping at the
+// edges. Well-spread dots rarely touch.
+fn clumping(image: &GrayImage, value: u8) -> f32 {
+    let (width, height) = image.dimensions();
+    let dots: Vec<(u32, u32)> = image.enumerate_pixels().filter(|(_, _, pixel)| pixel[0] == value).map(|(x, y, _)| (x, y)).collect();
+    let touching = dots
+        .iter()
+        .filter(|&&(x, y)| {
+            [((x + 1) % width, y), ((x + width - 1) % width, y), (x, (y + 1) % height), (x, (y + height - 1) % height)]
+                .iter()
+                .any(|&(nx, ny)| image.get_pixel(nx, ny)[0] == value)
+        })
+        .count();
+    touching as f32 / dots.len().max(1) as f32
+}
+
+#[test]
+fn blue_noise_spreads_dots_evenly() {
+    let settings = DitherSettings { algorithm: DitherAlgorithm::BlueNoise, color_levels: 2, ..Default::default() };
+    let dither = |gray: u8, settings: &DitherSettings| {
+        let flat = DynamicImage::ImageLuma8(GrayImage::from_pixel(128, 128, Luma([gray])));
+        DynamicImage::ImageRgba8(apply_dither(flat, settings, None)).to_luma8()
+    };
+    for (gray, sparse) in [(64u8, 255u8), (128, 255), (191, 0)] {
+        let output = dither(gray, &settings);
+        let white = output.pixels().filter(|pixel| pixel[0] == 255).count() as f32 / (128.0 * 128.0);
+        assert!((white - gray as f32 / 255.0).abs() < 0.02, "{gray}: {white} white");
+        // The 64-pixel tile repeats without a seam
+        assert!(output.enumerate_pixels().all(|(x, y, pixel)| output.get_pixel((x + 64) % 128, (y + 64) % 128) == pixel));
+        if gray != 128 {
+            assert!(clumping(&output, sparse) < 0.4, "{gray}: {} clumped", clumping(&output, sparse));
+        }
+    }
+
+    // Random thresholds clump more than twice as often
+    let random = DitherSettings { algorithm: DitherAlgorithm::Random, ..settings };
+    assert!(clumping(&dither(64, &random), 255) > 0.6);
+}
+
+#[test]
+fn sierra_variants_keep_the_average_tone() {
+    // Kernels whose weights sum to one carry all of each pixel's error forward, so a
+    // flat gray comes out with the same share of white
+    for algorithm in [DitherAlgorithm::Sierra, DitherAlgorithm::SierraTwoRow, DitherAlgorithm::SierraLite] {
+        for gray in [64u8, 128, 192] {
+            let flat = DynamicImage::ImageLuma8(GrayImage::from_pixel(64, 64, Luma([gray])));
+            let settings = DitherSettings { algorithm: algorithm.clone(), color_levels: 2, ..Default::default() };
+            let output = apply_dither(flat, &settings, None);
+            let white = output.pixels().filter(|pixel| pixel[0] == 255).count() as f32 / (64.0 * 64.0);
+            assert!((white - gray as f32 / 255.0).abs() < 0.03, "{} at {gray}: {white} white", algorithm.name());
+        }
+    }
+}
+
+#[test]
+fn cmyk_halftone_keeps_paper_white_and_ink_black() {
+    let settings = DitherSettings { algorithm: DitherAlgorithm::HalftoneCmyk, color_mode: DitherColorMode::Rgb, ..Default::default() };
+    let dither = |color: [u8; 3], settings: &DitherSettings| apply_dither(DynamicImage::ImageRgb8(RgbImage::from_pixel(48, 48, Rgb(color))), settings, None);
+
+    assert!(dither([255, 255, 255], &settings).pixels().all(|pixel| pixel.0 == [255, 255, 255, 255]));
+    for halftone_size in [2, 4, 9] {
+        let settings = DitherSettings { halftone_size, ..settings.clone() };
+        let black = dither([0, 0, 0], &settings);
+        let inked = black.pixels().filter(|pixel| pixel.0 == [0, 0, 0, 255]).count();
+        assert!(inked as f32 > 0.95 * 48.0 * 48.0, "{halftone_size}: {inked} black");
+    }
+
+    // A saturated red prints as magenta and yellow dots only: no blue or green light
+    // survives where both overlap, and no black plate is laid down
+    let red = dither([255, 0, 0], &settings);
+    assert!(red.pixels().all(|pixel| pixel[0] == 255));
+    assert!(red.pixels().any(|pixel| pixel.0 == [255, 0, 0, 255]));
+}
+
+#[test]
+fn pattern_dither_tiles_its_matrix() {
+    let ramp = DynamicImage::ImageLuma8(GrayImage::from_fn(64, 16, |x, _| Luma([(x * 4) as u8])));
+    let dither = |pattern: Vec<Vec<u8>>| {
+        apply_dither(ramp.clone(), &DitherSettings { algorithm: DitherAlgorithm::Pattern, pattern, ..Default::default() }, None)
+    };
+
+    for preset in DitherPattern::ALL {
+        let matrix = preset.matrix();
+        assert!(check_pattern(&matrix).is_ok(), "{}", preset.name());
+        let size = matrix.len() as u32;
+        let output = dither(matrix.clone());
+        for (x, y, pixel) in output.enumerate_pixels() {
+            let threshold = matrix[(y % size) as usize][(x % size) as usize] as f32 / (size * size) as f32 * 255.0;
+            assert_eq!(pixel[0] == 255, (x * 4) as f32 > threshold, "{} at {x},{y}", preset.name());
+        }
+    }
+
+    // Broken matrices dither with the default instead
+    let default = dither(DitherPattern::Bayer.matrix());
+    for broken in [vec![], vec![vec![0, 1], vec![2]], vec![vec![0, 9], vec![3, 1]]] {
+        assert!(check_pattern(&broken).is_err());
+        assert_eq!(dither(broken), default);
+    }
+}
+
+#[test]
+fn lab_matching_prefers_the_gray_that_looks_closer() {
+    // Nearer in RGB to the dark blue, but visibly a gray
+    let gray = DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb([60, 60, 60])));
+    let palette = vec![[100, 100, 100], [40, 40, 90], [255, 255, 255]];
+    let matched = |palette_distance| {
+        let settings = DitherSettings { color_mode: DitherColorMode::Rgb, palette: Some(palette.clone()), palette_distance, ..Default::default() };
+        let pixel = apply_dither(gray.clone(), &settings, None).get_pixel(0, 0).0;
+        [pixel[0], pixel[1], pixel[2]]
+    };
+    assert_eq!(matched(PaletteDistance::Rgb), [40, 40, 90]);
+    assert_eq!(matched(PaletteDistance::Lab), [100, 100, 100]);
+
+    // Exact palette colours match themselves either way
+    let image = DynamicImage::ImageRgb8(RgbImage::from_fn(16, 1, |x, _| Rgb(DitherPalette::Pico8.colors()[x as usize])));
+    for palette_distance in [PaletteDistance::Rgb, PaletteDistance::Lab] {
+        let settings = DitherSettings {
+            color_mode: DitherColorMode::Rgb,
+            palette: Some(DitherPalette::Pico8.colors()),
+            palette_distance,
+            ..Default::default()
+        };
+        let output = apply_dither(image.clone(), &settings, None);
+        assert!(output.enumerate_pixels().all(|(x, _, pixel)| [pixel[0], pixel[1], pixel[2]] == DitherPalette::Pico8.colors()[x as usize]));
+    }
+}
+
+#[test]
+fn indexed_png_is_lossless_and_far_smaller() {
+    let photo = DynamicImage::ImageRgb8(RgbImage::from_fn(256, 256, |x, y| {
+        Rgb([x as u8, y as u8, ((x * y) % 251) as u8])
+    }));
+    let rgba_png_size = |image: &image::RgbaImage| {
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image.write_to(&mut bytes, image::ImageFormat::Png).unwrap();
+        bytes.into_inner().len()
+    };
+
+    for settings in [
+        DitherSettings::default(),
+        DitherSettings { color_mode: DitherColorMode::Rgb, color_levels: 4, ..Default::default() },
+        DitherSettings { color_mode: DitherColorMode::Rgb, palette: Some(DitherPalette::Pico8.colors()), ..Default::default() },
+    ] {
+        let output = apply_dither(photo.clone(), &settings, None);
+        let indexed = encode_indexed_png(&output).unwrap().expect("dithered output is palette-limited");
+        assert_eq!(image::load_from_memory(&indexed).unwrap().to_rgba8(), output);
+        assert!(indexed.len() * 3 < rgba_png_size(&output), "{} vs {} bytes", indexed.len(), rgba_png_size(&output));
+    }
+
+    // Too many colours, or any transparency, need an ordinary PNG
+    assert!(encode_indexed_png(&photo.to_rgba8()).unwrap().is_none());
+    let mut translucent = apply_dither(photo.clone(), &DitherSettings::default(), None);
+    translucent.get_pixel_mut(0, 0)[3] = 128;
+    assert!(encode_indexed_png(&translucent).unwrap().is_none());
+}
+
+#[test]
+fn pixel_scale_dithers_in_blocks() {
+    let image = DynamicImage::ImageRgb8(RgbImage::from_fn(30, 18, |x, y| Rgb([(x * 8) as u8, (y * 14) as u8, 90])));
+    for algorithm in [DitherAlgorithm::FloydSteinberg, DitherAlgorithm::Ordered] {
+        let settings = DitherSettings { algorithm, pixel_scale: 4, ..Default::default() };
+        let output = apply_dither(image.clone(), &settings, None);
+        assert_eq!(output.dimensions(), (30, 18));
+        assert!(output.enumerate_pixels().all(|(x, y, pixel)| output.get_pixel(x / 4 * 4, y / 4 * 4) == pixel));
+
+        // The same as dithering each block's average on its own, including the partial
+        // blocks along the edges
+        let small = DynamicImage::ImageRgb8(RgbImage::from_fn(8, 5, |bx, by| {
+            let block: Vec<_> = (by * 4..(by * 4 + 4).min(18))
+                .flat_map(|y| (bx * 4..(bx * 4 + 4).min(30)).map(move |x| (x, y)))
+                .map(|(x, y)| image.to_luma8().get_pixel(x, y)[0] as u32)
+                .collect();
+            let gray = ((block.iter().sum::<u32>() + block.len() as u32 / 2) / block.len() as u32) as u8;
+            Rgb([gray; 3])
+        }));
+        let expected = apply_dither(small, &DitherSettings { pixel_scale: 1, ..settings.clone() }, None);
+        assert!(output.enumerate_pixels().all(|(x, y, pixel)| expected.get_pixel(x / 4, y / 4) == pixel), "{}", settings.algorithm.name());
+        assert_eq!(apply_dither_tiled(image.clone(), &settings, 5, None), output);
+    }
+}
+
+#[test]
+fn error_diffusion_tracks_a_smooth_gradient() {
+    // 0..=255 across 1024 columns, dithered to 4 levels: averaged over 32-column windows
+    // the output follows the input everywhere, shadows and highlights included, with no
+    // stretch pulled onto one level. Atkinson is left out, as it drops a quarter of the
+    // error by design.
+    let gradient = DynamicImage::ImageLuma8(GrayImage::from_fn(1024, 32, |x, _| Luma([(x / 4) as u8])));
+    for algorithm in [
+        DitherAlgorithm::FloydSteinberg,
+        DitherAlgorithm::Jarvis,
+        DitherAlgorithm::Stucki,
+        DitherAlgorithm::Burkes,
+        DitherAlgorithm::Sierra,
+        DitherAlgorithm::SierraTwoRow,
+        DitherAlgorithm::SierraLite,
+    ] {
+        let settings = DitherSettings { algorithm, color_levels: 4, ..Default::default() };
+        let output = apply_dither(gradient.clone(), &settings, None);
+        for window in 0..1024 / 32 {
+            let pixels = (window * 32..window * 32 + 32).flat_map(|x| (0..32).map(move |y| (x, y)));
+            let output_mean = pixels.clone().map(|(x, y)| output.get_pixel(x, y)[0] as f32).sum::<f32>() / 1024.0;
+            let input_mean = pixels.map(|(x, _)| (x / 4) as f32).sum::<f32>() / 1024.0;
+            assert!((output_mean - input_mean).abs() < 1.5, "{} around {input_mean}: {output_mean}", settings.algorithm.name());
+        }
+    }
+}
+
+#[test]
+fn prepared_images_serve_every_dither_setting() {
+    let image = DynamicImage::ImageRgb8(RgbImage::from_fn(40, 24, |x, y| Rgb([(x * 6) as u8, (y * 10) as u8, 200])));
+    let base = DitherSettings { contrast: 1.3, blur: 1.0, ..Default::default() };
+    let prepared = prepare_dither(&image, &base);
+
+    let dithering_only = [
+        DitherSettings { algorithm: DitherAlgorithm::Ordered, ..base.clone() },
+        DitherSettings { algorithm: DitherAlgorithm::Atkinson, color_levels: 3, ..base.clone() },
+        DitherSettings { palette: Some(DitherPalette::GameBoy.colors()), pixel_scale: 2, ..base.clone() },
+    ];
+    for settings in &dithering_only {
+        assert!(settings.same_preparation(&base));
+        assert_eq!(dither_prepared(prepared.clone(), settings, None), apply_dither(image.clone(), settings, None));
+    }
+
+    let retoned = [
+        DitherSettings { contrast: 1.0, ..base.clone() },
+        DitherSettings { blur: 0.0, ..base.clone() },
+        DitherSettings { color_mode: DitherColorMode::Rgb, ..base.clone() },
+    ];
+    for settings in &retoned {
+        assert!(!settings.same_preparation(&base));
+        assert_ne!(prepare_dither(&image, settings), prepared);
+    }
+}
+
+#[test]
+fn custom_colors_blend_between_the_dark_and_light_color() {
+    let ramp = DynamicImage::ImageLuma8(GrayImage::from_fn(256, 8, |x, _| Luma([x as u8])));
+    let (black, white) = ([20u8, 40, 80], [240u8, 220, 160]);
+    let settings = DitherSettings { color_levels: 4, custom_black: black, custom_white: white, ..Default::default() };
+    let output = apply_dither(ramp, &settings, None);
+
+    let blend = |t: f32| -> [u8; 3] { std::array::from_fn(|c| (black[c] as f32 + (white[c] as f32 - black[c] as f32) * t).round() as u8) };
+    let expected: Vec<[u8; 3]> = (0..4).map(|level| blend(level as f32 / 3.0)).collect();
+    let mut colors: Vec<[u8; 3]> = output.pixels().map(|p| [p[0], p[1], p[2]]).collect();
+    colors.sort();
+    colors.dedup();
+    assert_eq!(colors, expected);
+}
+
+#[test]
+fn scanline_bands_follow_orientation_thickness_and_phase() {
+    let ramp = DynamicImage::ImageLuma8(GrayImage::from_fn(16, 16, |x, y| Luma([(x * 16 + y) as u8])));
+
+    // The defaults keep the original alternating rows, every odd one inverted
+    let rows = apply_dither(ramp.clone(), &DitherSettings { algorithm: DitherAlgorithm::Scanline, ..Default::default() }, None);
+    for (x, y, pixel) in rows.enumerate_pixels() {
+        let gray = (x * 16 + y) as u8;
+        let expected = if y % 2 == 0 { gray } else { 255 - gray };
+        assert_eq!(pixel[0], if expected >= 128 { 255 } else { 0 }, "({}, {})", x, y);
+    }
+
+    let flat = DynamicImage::ImageLuma8(GrayImage::from_pixel(12, 4, Luma([200])));
+    let settings = DitherSettings {
+        algorithm: DitherAlgorithm::Scanline,
+        scanline_orientation: ScanlineOrientation::Vertical,
+        scanline_thickness: 3,
+        scanline_phase: 1,
+        scanline_mode: ScanlineMode::Darken,
+        ..Default::default()
+    };
+    let columns = apply_dither(flat, &settings, None);
+    let first_row: Vec<u8> = (0..12).map(|x| columns.get_pixel(x, 0)[0]).collect();
+    assert_eq!(first_row, [255, 255, 0, 0, 0, 255, 255, 255, 0, 0, 0, 255]);
+    assert!(columns.rows().all(|row| row.map(|pixel| pixel[0]).eq(first_row.iter().copied())));
+}
+
+#[test]
+fn strength_blends_back_to_the_prepared_image_before_tinting() {
+    let ramp = DynamicImage::ImageLuma8(GrayImage::from_fn(64, 64, |x, y| Luma([(x * 4 + y / 16) as u8])));
+    let settings = DitherSettings { algorithm: DitherAlgorithm::Ordered, ..Default::default() };
+    let prepared = prepare_dither(&ramp, &settings);
+    let full = apply_dither(ramp.clone(), &settings, None);
+
+    assert_eq!(apply_dither(ramp.clone(), &DitherSettings { strength: 0.0, ..settings.clone() }, None), prepared);
+    let half = apply_dither(ramp.clone(), &DitherSettings { strength: 0.5, ..settings.clone() }, None);
+    for ((blended, dithered), smooth) in half.pixels().zip(full.pixels()).zip(prepared.pixels()) {
+        assert_eq!(blended[0], ((dithered[0] as f32 + smooth[0] as f32) / 2.0).round() as u8);
+    }
+
+    // The duotone tint applies after the blend, so even the smooth image is tinted
+    let tinted = DitherSettings { strength: 0.0, custom_black: [0, 0, 100], custom_white: [200, 200, 200], ..settings };
+    let output = apply_dither(ramp, &tinted, None);
+    assert!(output.pixels().zip(prepared.pixels()).all(|(pixel, smooth)| pixel[2] >= 100 && pixel[0] <= smooth[0]));
+}
+
+// Scanline leaves its first row as plain quantized levels
+fn quantized_ramp(color_levels: u8) -> Vec<u8> {
+    let ramp = DynamicImage::ImageLuma8(GrayImage::from_fn(256, 1, |x, _| Luma([x as u8])));
+    let settings = DitherSettings { algorithm: DitherAlgorithm::Scanline, color_levels, ..Default::default() };
+    apply_dither(ramp, &settings, None).pixels().map(|pixel| pixel[0]).collect()
+}
+
+#[test]
+fn color_levels_below_two_quantize_like_two() {
+    let two = quantized_ramp(2);
+    assert_eq!(two[127], 0);
+    assert_eq!(two[128], 255);
+    assert_eq!(quantized_ramp(0), two);
+    assert_eq!(quantized_ramp(1), two);
+    assert!(check_dither_settings(&DitherSettings { color_levels: 0, ..Default::default() }).is_err());
+    assert!(check_dither_settings(&DitherSettings { color_levels: 2, ..Default::default() }).is_ok());
+}
+
+#[test]
+fn quantized_levels_round_to_the_nearest_value() {
+    // Steps of 255/254 used to truncate 254 down to 253
+    let fine = quantized_ramp(255);
+    assert_eq!(&fine[253..], [253, 254, 255]);
+    assert!(fine.iter().enumerate().all(|(value, &level)| (value as i32 - level as i32).abs() <= 1));
+
+    // Three levels put the middle one at 127.5, which rounds up
+    let three = quantized_ramp(3);
+    assert_eq!([three[0], three[64], three[191], three[192], three[255]], [0, 128, 128, 255, 255]);
+}
+
+#[test]
+fn ordered_dither_spans_a_whole_step_at_any_level_count() {
+    let gradient = DynamicImage::ImageLuma8(GrayImage::from_fn(256, 16, |x, _| Luma([x as u8])));
+    for color_levels in [2u8, 4, 8] {
+        let settings = DitherSettings { algorithm: DitherAlgorithm::Ordered, color_levels, ..Default::default() };
+        let output = DynamicImage::ImageRgba8(apply_dither(gradient.clone(), &settings, None)).to_luma8();
+        let step = 255.0 / (color_levels - 1) as f32;
+        let levels: Vec<u8> = (0..color_levels).map(|level| (level as f32 * step).round() as u8).collect();
+        assert!(levels.iter().all(|level| output.pixels().any(|pixel| pixel[0] == *level)), "{color_levels} levels");
+        assert!(output.pixels().all(|pixel| levels.contains(&pixel[0])), "{color_levels} levels");
+
+        // Every pixel stays within one step of its input, so nothing wraps around to the
+        // far end, and the ends of the gradient stay solid
+        for (x, _, pixel) in output.enumerate_pixels() {
+            assert!((pixel[0] as f32 - x as f32).abs() <= step, "{color_levels} levels at {x}: {}", pixel[0]);
+        }
+        assert!((0..16).all(|y| output.get_pixel(0, y)[0] == 0 && output.get_pixel(255, y)[0] == 255));
+
+        // Each 16×16 block keeps its average tone, which a spread narrower than a step
+        // loses towards the ends of each step
+        for block in 0..16 {
+            let mean = (block * 16..block * 16 + 16).flat_map(|x| (0..16).map(move |y| (x, y)))
+                .map(|(x, y)| output.get_pixel(x, y)[0] as f32).sum::<f32>() / 256.0;
+            assert!((mean - (block * 16) as f32 - 7.5).abs() < 12.0, "{color_levels} levels, block {block}: {mean}");
+        }
+
+        // Halfway between the first two levels both show up in equal measure rather than
+        // banding to one of them
+        let half = DynamicImage::ImageLuma8(GrayImage::from_pixel(16, 16, Luma([(step / 2.0).round() as u8])));
+        let output = apply_dither(half, &settings, None);
+        let upper = output.pixels().filter(|pixel| pixel[0] == levels[1]).count();
+        assert!((112..=144).contains(&upper), "{color_levels} levels: {upper} of 256 upper");
+    }
+}
+
+#[test]
+fn edge_preserve_keeps_a_rectangle_outline_solid() {
+    // A black rectangle on a light gradient that dithers to mostly white
+    let image = DynamicImage::ImageLuma8(GrayImage::from_fn(64, 64, |x, y| {
+        let inside = (16..48).contains(&x) && (16..48).contains(&y);
+        Luma([if inside { 0 } else { 140 + (x * 100 / 63) as u8 }])
+    }));
+    let plain = DitherSettings { algorithm: DitherAlgorithm::FloydSteinberg, ..Default::default() };
+    let preserved = DitherSettings { edge_preserve: 0.5, ..plain.clone() };
+    let sides = |output: &image::RgbaImage| -> Vec<u8> {
+        (17..47).flat_map(|i| [(15, i), (48, i), (i, 15), (i, 48)]).map(|(x, y)| output.get_pixel(x, y)[0]).collect()
+    };
+
+    let output = apply_dither(image.clone(), &preserved, None);
+    assert!((16..48).all(|x| (16..48).all(|y| output.get_pixel(x, y)[0] == 0)));
+    assert!(sides(&output).iter().all(|&value| value == 255));
+    // Away from the edges the gradient still dithers
+    assert!((0..64).any(|y| (0..8).any(|x| output.get_pixel(x, y)[0] == 0)));
+
+    // Without it, error from the rectangle dots the ring around it
+    assert!(sides(&apply_dither(image, &plain, None)).contains(&0));
+}
+
+#[test]
+fn yliluoma_mixes_palette_entries_into_smooth_steps() {
+    // From the darkest green of the palette to its lightest
+    let game_boy = DitherPalette::GameBoy.colors();
+    let (dark, light) = (game_boy[0], game_boy[3]);
+    let gradient = DynamicImage::ImageRgb8(RgbImage::from_fn(256, 16, |x, _| {
+        Rgb([0, 1, 2].map(|c| (dark[c] as f32 + (light[c] as f32 - dark[c] as f32) * x as f32 / 255.0).round() as u8))
+    }));
+    let settings = DitherSettings {
+        algorithm: DitherAlgorithm::YliluomaOrdered,
+        color_mode: DitherColorMode::Rgb,
+        palette: Some(game_boy.clone()),
+        ..Default::default()
+    };
+    let output = apply_dither(gradient, &settings, None);
+    assert!(output.pixels().all(|pixel| game_boy.contains(&[pixel[0], pixel[1], pixel[2]])));
+
+    // The mean green of each 4-column strip climbs steadily, through several times the
+    // four bands that matching each pixel to its nearest entry would give
+    let strip_means = |output: &image::RgbaImage| -> Vec<u32> {
+        (0..64).map(|strip| (strip * 4..strip * 4 + 4).flat_map(|x| (0..16).map(move |y| (x, y))).map(|(x, y)| output.get_pixel(x, y)[1] as u32).sum::<u32>()).collect()
+    };
+    let mixed = strip_means(&output);
+    assert!(mixed.windows(2).all(|pair| pair[1] + 16 >= pair[0]), "{mixed:?}");
+    let mut steps = mixed.clone();
+    steps.dedup();
+    assert!(steps.len() >= 20, "{mixed:?}");
+
+    // Without a palette it is plain Bayer
+    let flat = DynamicImage::ImageLuma8(GrayImage::from_pixel(8, 8, Luma([100])));
+    let ordered = DitherSettings { algorithm: DitherAlgorithm::Ordered, ..Default::default() };
+    let yliluoma = DitherSettings { algorithm: DitherAlgorithm::YliluomaOrdered, ..Default::default() };
+    assert_eq!(apply_dither(flat.clone(), &yliluoma, None), apply_dither(flat, &ordered, None));
+}
+
+#[test]
+fn error_clamp_shortens_trails_beside_a_bright_square() {
+    // White is far outside the palette, so the square leaves a trail of positive error
+    // that lights up the black beside it
+    let image = DynamicImage::ImageLuma8(GrayImage::from_fn(96, 64, |x, y| {
+        Luma([if (16..40).contains(&x) && (16..48).contains(&y) { 255 } else { 0 }])
+    }));
+    let trail = |error_clamp: f32| {
+        let settings = DitherSettings {
+            algorithm: DitherAlgorithm::Jarvis,
+            palette: Some(vec![[0, 0, 0], [32, 32, 32]]),
+            error_clamp,
+            ..Default::default()
+        };
+        let output = apply_dither(image.clone(), &settings, None);
+        (40..96).flat_map(|x| (0..64).map(move |y| (x, y))).filter(|&(x, y)| output.get_pixel(x, y)[0] != 0).count()
+    };
+    let trails: Vec<usize> = [1.0, 0.75, 0.5, 0.25, 0.1].into_iter().map(trail).collect();
+    assert!(trails.windows(2).all(|pair| pair[1] <= pair[0]), "{trails:?}");
+    assert!(trails[4] * 2 < trails[0], "{trails:?}");
+}
@@ -0,0 +1,50 @@
+use pixforge::drosteconverter::{droste_map, DrosteSettings};
+
+fn flat() -> DrosteSettings {
+    DrosteSettings { turns: 0, ..DrosteSettings::default() }
+}
+
+fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+    assert!((actual.0 - expected.0).abs() < 1e-4 && (actual.1 - expected.1).abs() < 1e-4, "{:?} != {:?}", actual, expected);
+}
+
+#[test]
+fn center_maps_to_itself() {
+    for turns in [0, 1, -2] {
+        let settings = DrosteSettings { turns, ..DrosteSettings::default() };
+        assert_eq!(droste_map(0.0, 0.0, &settings), (0.0, 0.0));
+    }
+}
+
+#[test]
+fn annulus_maps_to_itself() {
+    let settings = flat();
+    for (x, y) in [(0.4, 0.0), (0.0, -0.5), (0.3, 0.3), (-0.6, 0.2)] {
+        assert_close(droste_map(x, y, &settings), (x, y));
+    }
+}
+
+#[test]
+fn one_scale_ratio_out_maps_onto_the_inner_copy() {
+    let settings = flat();
+    let ratio = settings.outer_radius / settings.inner_radius;
+    for (x, y) in [(0.4, 0.0), (0.0, -0.5), (0.3, 0.3), (-0.6, 0.2)] {
+        // A point inside the hole is the inner copy of one in the annulus, and vice versa outside
+        assert_close(droste_map(x / ratio, y / ratio, &settings), (x, y));
+        assert_close(droste_map(x * ratio, y * ratio, &settings), (x, y));
+    }
+}
+
+#[test]
+fn spirals_stay_inside_the_annulus() {
+    for turns in [-2, 1, 3] {
+        let settings = DrosteSettings { turns, ..DrosteSettings::default() };
+        for i in 1..200 {
+            let angle = i as f32 * 0.7;
+            let radius = i as f32 / 100.0;
+            let (x, y) = droste_map(radius * angle.cos(), radius * angle.sin(), &settings);
+            let r = (x * x + y * y).sqrt();
+            assert!(r >= settings.inner_radius - 1e-4 && r <= settings.outer_radius + 1e-4, "turns {}: radius {}", turns, r);
+        }
+    }
+}
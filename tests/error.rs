@@ -0,0 +1,42 @@
+use std::io;
+
+use image::error::{EncodingError, ImageFormatHint};
+use image::{ImageError, ImageFormat};
+
+use pixforge::ArtsifyError;
+
+#[test]
+fn variants_display_with_their_prefix() {
+    let io = ArtsifyError::Io(io::Error::new(io::ErrorKind::NotFound, "no such file"));
+    assert_eq!(io.to_string(), "I/O error: no such file");
+    assert_eq!(ArtsifyError::Decode("bad header".to_string()).to_string(), "could not decode: bad header");
+    assert_eq!(ArtsifyError::Encode("too large".to_string()).to_string(), "could not encode: too large");
+    assert_eq!(ArtsifyError::Font("no glyphs".to_string()).to_string(), "font error: no glyphs");
+    assert_eq!(ArtsifyError::Cancelled.to_string(), "operation cancelled");
+}
+
+#[test]
+fn io_errors_convert_directly() {
+    let error: ArtsifyError = io::Error::new(io::ErrorKind::PermissionDenied, "read-only").into();
+    assert!(matches!(error, ArtsifyError::Io(ref e) if e.kind() == io::ErrorKind::PermissionDenied));
+}
+
+#[test]
+fn image_errors_map_by_kind() {
+    let missing = image::open(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/missing.png")).unwrap_err();
+    assert!(matches!(ArtsifyError::from(missing), ArtsifyError::Io(ref e) if e.kind() == io::ErrorKind::NotFound));
+
+    let corrupt = image::load_from_memory_with_format(b"\x89PNG\r\n\x1a\nnot really", ImageFormat::Png).unwrap_err();
+    assert!(matches!(corrupt, ImageError::Decoding(_)));
+    let error = ArtsifyError::from(corrupt);
+    assert!(matches!(error, ArtsifyError::Decode(_)), "{:?}", error);
+    assert!(error.to_string().starts_with("could not decode: "));
+
+    let encoding = ImageError::Encoding(EncodingError::new(ImageFormatHint::Exact(ImageFormat::Png), "out of space"));
+    let error = ArtsifyError::from(encoding);
+    assert!(matches!(error, ArtsifyError::Encode(ref message) if message.contains("out of space")), "{:?}", error);
+
+    // Anything else is a request the library cannot carry out, such as an unknown format
+    let unknown = image::load_from_memory(b"plain text").unwrap_err();
+    assert!(matches!(ArtsifyError::from(unknown), ArtsifyError::InvalidSettings(_)));
+}
@@ -0,0 +1,321 @@
+// Golden-image tests: every converter runs over the fixtures in tests/fixtures and its
+// output is compared with the files in tests/golden. Regenerate after an intentional
+// change with `UPDATE_GOLDENS=1 cargo test --test golden` and review the diff.
+
+use std::path::PathBuf;
+use image::{DynamicImage, RgbaImage};
+
+use pixforge::asciiconverter::{convert_image_to_ascii, AsciiMode, AsciiSettings, DetailLevel, SamplingQuality};
+use pixforge::crtconverter::{apply_crt, CrtSettings};
+use pixforge::ditherconverter::{apply_dither, DitherAlgorithm, DitherColorMode, DitherPalette, DitherPattern, DitherSettings};
+use pixforge::fisheyeconverter::{apply_fisheye, FisheyeSettings};
+
+const FIXTURES: [&str; 7] = ["gradient", "shapes", "alpha", "pixel_1x1", "column_1x16", "black", "white"];
+
+// Distortion filters interpolate in f32, so allow for last-bit rounding differences
+const RESAMPLE_TOLERANCE: u8 = 1;
+
+fn root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests")
+}
+
+fn fixture(name: &str) -> DynamicImage {
+    let path = root().join("fixtures").join(format!("{}.png", name));
+    image::open(&path).unwrap_or_else(|e| panic!("cannot open fixture {}: {}", path.display(), e))
+}
+
+fn updating() -> bool {
+    std::env::var_os("UPDATE_GOLDENS").is_some()
+}
+
+fn golden_path(name: &str, extension: &str) -> PathBuf {
+    root().join("golden").join(format!("{}.{}", name, extension))
+}
+
+// Returns a description of the mismatch, or None when `actual` matches the golden
+fn compare_image(name: &str, actual: &RgbaImage, tolerance: u8) -> Option<String> {
+    let path = golden_path(name, "png");
+    if updating() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        actual.save(&path).unwrap();
+        return None;
+    }
+    let expected = match image::open(&path) {
+        Ok(image) => image.to_rgba8(),
+        Err(e) => return Some(format!("{}: cannot open golden ({}), run with UPDATE_GOLDENS=1", name, e)),
+    };
+    if expected.dimensions() != actual.dimensions() {
+        return Some(format!("{}: size {:?}, expected {:?}", name, actual.dimensions(), expected.dimensions()));
+    }
+    for (x, y, pixel) in actual.enumerate_pixels() {
+        let golden = expected.get_pixel(x, y);
+        if pixel.0.iter().zip(golden.0.iter()).any(|(a, b)| a.abs_diff(*b) > tolerance) {
+            return Some(format!("{}: pixel ({}, {}) is {:?}, expected {:?}", name, x, y, pixel.0, golden.0));
+        }
+    }
+    None
+}
+
+fn compare_text(name: &str, actual: &str) -> Option<String> {
+    let path = golden_path(name, "txt");
+    if updating() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, actual).unwrap();
+        return None;
+    }
+    match std::fs::read_to_string(&path) {
+        Ok(expected) if expected == actual => None,
+        Ok(_) => Some(format!("{}: text differs from golden", name)),
+        Err(e) => Some(format!("{}: cannot open golden ({}), run with UPDATE_GOLDENS=1", name, e)),
+    }
+}
+
+fn assert_all_match(failures: Vec<String>) {
+    assert!(failures.is_empty(), "{} golden mismatches:\n{}", failures.len(), failures.join("\n"));
+}
+
+#[test]
+fn dither_matches_goldens() {
+    let cases = [
+        ("floyd_steinberg", DitherSettings::default()),
+        ("atkinson_4_levels", DitherSettings { algorithm: DitherAlgorithm::Atkinson, color_levels: 4, ..Default::default() }),
+        ("ordered_tone", DitherSettings { algorithm: DitherAlgorithm::Ordered, contrast: 1.5, midtones: 0.3, luminance_threshold: 128.0, ..Default::default() }),
+        ("threshold", DitherSettings { algorithm: DitherAlgorithm::Threshold, threshold: 100.0, ..Default::default() }),
+        ("halftone_blur", DitherSettings { algorithm: DitherAlgorithm::Halftone, blur: 1.0, ..Default::default() }),
+        ("random", DitherSettings { algorithm: DitherAlgorithm::Random, ..Default::default() }),
+        ("blue_noise", DitherSettings { algorithm: DitherAlgorithm::BlueNoise, ..Default::default() }),
+        ("crosshatch_pattern", DitherSettings { algorithm: DitherAlgorithm::Pattern, pattern: DitherPattern::Crosshatch.matrix(), ..Default::default() }),
+        ("cmyk_halftone", DitherSettings { algorithm: DitherAlgorithm::HalftoneCmyk, color_mode: DitherColorMode::Rgb, ..Default::default() }),
+        ("rgb_floyd_steinberg", DitherSettings { color_mode: DitherColorMode::Rgb, ..Default::default() }),
+        ("rgb_ordered_4_levels", DitherSettings { algorithm: DitherAlgorithm::Ordered, color_mode: DitherColorMode::Rgb, color_levels: 4, ..Default::default() }),
+        ("pico8_atkinson", DitherSettings { algorithm: DitherAlgorithm::Atkinson, color_mode: DitherColorMode::Rgb, palette: Some(DitherPalette::Pico8.colors()), ..Default::default() }),
+    ];
+    let mut failures = Vec::new();
+    for fixture_name in FIXTURES {
+        let image = fixture(fixture_name);
+        for (case, settings) in &cases {
+            let output = apply_dither(image.clone(), settings, None);
+            failures.extend(compare_image(&format!("dither/{}_{}", fixture_name, case), &output, 0));
+        }
+    }
+    assert_all_match(failures);
+}
+
+#[test]
+fn fisheye_matches_goldens() {
+    let cases = [
+        ("default", FisheyeSettings::default()),
+        ("pincushion", FisheyeSettings { strength: -0.6, zoom: 1.2, ..Default::default() }),
+        ("off_center", FisheyeSettings { strength: 0.8, center_x: 0.25, center_y: 0.7, ..Default::default() }),
+    ];
+    let mut failures = Vec::new();
+    for fixture_name in FIXTURES {
+        let image = fixture(fixture_name);
+        for (case, settings) in &cases {
+            let output = apply_fisheye(image.clone(), settings, None);
+            failures.extend(compare_image(&format!("fisheye/{}_{}", fixture_name, case), &output, RESAMPLE_TOLERANCE));
+        }
+    }
+    assert_all_match(failures);
+}
+
+#[test]
+fn crt_matches_goldens() {
+    let cases = [
+        ("default", CrtSettings::default()),
+        ("flat_no_bezel", CrtSettings { curvature: 0.0, bezel_size: 0.0, vignette_strength: 0.0, ..Default::default() }),
+        ("curved_transparent", CrtSettings { curvature: 0.3, bezel_size: 0.1, bg_opacity: 0, scanline_density: 3.0, ..Default::default() }),
+    ];
+    let mut failures = Vec::new();
+    for fixture_name in FIXTURES {
+        let image = fixture(fixture_name);
+        for (case, settings) in &cases {
+            let output = apply_crt(image.clone(), settings, None);
+            failures.extend(compare_image(&format!("crt/{}_{}", fixture_name, case), &output, RESAMPLE_TOLERANCE));
+        }
+    }
+    assert_all_match(failures);
+}
+
+#[test]
+fn ascii_matches_goldens() {
+    let cases = [
+        ("default", AsciiSettings::default()),
+        ("low_gray", AsciiSettings { detail_level: DetailLevel::Low, use_colors: false, ..Default::default() }),
+        ("narrow_contrast", AsciiSettings { detail_level: DetailLevel::Custom(50), brightness: 0.8, contrast: 1.8, ..Default::default() }),
+        ("inverted", AsciiSettings { detail_level: DetailLevel::Low, invert: true, ..Default::default() }),
+        ("half_block", AsciiSettings { detail_level: DetailLevel::Low, mode: AsciiMode::HalfBlock, ..Default::default() }),
+        ("edges", AsciiSettings { detail_level: DetailLevel::Low, edge_strength: 80.0, ..Default::default() }),
+        ("square_cells", AsciiSettings { detail_level: DetailLevel::Low, char_aspect: 1.0, ..Default::default() }),
+        ("best_sampling", AsciiSettings { sampling: SamplingQuality::Best, ..Default::default() }),
+        ("fit_height", AsciiSettings { fit_height: true, max_rows: 24, ..Default::default() }),
+        ("short_ramp", AsciiSettings { detail_level: DetailLevel::Low, charset: "@#*. ".to_string(), ..Default::default() }),
+        // Few enough cells that the larger fixtures are box-averaged
+        ("box_average", AsciiSettings { detail_level: DetailLevel::Custom(12), ..Default::default() }),
+    ];
+    let mut failures = Vec::new();
+    for fixture_name in FIXTURES {
+        let image = fixture(fixture_name);
+        let dimensions = (image.width(), image.height());
+        for (case, settings) in &cases {
+            let name = format!("ascii/{}_{}", fixture_name, case);
+            let result = convert_image_to_ascii(image.clone(), settings, dimensions, None);
+            failures.extend(compare_text(&name, &result.ascii_art));
+
+            // One pixel per character cell, so colour regressions show up too
+            let height = result.colored_ascii.len() as u32;
+            let width = result.colored_ascii.first().map_or(0, |row| row.len()) as u32;
+            let colors = RgbaImage::from_fn(width, height, |x, y| {
+                let [r, g, b] = result.colored_ascii[y as usize][x as usize].fg;
+                image::Rgba([r, g, b, 255])
+            });
+            failures.extend(compare_image(&format!("{}_colors", name), &colors, 0));
+            if settings.mode == AsciiMode::HalfBlock {
+                let backgrounds = RgbaImage::from_fn(width, height, |x, y| {
+                    let [r, g, b] = result.colored_ascii[y as usize][x as usize].bg.unwrap_or_default();
+                    image::Rgba([r, g, b, 255])
+                });
+                failures.extend(compare_image(&format!("{}_backgrounds", name), &backgrounds, 0));
+            }
+        }
+    }
+    assert_all_match(failures);
+}
+
+// Rendered ASCII art, to keep the renderer pixel-for-pixel stable; the grids are tall
+// enough to span several of the bands it draws in parallel
+#[cfg(feature = "render")]
+#[test]
+fn ascii_render_matches_goldens() {
+    use pixforge::asciiconverter::{render_ascii_to_image, ColorMode};
+
+    let cases = [
+        ("render_default", AsciiSettings { detail_level: DetailLevel::Custom(60), font_size: 9.0, ..Default::default() }),
+        ("render_gray_light", AsciiSettings { detail_level: DetailLevel::Custom(60), font_size: 9.0, use_colors: false, background_color: [240, 240, 240], ..Default::default() }),
+        ("render_tight_filled", AsciiSettings { detail_level: DetailLevel::Custom(60), font_size: 11.0, line_spacing: 0.7, fill_cells: true, caption: "Gy".to_string(), ..Default::default() }),
+        ("render_half_block", AsciiSettings { detail_level: DetailLevel::Custom(60), font_size: 6.0, mode: AsciiMode::HalfBlock, ..Default::default() }),
+        ("render_shade_duotone", AsciiSettings { detail_level: DetailLevel::Custom(60), font_size: 8.0, mode: AsciiMode::ShadeBlocks, color_mode: ColorMode::Duotone { dark: [20, 0, 60], light: [255, 200, 80] }, ..Default::default() }),
+    ];
+    let mut failures = Vec::new();
+    for fixture_name in ["shapes", "alpha"] {
+        let image = fixture(fixture_name);
+        let dimensions = (image.width(), image.height());
+        for (case, settings) in &cases {
+            let result = convert_image_to_ascii(image.clone(), settings, dimensions, None);
+            let rendered = render_ascii_to_image(&result.colored_ascii, settings, None, Some(&image)).unwrap();
+            failures.extend(compare_image(&format!("ascii/{}_{}", fixture_name, case), &rendered, 0));
+        }
+    }
+    assert_all_match(failures);
+}
+
+#[test]
+fn watercolor_matches_goldens() {
+    use pixforge::watercolorconverter::{apply_watercolor, WatercolorSettings};
+
+    let cases = [
+        ("default", WatercolorSettings::default()),
+        ("heavy_bleed", WatercolorSettings { smoothing: 5, bleed: 90.0, ..Default::default() }),
+        ("flat_wash", WatercolorSettings { texture: 0.0, edge_darkening: 0.0, ..Default::default() }),
+        ("other_seed", WatercolorSettings { texture: 100.0, seed: 4, ..Default::default() }),
+    ];
+    let mut failures = Vec::new();
+    for fixture_name in ["gradient", "shapes", "alpha"] {
+        let image = fixture(fixture_name);
+        for (case, settings) in &cases {
+            let output = apply_watercolor(image.clone(), settings, None);
+            failures.extend(compare_image(&format!("watercolor/{}_{}", fixture_name, case), &output, RESAMPLE_TOLERANCE));
+        }
+    }
+    assert_all_match(failures);
+}
+
+#[test]
+fn engraving_matches_goldens() {
+    use pixforge::engravingconverter::{apply_engraving, EngravingSettings};
+
+    let cases = [
+        ("default", EngravingSettings::default()),
+        ("straight", EngravingSettings { follow_flow: false, ..Default::default() }),
+        ("single_layer", EngravingSettings { layers: 1, line_spacing: 4.0, max_thickness: 0.9, ..Default::default() }),
+    ];
+    let mut failures = Vec::new();
+    // The radial fixture's rings show whether strokes bend with the tone
+    for fixture_name in ["gradient", "shapes", "radial"] {
+        let image = fixture(fixture_name);
+        for (case, settings) in &cases {
+            let output = apply_engraving(image.clone(), settings);
+            failures.extend(compare_image(&format!("engraving/{}_{}", fixture_name, case), &output, RESAMPLE_TOLERANCE));
+        }
+    }
+    assert_all_match(failures);
+}
+
+#[test]
+fn lomo_matches_goldens() {
+    use pixforge::lomoconverter::{apply_lomo, LomoPreset, LomoSettings};
+
+    let preset = |preset: LomoPreset| {
+        let mut settings = LomoSettings { preset, ..Default::default() };
+        settings.apply_preset();
+        settings
+    };
+    let cases = [
+        ("classic", preset(LomoPreset::Classic)),
+        ("cross_processed", preset(LomoPreset::CrossProcessed)),
+        ("dreamy", preset(LomoPreset::Dreamy)),
+        ("leak_other_seed", LomoSettings { preset: LomoPreset::Custom, light_leak: 100.0, seed: 9, ..Default::default() }),
+    ];
+    let mut failures = Vec::new();
+    for fixture_name in ["gradient", "shapes"] {
+        let image = fixture(fixture_name);
+        for (case, settings) in &cases {
+            let output = apply_lomo(image.clone(), settings);
+            failures.extend(compare_image(&format!("lomo/{}_{}", fixture_name, case), &output, RESAMPLE_TOLERANCE));
+        }
+    }
+    assert_all_match(failures);
+}
+
+// The remaining converters, mostly at their defaults, on the two non-degenerate fixtures
+#[test]
+fn other_converters_match_goldens() {
+    use pixforge::{
+        dotmatrixconverter, drosteconverter, hslconverter, infraredconverter,
+        kaleidoscopeconverter, levelsconverter, lutconverter, mosaicconverter, neonconverter,
+        oldphotoconverter, ortonconverter, popartconverter, retroconverter, sketchconverter, solarizeconverter,
+        stainedglassconverter, tiltshiftconverter, whitebalanceconverter,
+    };
+
+    type Converter = fn(DynamicImage) -> RgbaImage;
+    let converters: [(&str, Converter); 18] = [
+        ("dot_matrix", |image| dotmatrixconverter::apply_dot_matrix(image, &Default::default())),
+        ("droste", |image| drosteconverter::apply_droste(image, &Default::default())),
+        ("hsl", |image| hslconverter::apply_hsl(image, &hslconverter::HslSettings { hue: 40.0, saturation: 20.0, ..Default::default() })),
+        ("infrared", |image| infraredconverter::apply_infrared(image, &Default::default())),
+        ("kaleidoscope", |image| kaleidoscopeconverter::apply_kaleidoscope(image, &Default::default())),
+        ("levels", |image| levelsconverter::apply_levels(image, &levelsconverter::LevelsSettings { input_black: 30.0, gamma: 1.4, ..Default::default() })),
+        ("lut", |image| lutconverter::apply_lut(image, &Default::default())),
+        ("mosaic", |image| mosaicconverter::apply_mosaic(image, &Default::default())),
+        ("neon", |image| neonconverter::apply_neon(image, &Default::default())),
+        ("old_photo", |image| oldphotoconverter::apply_old_photo(image, &Default::default())),
+        ("orton", |image| ortonconverter::apply_orton(image, &Default::default())),
+        ("pop_art", |image| popartconverter::apply_pop_art(image, &Default::default())),
+        ("retro", |image| retroconverter::apply_retro(image, &retroconverter::RetroSettings { scale: 1, ..Default::default() })),
+        ("sketch", |image| sketchconverter::apply_sketch(image, &Default::default())),
+        ("solarize", |image| solarizeconverter::apply_solarize(image, &Default::default())),
+        ("stained_glass", |image| stainedglassconverter::apply_stained_glass(image, &Default::default())),
+        ("tilt_shift", |image| tiltshiftconverter::apply_tilt_shift(image, &Default::default())),
+        ("white_balance", |image| whitebalanceconverter::apply_white_balance(image, &whitebalanceconverter::WhiteBalanceSettings { temperature: 40.0, ..Default::default() })),
+    ];
+
+    let mut failures = Vec::new();
+    for fixture_name in ["gradient", "shapes"] {
+        let image = fixture(fixture_name);
+        for (name, convert) in &converters {
+            let output = convert(image.clone());
+            failures.extend(compare_image(&format!("{}/{}", name, fixture_name), &output, RESAMPLE_TOLERANCE));
+        }
+    }
+    assert_all_match(failures);
+}
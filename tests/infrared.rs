@@ -0,0 +1,38 @@
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use pixforge::infraredconverter::{apply_infrared, mix_pixel, InfraredPreset, InfraredSettings};
+
+const PIXEL: [u8; 3] = [100, 150, 50];
+
+// Worked by hand from the preset rows, e.g. false colour red = 1.3 * 100 - 0.1 * 150 - 0.2 * 50
+const EXPECTED: [(InfraredPreset, [u8; 3]); 3] = [
+    (InfraredPreset::FalseColor, [105, 130, 80]),
+    (InfraredPreset::BlackAndWhite, [110, 110, 110]),
+    // The custom mixer starts out as the identity
+    (InfraredPreset::Custom, PIXEL),
+];
+
+#[test]
+fn presets_mix_a_known_pixel() {
+    for (preset, expected) in EXPECTED {
+        let settings = InfraredSettings { preset: preset.clone(), ..InfraredSettings::default() };
+        assert_eq!(mix_pixel(&settings.mixer(), PIXEL), expected, "{}", preset.name());
+
+        // With glow and grain off the whole filter is just the mixer
+        let settings = InfraredSettings { glow: 0.0, grain: 0.0, ..settings };
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(3, 3, Rgba([PIXEL[0], PIXEL[1], PIXEL[2], 200])));
+        let output = apply_infrared(image, &settings);
+        assert_eq!(*output.get_pixel(1, 1), Rgba([expected[0], expected[1], expected[2], 200]), "{}", preset.name());
+    }
+}
+
+#[test]
+fn mixing_clamps_and_keeps_grays() {
+    let false_color = InfraredPreset::FalseColor.matrix().unwrap();
+    // Red = 1.3 * 250 - 0.2 * 250 and -0.1 * 250
+    assert_eq!(mix_pixel(&false_color, [250, 0, 250]), [255, 125, 225]);
+    assert_eq!(mix_pixel(&false_color, [0, 250, 0]), [0, 125, 25]);
+    for preset in [InfraredPreset::FalseColor, InfraredPreset::BlackAndWhite] {
+        assert_eq!(mix_pixel(&preset.matrix().unwrap(), [90, 90, 90]), [90, 90, 90], "{}", preset.name());
+    }
+}
@@ -0,0 +1,61 @@
+use std::f32::consts::TAU;
+
+use pixforge::kaleidoscopeconverter::fold_angle;
+
+const EPSILON: f32 = 1e-4;
+
+fn assert_close(actual: f32, expected: f32, what: &str) {
+    assert!((actual - expected).abs() < EPSILON, "{}: {} != {}", what, actual, expected);
+}
+
+#[test]
+fn first_wedge_stays_fixed() {
+    for n in [2, 6, 12] {
+        let segment = TAU / n as f32;
+        for i in 0..=10 {
+            let angle = segment / 2.0 * i as f32 / 10.0;
+            assert_close(fold_angle(angle, n, true), angle, &format!("n = {}, mirrored", n));
+        }
+        for i in 0..10 {
+            let angle = segment * i as f32 / 10.0;
+            assert_close(fold_angle(angle, n, false), angle, &format!("n = {}", n));
+        }
+    }
+}
+
+#[test]
+fn mirrored_halves_reflect() {
+    for n in [2, 6, 12] {
+        let segment = TAU / n as f32;
+        for i in 1..10 {
+            let offset = segment / 2.0 * i as f32 / 10.0;
+            // The second half of each wedge is the first half seen in a mirror
+            assert_close(fold_angle(segment - offset, n, true), offset, &format!("n = {}", n));
+            // Every later wedge repeats the first, and so does the one before it
+            for k in [-1, 1, n as i32 - 1] {
+                let shift = segment * k as f32;
+                assert_close(fold_angle(offset + shift, n, true), offset, &format!("n = {}, wedge {}", n, k));
+                assert_close(fold_angle(segment - offset + shift, n, true), offset, &format!("n = {}, wedge {}", n, k));
+                assert_close(fold_angle(offset + shift, n, false), offset, &format!("n = {}, wedge {}", n, k));
+            }
+        }
+    }
+}
+
+#[test]
+fn boundaries_are_continuous() {
+    for n in [2, 6, 12] {
+        let segment = TAU / n as f32;
+        for k in 0..=n {
+            let edge = segment * k as f32;
+            // Wedge edges fold onto the start edge, from either side
+            assert_close(fold_angle(edge, n, true), 0.0, &format!("n = {}, edge {}", n, k));
+            assert_close(fold_angle(edge + 1e-3, n, true), 1e-3, &format!("n = {}, after edge {}", n, k));
+            assert_close(fold_angle(edge - 1e-3, n, true), 1e-3, &format!("n = {}, before edge {}", n, k));
+            // The mirror line halfway across is where both halves meet
+            let middle = edge + segment / 2.0;
+            assert_close(fold_angle(middle - 1e-3, n, true), segment / 2.0 - 1e-3, &format!("n = {}, middle {}", n, k));
+            assert_close(fold_angle(middle + 1e-3, n, true), segment / 2.0 - 1e-3, &format!("n = {}, middle {}", n, k));
+        }
+    }
+}
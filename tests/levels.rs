@@ -0,0 +1,67 @@
+use pixforge::levelsconverter::{build_lut, evaluate_curve, LevelsSettings};
+
+fn with_curve(curve: Vec<[f32; 2]>) -> LevelsSettings {
+    LevelsSettings { curve, ..LevelsSettings::default() }
+}
+
+#[test]
+fn default_settings_are_the_identity() {
+    let lut = build_lut(&LevelsSettings::default());
+    for (i, &v) in lut.iter().enumerate() {
+        assert_eq!(v as usize, i);
+    }
+}
+
+#[test]
+fn falling_curve_inverts() {
+    let lut = build_lut(&with_curve(vec![[0.0, 1.0], [1.0, 0.0]]));
+    for (i, &v) in lut.iter().enumerate() {
+        assert_eq!(v as usize, 255 - i);
+    }
+}
+
+#[test]
+fn swapped_output_levels_invert() {
+    let settings = LevelsSettings { output_black: 255.0, output_white: 0.0, ..LevelsSettings::default() };
+    let lut = build_lut(&settings);
+    for (i, &v) in lut.iter().enumerate() {
+        assert_eq!(v as usize, 255 - i);
+    }
+}
+
+#[test]
+fn s_curve_is_monotone_without_overshoot() {
+    let points = vec![[0.0, 0.0], [0.25, 0.1], [0.5, 0.5], [0.75, 0.9], [1.0, 1.0]];
+    let mut previous = f32::MIN;
+    for i in 0..=1000 {
+        let y = evaluate_curve(&points, i as f32 / 1000.0);
+        assert!(y >= previous, "curve falls at x = {}", i as f32 / 1000.0);
+        assert!((0.0..=1.0).contains(&y), "curve leaves 0..1 at x = {}: {}", i as f32 / 1000.0, y);
+        previous = y;
+    }
+    for [x, y] in &points {
+        assert!((evaluate_curve(&points, *x) - y).abs() < 1e-6);
+    }
+
+    let lut = build_lut(&with_curve(points));
+    assert!(lut.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!((lut[0], lut[255]), (0, 255));
+    // Darker than the identity below the midpoint and lighter above it
+    assert!(lut[64] < 64 && lut[192] > 192);
+}
+
+#[test]
+fn steep_curve_does_not_overshoot_flat_segments() {
+    // A natural spline would bulge past 1.0 after the jump and below 0.0 before it
+    let points = vec![[0.0, 0.0], [0.45, 0.0], [0.55, 1.0], [1.0, 1.0]];
+    for i in 0..=1000 {
+        let x = i as f32 / 1000.0;
+        let y = evaluate_curve(&points, x);
+        assert!((0.0..=1.0).contains(&y), "curve leaves 0..1 at x = {}: {}", x, y);
+        if x <= 0.45 {
+            assert_eq!(y, 0.0);
+        } else if x >= 0.55 {
+            assert_eq!(y, 1.0);
+        }
+    }
+}
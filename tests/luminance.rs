@@ -0,0 +1,46 @@
+use image::{DynamicImage, Rgb, RgbImage};
+
+use pixforge::asciiconverter::{convert_image_to_ascii, AsciiSettings};
+use pixforge::ditherconverter::{apply_dither, DitherAlgorithm, DitherSettings};
+use pixforge::luminance::LuminanceModel;
+
+const MODELS: [(LuminanceModel, f32); 4] = [
+    (LuminanceModel::Rec601, 0.587),
+    (LuminanceModel::Rec709, 0.7152),
+    (LuminanceModel::Average, 1.0 / 3.0),
+    (LuminanceModel::Lightness, 0.5),
+];
+
+fn green() -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, Rgb([0, 255, 0])))
+}
+
+#[test]
+fn pure_green_matches_the_documented_values() {
+    for (model, expected) in MODELS {
+        assert!((model.luminance(0.0, 1.0, 0.0) - expected).abs() < 1e-6, "{}", model.name());
+        let gray = model.to_luma8(&green());
+        assert_eq!(gray.get_pixel(0, 0)[0], (expected * 255.0).round() as u8, "{}", model.name());
+    }
+}
+
+#[test]
+fn rec709_matches_the_image_crate() {
+    let image = DynamicImage::ImageRgb8(RgbImage::from_fn(16, 16, |x, y| Rgb([(x * 16) as u8, (y * 16) as u8, 77])));
+    assert_eq!(LuminanceModel::Rec709.to_luma8(&image), image.to_luma8());
+}
+
+#[test]
+fn both_converters_follow_the_model() {
+    for (model, expected) in MODELS {
+        let settings = DitherSettings { algorithm: DitherAlgorithm::Threshold, luminance: model, luminance_threshold: 0.0, color_levels: 255, ..Default::default() };
+        let threshold = (expected * 255.0).round();
+        let above = apply_dither(green(), &DitherSettings { threshold: threshold - 1.0, ..settings.clone() }, None);
+        let below = apply_dither(green(), &DitherSettings { threshold: threshold + 1.0, ..settings }, None);
+        assert_eq!((above.get_pixel(0, 0)[0], below.get_pixel(0, 0)[0]), (255, 0), "{}", model.name());
+
+        let ascii = AsciiSettings { luminance: model, brightness: 1.0, contrast: 1.0, use_colors: false, ..Default::default() };
+        let result = convert_image_to_ascii(green(), &ascii, (4, 4), None);
+        assert_eq!(result.colored_ascii[0][0].fg[0], (expected * 255.0) as u8, "{}", model.name());
+    }
+}
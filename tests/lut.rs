@@ -0,0 +1,88 @@
+use pixforge::lutconverter::{parse_cube, Lut3D};
+use pixforge::ArtsifyError;
+
+// A .cube file of `size`³ entries, red varying fastest
+fn cube(size: usize, f: impl Fn([f32; 3]) -> [f32; 3]) -> String {
+    let mut text = format!("TITLE \"test\"\n# comment\nLUT_3D_SIZE {}\n", size);
+    for entry in Lut3D::from_fn("", size, f).data {
+        text.push_str(&format!("{} {} {}\n", entry[0], entry[1], entry[2]));
+    }
+    text
+}
+
+fn assert_close(actual: [f32; 3], expected: [f32; 3]) {
+    for c in 0..3 {
+        assert!((actual[c] - expected[c]).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn identity_lut_is_a_no_op() {
+    let lut = parse_cube(&cube(17, |c| c)).unwrap();
+    assert_eq!(lut.title, "test");
+    assert_eq!(lut.size, 17);
+    for color in [[0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [0.2, 0.5, 0.9], [0.33, 0.01, 0.77], [0.999, 0.5, 0.0]] {
+        assert_close(lut.sample(color), color);
+    }
+}
+
+#[test]
+fn identity_33_lut_is_a_no_op_between_grid_points() {
+    let lut = parse_cube(&cube(33, |c| c)).unwrap();
+    assert_eq!(lut.size, 33);
+    assert_eq!(lut.data.len(), 33 * 33 * 33);
+    // None of these sit on the 1/32 grid, so every sample interpolates
+    for color in [[0.01, 0.5, 0.99], [0.2, 0.3, 0.4], [0.777, 0.123, 0.456], [0.515, 0.015, 0.985]] {
+        assert_close(lut.sample(color), color);
+    }
+    // A curve is reproduced exactly on its grid points
+    let squared = parse_cube(&cube(33, |c| c.map(|v| v * v))).unwrap();
+    assert_close(squared.sample([0.25, 0.5, 0.75]), [0.0625, 0.25, 0.5625]);
+}
+
+#[test]
+fn red_to_blue_lut_swaps_channels() {
+    let lut = parse_cube(&cube(2, |[r, g, b]| [b, g, r])).unwrap();
+    assert_close(lut.sample([1.0, 0.0, 0.0]), [0.0, 0.0, 1.0]);
+    assert_close(lut.sample([0.8, 0.4, 0.1]), [0.1, 0.4, 0.8]);
+}
+
+#[test]
+fn input_range_rescales_samples() {
+    let text = cube(2, |c| c).replace("LUT_3D_SIZE 2", "LUT_3D_SIZE 2\nLUT_3D_INPUT_RANGE 0 2");
+    let lut = parse_cube(&text).unwrap();
+    assert_close(lut.sample([1.0, 0.5, 2.0]), [0.5, 0.25, 1.0]);
+}
+
+#[test]
+fn wrong_entry_count_is_rejected() {
+    let mut text = cube(3, |c| c);
+    text.push_str("0 0 0\n");
+    let err = parse_cube(&text).err().expect("an extra entry must be rejected");
+    assert!(matches!(err, ArtsifyError::Decode(ref message) if message.contains("found 28")), "{}", err);
+
+    let short: String = cube(3, |c| c).lines().take(10).map(|l| format!("{}\n", l)).collect();
+    assert!(matches!(parse_cube(&short), Err(ArtsifyError::Decode(_))));
+}
+
+#[test]
+fn missing_size_is_rejected() {
+    let err = parse_cube("TITLE \"empty\"\n").err().expect("a file without LUT_3D_SIZE must be rejected");
+    assert!(matches!(err, ArtsifyError::Decode(ref message) if message.contains("LUT_3D_SIZE")), "{}", err);
+
+    let data_only = cube(2, |c| c).replace("LUT_3D_SIZE 2\n", "");
+    assert!(matches!(parse_cube(&data_only), Err(ArtsifyError::Decode(_))));
+}
+
+#[test]
+fn other_malformed_files_are_rejected() {
+    for text in [
+        "LUT_1D_SIZE 4\n",
+        "LUT_3D_SIZE 1\n",
+        "LUT_3D_SIZE 2\n0 0\n",
+        "LUT_3D_SIZE 2\nBOGUS 1\n",
+        "LUT_3D_SIZE 2\nDOMAIN_MIN 1 1 1\nDOMAIN_MAX 0 0 0\n",
+    ] {
+        assert!(matches!(parse_cube(text), Err(ArtsifyError::Decode(_))), "{:?} was accepted", text);
+    }
+}
@@ -0,0 +1,29 @@
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use pixforge::neonconverter::{apply_neon, NeonColorMode, NeonSettings};
+
+#[test]
+fn flat_background_stays_dark() {
+    for value in [40u8, 128, 255] {
+        let flat = DynamicImage::ImageRgba8(RgbaImage::from_pixel(48, 32, Rgba([value, value, value, 255])));
+        for color_mode in [NeonColorMode::Single, NeonColorMode::SourceHue, NeonColorMode::RainbowDirection] {
+            let settings = NeonSettings { color_mode, ..NeonSettings::default() };
+            let dimmed = (value as f32 * (1.0 - settings.background_dim / 100.0)).round() as u8;
+            let output = apply_neon(flat.clone(), &settings);
+            for (x, y, p) in output.enumerate_pixels() {
+                assert_eq!(*p, Rgba([dimmed, dimmed, dimmed, 255]), "value {} at ({}, {})", value, x, y);
+            }
+        }
+    }
+}
+
+#[test]
+fn edges_light_up() {
+    let square = RgbaImage::from_fn(48, 48, |x, y| {
+        if (12..36).contains(&x) && (12..36).contains(&y) { Rgba([255, 255, 255, 255]) } else { Rgba([0, 0, 0, 255]) }
+    });
+    let output = apply_neon(DynamicImage::ImageRgba8(square), &NeonSettings { color_mode: NeonColorMode::Single, ..NeonSettings::default() });
+    let edge = output.get_pixel(12, 24);
+    let inside = output.get_pixel(24, 24);
+    assert!(edge[0] > inside[0] && edge[0] > 200, "edge {:?}, inside {:?}", edge, inside);
+}
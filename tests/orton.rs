@@ -0,0 +1,37 @@
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use pixforge::ortonconverter::{apply_orton, OrtonSettings};
+
+fn fixture(name: &str) -> DynamicImage {
+    image::open(format!("{}/tests/fixtures/{}.png", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+#[test]
+fn zero_strength_is_a_no_op() {
+    let settings = OrtonSettings { glow_strength: 0.0, ..OrtonSettings::default() };
+    for name in ["gradient", "shapes", "alpha"] {
+        let image = fixture(name);
+        assert_eq!(apply_orton(image.clone(), &settings), image.to_rgba8(), "{}", name);
+    }
+}
+
+#[test]
+fn white_stays_white() {
+    let white = fixture("white");
+    for (glow_strength, highlight_protection) in [(60.0, 50.0), (100.0, 0.0), (100.0, 100.0)] {
+        let settings = OrtonSettings { glow_strength, highlight_protection, ..OrtonSettings::default() };
+        let output = apply_orton(white.clone(), &settings);
+        assert!(output.pixels().all(|p| p[0] == 255 && p[1] == 255 && p[2] == 255), "strength {}", glow_strength);
+    }
+}
+
+#[test]
+fn near_white_does_not_wrap() {
+    let input = RgbaImage::from_fn(16, 16, |x, _| Rgba([240 + x as u8, 250, 255, 255]));
+    let settings = OrtonSettings { glow_strength: 100.0, highlight_protection: 0.0, ..OrtonSettings::default() };
+    let output = apply_orton(DynamicImage::ImageRgba8(input.clone()), &settings);
+    // Two screened copies multiplied together only brighten tones this high, up to 255
+    for (out, pixel) in output.pixels().zip(input.pixels()) {
+        assert!((0..3).all(|c| out[c] >= pixel[c]), "{:?} from {:?}", out, pixel);
+    }
+}
@@ -0,0 +1,24 @@
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use pixforge::popartconverter::{apply_pop_art, PopArtSettings};
+
+// The colour outlines are drawn in
+const INK: Rgba<u8> = Rgba([15, 15, 15, 255]);
+
+fn outline_pixels(thickness: u32) -> usize {
+    // A single vertical edge between two light panels, so only the outline is near black
+    let input = RgbaImage::from_fn(64, 64, |x, _| if x < 32 { Rgba([250, 250, 250, 255]) } else { Rgba([20, 90, 200, 255]) });
+    let settings = PopArtSettings { outline_thickness: thickness, ..PopArtSettings::default() };
+    let output = apply_pop_art(DynamicImage::ImageRgba8(input), &settings);
+    output.pixels().filter(|&&p| p == INK).count()
+}
+
+#[test]
+fn every_thickness_step_widens_the_outline() {
+    let counts: Vec<usize> = (0..=4).map(outline_pixels).collect();
+    assert_eq!(counts[0], 0);
+    assert!(counts[1] > 0);
+    for pair in counts.windows(2) {
+        assert!(pair[1] > pair[0], "{:?}", counts);
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::{mpsc, Arc};
+
+use image::{DynamicImage, RgbaImage};
+
+use pixforge::asciiconverter::{convert_image_to_ascii, AsciiSettings};
+use pixforge::crtconverter::{apply_crt, CrtSettings};
+use pixforge::ditherconverter::{apply_dither, DitherSettings};
+use pixforge::fisheyeconverter::{apply_fisheye, FisheyeSettings};
+use pixforge::progress::Progress;
+use pixforge::watercolorconverter::{apply_watercolor, WatercolorSettings};
+
+type Converter = fn(DynamicImage, &Progress);
+
+const CONVERTERS: [(&str, Converter); 5] = [
+    ("ascii", |image, progress| {
+        convert_image_to_ascii(image, &AsciiSettings::default(), (48, 32), Some(progress));
+    }),
+    ("crt", |image, progress| {
+        apply_crt(image, &CrtSettings::default(), Some(progress));
+    }),
+    ("dither", |image, progress| {
+        apply_dither(image, &DitherSettings::default(), Some(progress));
+    }),
+    ("fisheye", |image, progress| {
+        apply_fisheye(image, &FisheyeSettings::default(), Some(progress));
+    }),
+    ("watercolor", |image, progress| {
+        apply_watercolor(image, &WatercolorSettings::default(), Some(progress));
+    }),
+];
+
+fn run(convert: Converter, cancelled: bool) -> Vec<(usize, usize)> {
+    let (sender, receiver) = mpsc::channel();
+    let progress = Progress::new(sender, Arc::new(AtomicBool::new(cancelled)));
+    convert(DynamicImage::ImageRgba8(RgbaImage::new(48, 32)), &progress);
+    drop(progress);
+    receiver.iter().collect()
+}
+
+#[test]
+fn progress_reaches_total() {
+    for (name, convert) in CONVERTERS {
+        let updates = run(convert, false);
+        let (done, total) = *updates.last().unwrap_or_else(|| panic!("{} reported nothing", name));
+        assert!(total > 0 && done == total, "{} stopped at {}/{}", name, done, total);
+        assert!(updates.iter().all(|&(_, t)| t == total), "{} changed its total", name);
+    }
+}
+
+#[test]
+fn cancelled_converters_stop_early() {
+    for (name, convert) in CONVERTERS {
+        let updates = run(convert, true);
+        assert!(updates.iter().all(|&(done, total)| done < total), "{} finished despite cancellation", name);
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashSet;
+use std::f32::consts::TAU;
+
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use pixforge::retroconverter::{apply_retro, frame_palette, hardware_palette, RetroConsole};
+
+// Hue sweeps left to right and brightness top to bottom, so every console has to pick
+fn colorful(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+        let t = x as f32 / (width - 1) as f32;
+        let v = 1.0 - y as f32 / (height - 1) as f32;
+        let channel = |phase: f32| ((1.0 + (t * TAU + phase).cos()) * 0.5 * v * 255.0).round() as u8;
+        Rgba([channel(0.0), channel(TAU / 3.0), channel(TAU * 2.0 / 3.0), 255])
+    })
+}
+
+fn is_15bit(color: [u8; 3]) -> bool {
+    color.iter().all(|&c| c == (c & 0xF8) | (c >> 5))
+}
+
+#[test]
+fn output_uses_only_the_consoles_colors() {
+    let image = DynamicImage::ImageRgba8(colorful(96, 64));
+    for console in RetroConsole::ALL {
+        let settings = console.settings();
+        let output = apply_retro(image.clone(), &settings);
+        let colors: HashSet<[u8; 3]> = output.pixels().map(|p| [p[0], p[1], p[2]]).collect();
+        match hardware_palette(console) {
+            Some(hardware) => {
+                for color in &colors {
+                    assert!(hardware.contains(color), "{}: {:?} is not a hardware color", console.name(), color);
+                }
+            }
+            None => assert!(colors.iter().all(|&c| is_15bit(c)), "{}: colors outside the 15-bit gamut", console.name()),
+        }
+        assert!(colors.len() <= settings.max_colors as usize, "{}: {} colors on screen", console.name(), colors.len());
+    }
+}
+
+#[test]
+fn frame_palette_has_no_duplicates() {
+    // Dark colors a few steps apart, which median cut keeps apart but 15-bit snapping merges
+    let dark = RgbaImage::from_fn(64, 4, |x, y| Rgba([(x * 3 % 17) as u8, (y * 5) as u8, (x % 7) as u8, 255]));
+    for image in [colorful(64, 64), dark] {
+        for console in RetroConsole::ALL {
+            let palette = frame_palette(&image, console, 8);
+            let unique: HashSet<[u8; 3]> = palette.iter().copied().collect();
+            assert_eq!(unique.len(), palette.len(), "{}: {:?}", console.name(), palette);
+        }
+    }
+}
+
@@ -0,0 +1,32 @@
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use pixforge::sketchconverter::{apply_sketch, SketchMode, SketchSettings};
+
+fn fixture(name: &str) -> DynamicImage {
+    image::open(format!("{}/tests/fixtures/{}.png", env!("CARGO_MANIFEST_DIR"), name)).unwrap()
+}
+
+fn assert_near_white(output: &RgbaImage, what: &str) {
+    for (x, y, p) in output.enumerate_pixels() {
+        assert!(p[0] >= 250 && p[1] >= 250 && p[2] >= 250, "{}: ({}, {}) is {:?}", what, x, y, p);
+    }
+}
+
+#[test]
+fn white_paper_stays_white() {
+    let white = fixture("white");
+    for mode in [SketchMode::Pencil, SketchMode::ColoredPencil, SketchMode::LineArt] {
+        let settings = SketchSettings { mode: mode.clone(), ..SketchSettings::default() };
+        assert_near_white(&apply_sketch(white.clone(), &settings), mode.name());
+    }
+}
+
+#[test]
+fn flat_gray_has_nothing_to_draw() {
+    let gray = DynamicImage::ImageRgba8(RgbaImage::from_pixel(48, 32, Rgba([128, 128, 128, 255])));
+    // Coloured pencil keeps the source colour under the graphite, so only the others go white
+    for mode in [SketchMode::Pencil, SketchMode::LineArt] {
+        let settings = SketchSettings { mode: mode.clone(), ..SketchSettings::default() };
+        assert_near_white(&apply_sketch(gray.clone(), &settings), mode.name());
+    }
+}
@@ -0,0 +1,50 @@
+use pixforge::solarizeconverter::{build_lut, inversion_weight, SolarizeDirection, SolarizeSettings};
+
+fn settings(threshold: f32, direction: SolarizeDirection) -> SolarizeSettings {
+    SolarizeSettings { threshold, direction, ..SolarizeSettings::default() }
+}
+
+fn largest_step(lut: &[u8; 256]) -> u8 {
+    lut.windows(2).map(|w| w[0].abs_diff(w[1])).max().unwrap()
+}
+
+#[test]
+fn threshold_255_leaves_highlights_alone() {
+    let lut = build_lut(&settings(255.0, SolarizeDirection::Highlights));
+    for (i, &v) in lut.iter().enumerate() {
+        assert_eq!(v as usize, i);
+    }
+}
+
+#[test]
+fn threshold_0_inverts_every_highlight() {
+    let lut = build_lut(&settings(0.0, SolarizeDirection::Highlights));
+    for (i, &v) in lut.iter().enumerate() {
+        assert_eq!(v as usize, 255 - i);
+    }
+}
+
+#[test]
+fn shadows_mirror_the_thresholds() {
+    let none = build_lut(&settings(0.0, SolarizeDirection::Shadows));
+    let all = build_lut(&settings(255.0, SolarizeDirection::Shadows));
+    for i in 0..256 {
+        assert_eq!(none[i] as usize, i);
+        assert_eq!(all[i] as usize, 255 - i);
+    }
+}
+
+#[test]
+fn curve_is_continuous_across_the_threshold() {
+    for threshold in (0..=255i32).step_by(15) {
+        for direction in [SolarizeDirection::Highlights, SolarizeDirection::Shadows] {
+            let s = settings(threshold as f32, direction.clone());
+            let lut = build_lut(&s);
+            // A hard threshold would jump by |255 - 2 * threshold|; the ramp spreads that out
+            let hard_jump = (255 - 2 * threshold).unsigned_abs() as u8;
+            assert!(largest_step(&lut) <= (hard_jump / 8).max(2), "threshold {}: step of {}", threshold, largest_step(&lut));
+            let weights: Vec<f32> = (0..256).map(|v| inversion_weight(v as f32, s.threshold, s.rolloff, &direction)).collect();
+            assert!(weights.windows(2).all(|w| (w[1] - w[0]).abs() <= 1.5 / s.rolloff + 1e-4));
+        }
+    }
+}
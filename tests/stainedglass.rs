@@ -0,0 +1,52 @@
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use pixforge::stainedglassconverter::{apply_stained_glass, generate_sites, voronoi_partition, StainedGlassSettings};
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 48;
+const GLASS: u8 = 128;
+
+#[test]
+fn lead_follows_the_cell_boundaries() {
+    // Flat gray with no boost or backlight, so anything other than GLASS is lead
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(WIDTH, HEIGHT, Rgba([GLASS, GLASS, GLASS, 255])));
+    let settings = StainedGlassSettings { cell_count: 40, border_width: 1.0, saturation_boost: 0.0, backlight: false, seed: 7 };
+    let output = apply_stained_glass(image.clone(), &settings);
+    let sites = generate_sites(&image, settings.cell_count, settings.seed);
+    let cells = voronoi_partition(WIDTH, HEIGHT, &sites, 3.0);
+    let site = |x: u32, y: u32| cells[(y * WIDTH + x) as usize].site;
+    let lead = |x: u32, y: u32| output.get_pixel(x, y)[0];
+
+    let mut boundaries = 0;
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            // Wherever neighbouring ids differ, the line between them is at least half lead
+            for (nx, ny) in [(x + 1, y), (x, y + 1)] {
+                if nx < WIDTH && ny < HEIGHT && site(x, y) != site(nx, ny) {
+                    boundaries += 1;
+                    let darkest = lead(x, y).min(lead(nx, ny));
+                    assert!(darkest <= (GLASS + 28) / 2, "no lead between ({}, {}) and ({}, {})", x, y, nx, ny);
+                }
+            }
+            // And lead only appears within a pixel of a change of id. Edge pixels can see
+            // a boundary that runs just outside the image, so they are left out.
+            let edge = x == 0 || y == 0 || x == WIDTH - 1 || y == HEIGHT - 1;
+            if !edge && lead(x, y) != GLASS {
+                let near_boundary = (y - 1..=y + 1)
+                    .flat_map(|ny| (x - 1..=x + 1).map(move |nx| (nx, ny)))
+                    .any(|(nx, ny)| site(nx, ny) != site(x, y));
+                assert!(near_boundary, "stray lead at ({}, {})", x, y);
+            }
+        }
+    }
+    assert!(boundaries > 100);
+}
+
+#[test]
+fn same_seed_gives_the_same_panes() {
+    let image = DynamicImage::ImageRgba8(RgbaImage::from_fn(WIDTH, HEIGHT, |x, y| Rgba([x as u8 * 4, y as u8 * 5, 90, 255])));
+    let settings = StainedGlassSettings { cell_count: 40, seed: 3, ..StainedGlassSettings::default() };
+    assert_eq!(generate_sites(&image, 40, 3), generate_sites(&image, 40, 3));
+    assert_ne!(generate_sites(&image, 40, 3), generate_sites(&image, 40, 4));
+    assert_eq!(apply_stained_glass(image.clone(), &settings), apply_stained_glass(image, &settings));
+}
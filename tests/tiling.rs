@@ -0,0 +1,74 @@
+// The tiled paths must give exactly the untiled output for every band height,
+// including bands that don't divide the image and single-row bands.
+
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use pixforge::crtconverter::{apply_crt, apply_crt_tiled, CrtSettings};
+use pixforge::ditherconverter::{apply_dither, apply_dither_tiled, DitherAlgorithm, DitherSettings};
+use pixforge::fisheyeconverter::{apply_fisheye, apply_fisheye_tiled, FisheyeSettings};
+
+const BAND_HEIGHTS: [u32; 5] = [1, 7, 16, 41, 1000];
+
+fn test_image() -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(53, 41, |x, y| {
+        Rgba([(x * 255 / 52) as u8, (y * 255 / 40) as u8, ((x * 7 + y * 13) % 256) as u8, if (x + y) % 9 == 0 { 128 } else { 255 }])
+    }))
+}
+
+#[test]
+fn tiled_fisheye_matches_untiled() {
+    let image = test_image();
+    for settings in [
+        FisheyeSettings::default(),
+        FisheyeSettings { strength: -0.6, zoom: 1.2, ..Default::default() },
+        FisheyeSettings { strength: 0.8, center_x: 0.25, center_y: 0.7, ..Default::default() },
+    ] {
+        let expected = apply_fisheye(image.clone(), &settings, None);
+        for band_height in BAND_HEIGHTS {
+            let tiled = apply_fisheye_tiled(image.clone(), &settings, band_height, None);
+            assert!(tiled == expected, "fisheye differs with {}-row bands", band_height);
+        }
+    }
+}
+
+#[test]
+fn tiled_crt_matches_untiled() {
+    let image = test_image();
+    for settings in [
+        CrtSettings::default(),
+        CrtSettings { curvature: 0.0, bezel_size: 0.0, vignette_strength: 0.0, ..Default::default() },
+        CrtSettings { curvature: 0.3, bezel_size: 0.1, bg_opacity: 0, scanline_density: 3.0, ..Default::default() },
+    ] {
+        let expected = apply_crt(image.clone(), &settings, None);
+        for band_height in BAND_HEIGHTS {
+            let tiled = apply_crt_tiled(image.clone(), &settings, band_height, None);
+            assert!(tiled == expected, "CRT differs with {}-row bands", band_height);
+        }
+    }
+}
+
+#[test]
+fn tiled_dither_matches_untiled() {
+    let image = test_image();
+    let algorithms = [
+        DitherAlgorithm::Ordered,
+        DitherAlgorithm::Threshold,
+        DitherAlgorithm::Scanline,
+        DitherAlgorithm::Pattern,
+        DitherAlgorithm::Random,
+        DitherAlgorithm::Halftone,
+        DitherAlgorithm::HalftoneCmyk,
+        DitherAlgorithm::BlueNoise,
+        DitherAlgorithm::FloydSteinberg,
+    ];
+    for algorithm in algorithms {
+        for blur in [0.0, 1.0, 3.5] {
+            let settings = DitherSettings { algorithm: algorithm.clone(), blur, contrast: 1.3, ..Default::default() };
+            let expected = apply_dither(image.clone(), &settings, None);
+            for band_height in BAND_HEIGHTS {
+                let tiled = apply_dither_tiled(image.clone(), &settings, band_height, None);
+                assert!(tiled == expected, "{} with blur {} differs with {}-row bands", algorithm.name(), blur, band_height);
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use pixforge::tiltshiftconverter::{apply_tilt_shift, TiltShiftSettings};
+
+// Busy enough that any blur would change it
+fn checker(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+        if (x / 2 + y / 2) % 2 == 0 { Rgba([220, 60, 30, 255]) } else { Rgba([20, 90, 200, 255]) }
+    })
+}
+
+// Rows whose centres lie inside the focus band
+fn band_rows(settings: &TiltShiftSettings, height: u32) -> Vec<u32> {
+    let (top, bottom) = settings.band_edges();
+    (0..height)
+        .filter(|&y| {
+            let py = y as f32 + 0.5;
+            py >= top * height as f32 && py <= bottom * height as f32
+        })
+        .collect()
+}
+
+#[test]
+fn rows_inside_the_band_are_untouched() {
+    let input = checker(40, 100);
+    let settings = TiltShiftSettings::default();
+    assert!(settings.saturation > 0.0);
+    let output = apply_tilt_shift(DynamicImage::ImageRgba8(input.clone()), &settings);
+    let rows = band_rows(&settings, 100);
+    assert!(rows.len() >= 10);
+    for y in rows {
+        for x in 0..40 {
+            assert_eq!(output.get_pixel(x, y), input.get_pixel(x, y), "({}, {})", x, y);
+        }
+    }
+    // Far outside the band the checks are blurred together
+    assert_ne!(output.get_pixel(10, 2), input.get_pixel(10, 2));
+}
+
+#[test]
+fn saturation_boost_ramps_up_outside_the_band() {
+    // One flat colour, so with the blur off only the boost can change it
+    let input = RgbaImage::from_pixel(8, 100, Rgba([160, 120, 100, 255]));
+    let settings = TiltShiftSettings { max_blur: 0.0, ..TiltShiftSettings::default() };
+    let output = apply_tilt_shift(DynamicImage::ImageRgba8(input.clone()), &settings);
+    let spread = |y: u32| {
+        let p = output.get_pixel(0, y);
+        p[0] as i32 - p[2] as i32
+    };
+    let y = band_rows(&settings, 100)[0];
+    assert_eq!(output.get_pixel(0, y), input.get_pixel(0, y));
+    // Halfway through the transition and then at full strength
+    assert!(spread(y - 12) > 60 && spread(2) > spread(y - 12), "{} {}", spread(y - 12), spread(2));
+}
@@ -0,0 +1,38 @@
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use pixforge::whitebalanceconverter::{adaptation_matrix, apply_white_balance, source_white, WhiteBalanceSettings};
+
+const PICKED: [u8; 3] = [200, 180, 160];
+
+fn picked() -> WhiteBalanceSettings {
+    WhiteBalanceSettings { picked_white: Some(PICKED), ..WhiteBalanceSettings::default() }
+}
+
+#[test]
+fn picked_white_maps_to_gray() {
+    let white = source_white(&picked());
+    let m = adaptation_matrix(white);
+    let adapted: Vec<f32> = (0..3).map(|i| (0..3).map(|j| m[i][j] * white[j]).sum()).collect();
+    for c in 1..3 {
+        assert!((adapted[c] - adapted[0]).abs() < 1e-4, "{:?} is not neutral", adapted);
+    }
+}
+
+#[test]
+fn eyedropper_pixel_comes_out_neutral() {
+    let image = RgbaImage::from_fn(8, 8, |x, _| if x < 4 { Rgba([PICKED[0], PICKED[1], PICKED[2], 255]) } else { Rgba([90, 140, 60, 255]) });
+    let output = apply_white_balance(DynamicImage::ImageRgba8(image), &picked());
+    let p = output.get_pixel(0, 0);
+    let (min, max) = (p[0].min(p[1]).min(p[2]), p[0].max(p[1]).max(p[2]));
+    assert!(max - min <= 1, "{:?} is not neutral", p);
+    // Same luminance, so the gray sits between the picked channels
+    assert!(min > PICKED[2] && max < PICKED[0], "{:?}", p);
+    // Other colours are shifted, not flattened
+    assert_ne!(*output.get_pixel(7, 0), Rgba([90, 140, 60, 255]));
+}
+
+#[test]
+fn default_settings_are_a_no_op() {
+    let image = RgbaImage::from_fn(4, 4, |x, y| Rgba([x as u8 * 60, y as u8 * 60, 128, 200]));
+    assert_eq!(apply_white_balance(DynamicImage::ImageRgba8(image.clone()), &WhiteBalanceSettings::default()), image);
+}